@@ -0,0 +1,159 @@
+use crate::types::errors::{Error, ErrorKind};
+use serde_json::Value;
+use thiserror::Error;
+
+#[cfg(feature = "attest-ed25519")]
+pub mod ed25519;
+
+#[cfg(feature = "attest-ed25519")]
+pub use ed25519::{key_fingerprint, Ed25519Attestor};
+
+#[derive(Debug, Error)]
+pub enum AttestationError {
+    #[error("attestation signing failed: {msg}")]
+    Signing { msg: String },
+    #[error("attestation verification failed: {msg}")]
+    Verification { msg: String },
+    #[error("attestation key unusable: {msg}")]
+    Key { msg: String },
+}
+
+impl From<AttestationError> for Error {
+    fn from(e: AttestationError) -> Self {
+        Error {
+            kind: ErrorKind::Io, // Using Io for now, could be more specific
+            msg: e.to_string(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Signature(pub Vec<u8>);
+
+pub trait Attestor: Send + Sync {
+    /// Sign the given bundle.
+    /// # Errors
+    /// Returns an error if signing fails.
+    fn sign(&self, bundle: &[u8]) -> Result<Signature, AttestationError>;
+    /// Return a stable identifier for the public key used to sign (e.g., fingerprint or KID).
+    fn key_id(&self) -> String;
+    /// Return the signature algorithm label for attestations. Defaults to "ed25519".
+    fn algorithm(&self) -> &'static str {
+        "ed25519"
+    }
+}
+
+/// The canonical bytes signed for an `apply.result` summary.
+///
+/// Compact JSON (no whitespace) with keys in lexicographic order:
+/// `{"chain_head":"<hex>","executed":<n>,"plan_id":"<uuid>","rolled_back":<bool>}`.
+/// `chain_head` is present only when `policy.audit.hash_chain` is enabled; `executed` is the
+/// number of actions executed (`executed_count` in the fact).
+#[must_use]
+pub fn attestation_bundle(
+    plan_id: &str,
+    executed: usize,
+    rolled_back: bool,
+    chain_head: Option<&str>,
+) -> Vec<u8> {
+    #[derive(serde::Serialize)]
+    struct Bundle<'a> {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        chain_head: Option<&'a str>,
+        executed: usize,
+        plan_id: &'a str,
+        rolled_back: bool,
+    }
+    serde_json::to_vec(&Bundle {
+        chain_head,
+        executed,
+        plan_id,
+        rolled_back,
+    })
+    .unwrap_or_default()
+}
+
+/// Rebuild the signed bundle from an emitted `apply.result` summary fact.
+///
+/// Returns `None` when the fact lacks `plan_id`, `executed_count` or `rolled_back`.
+#[must_use]
+pub fn bundle_from_fact(fact: &Value) -> Option<Vec<u8>> {
+    let plan_id = fact.get("plan_id")?.as_str()?;
+    let executed = usize::try_from(fact.get("executed_count")?.as_u64()?).ok()?;
+    let rolled_back = fact.get("rolled_back")?.as_bool()?;
+    let chain_head = fact
+        .get("attestation")
+        .and_then(|a| a.get("chain_head"))
+        .and_then(Value::as_str);
+    Some(attestation_bundle(
+        plan_id,
+        executed,
+        rolled_back,
+        chain_head,
+    ))
+}
+
+/// Build a JSON object with attestation fields for emission given an attestor and a bundle.
+/// Returns None if signing fails.
+pub fn build_attestation_fields<A: Attestor + ?Sized>(att: &A, bundle: &[u8]) -> Option<Value> {
+    use base64::Engine as _;
+    let sig = att.sign(bundle).ok()?;
+    let sig_b64 = base64::engine::general_purpose::STANDARD.encode(sig.0.clone());
+    Some(serde_json::json!({
+        "sig_alg": att.algorithm(),
+        "signature": sig_b64,
+        "bundle_hash": sha256_hex(bundle),
+        "public_key_id": att.key_id(),
+    }))
+}
+
+/// Verify attestation `fields` (as built by [`build_attestation_fields`]) over `bundle` with a
+/// raw 32-byte Ed25519 `public_key`.
+///
+/// `fields` may also be a whole `apply.result` fact; its `attestation` object is used then.
+/// Checks `sig_alg`, `bundle_hash`, `public_key_id` (when present, against
+/// [`key_fingerprint`]) and the signature itself.
+///
+/// # Errors
+/// Returns `AttestationError::Key` for a malformed public key and
+/// `AttestationError::Verification` for any mismatch.
+#[cfg(feature = "attest-ed25519")]
+pub fn verify_attestation(
+    fields: &Value,
+    bundle: &[u8],
+    public_key: &[u8],
+) -> Result<(), AttestationError> {
+    use base64::Engine as _;
+    let fail = |msg: &str| AttestationError::Verification {
+        msg: msg.to_string(),
+    };
+    let fields = fields.get("attestation").unwrap_or(fields);
+    let key: &[u8; 32] = public_key.try_into().map_err(|_| AttestationError::Key {
+        msg: format!("expected 32-byte public key, got {}", public_key.len()),
+    })?;
+
+    if fields.get("sig_alg").and_then(Value::as_str) != Some("ed25519") {
+        return Err(fail("sig_alg is not ed25519"));
+    }
+    if fields.get("bundle_hash").and_then(Value::as_str) != Some(sha256_hex(bundle).as_str()) {
+        return Err(fail("bundle_hash does not match bundle"));
+    }
+    if let Some(id) = fields.get("public_key_id").and_then(Value::as_str) {
+        if id != key_fingerprint(key) {
+            return Err(fail("public_key_id does not match public key"));
+        }
+    }
+    let sig = fields
+        .get("signature")
+        .and_then(Value::as_str)
+        .ok_or_else(|| fail("signature missing"))?;
+    let sig = base64::engine::general_purpose::STANDARD
+        .decode(sig)
+        .map_err(|e| fail(&format!("signature is not base64: {e}")))?;
+    ed25519::verify(key, bundle, &sig)
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    use sha2::Digest as _;
+    hex::encode(sha2::Sha256::digest(bytes))
+}
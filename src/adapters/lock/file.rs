@@ -1,5 +1,5 @@
 use std::fs::{File, OpenOptions};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::thread;
 use std::time::{Duration, Instant};
 
@@ -7,7 +7,7 @@ use crate::constants::LOCK_POLL_MS;
 use crate::types::errors::{Error, ErrorKind, Result};
 use fs2::FileExt;
 
-use super::{LockGuard, LockManager};
+use super::{LockGuard, LockHolder, LockManager, LockMode};
 
 #[derive(Debug)]
 pub struct FileLockManager {
@@ -23,38 +23,94 @@ impl FileLockManager {
 
 struct FileGuard {
     file: File,
+    mode: LockMode,
 }
 
 impl Drop for FileGuard {
     fn drop(&mut self) {
+        // Clear holder metadata before releasing so readers never see a departed holder. Shared
+        // holders never wrote any and must not wipe it while others still hold the lock.
+        if self.mode == LockMode::Exclusive {
+            let _ = self.file.set_len(0);
+        }
         // Use trait-qualified call (via import) to avoid MSRV conflicts and satisfy clippy
         let _ = FileExt::unlock(&self.file);
     }
 }
 
-impl LockGuard for FileGuard {}
+impl LockGuard for FileGuard {
+    fn mode(&self) -> LockMode {
+        self.mode
+    }
+}
 
-impl LockManager for FileLockManager {
-    fn acquire_process_lock(&self, timeout_ms: u64) -> Result<Box<dyn LockGuard>> {
+impl FileLockManager {
+    /// Lock file used for `path`: a sibling of the process lock file named after a hash of the
+    /// path, so nothing is written into the locked tree itself.
+    #[must_use]
+    pub fn path_lock_file(&self, path: &Path) -> PathBuf {
+        use sha2::{Digest, Sha256};
+        use std::os::unix::ffi::OsStrExt as _;
+        let digest = Sha256::digest(path.as_os_str().as_bytes());
+        let name = self
+            .path
+            .file_name()
+            .map_or_else(|| "switchyard.lock".into(), |n| n.to_string_lossy());
+        self.path
+            .with_file_name(format!("{name}.{}", &hex::encode(digest)[..16]))
+    }
+
+    /// Lock `lock_path` in `mode` (`flock` `LOCK_SH`/`LOCK_EX`). Exclusive holders record
+    /// `holder` in the file; it is not truncated before the lock is held, so a contender can read
+    /// the current holder on timeout.
+    fn lock_file(
+        lock_path: &Path,
+        timeout_ms: u64,
+        mode: LockMode,
+        what: &str,
+        holder: &LockHolder,
+    ) -> Result<Box<dyn LockGuard>> {
         let t0 = Instant::now();
         let file = OpenOptions::new()
             .create(true)
             .read(true)
             .write(true)
-            .truncate(true)
-            .open(&self.path)
+            .truncate(false)
+            .open(lock_path)
             .map_err(|e| Error {
                 kind: ErrorKind::Io,
                 msg: e.to_string(),
             })?;
         loop {
-            match file.try_lock_exclusive() {
-                Ok(()) => return Ok(Box::new(FileGuard { file })),
+            // Trait-qualified: std's inherent `File` locking methods shadow fs2's names.
+            let res = match mode {
+                LockMode::Shared => FileExt::try_lock_shared(&file),
+                LockMode::Exclusive => FileExt::try_lock_exclusive(&file),
+            };
+            match res {
+                Ok(()) => {
+                    if mode == LockMode::Exclusive {
+                        // Best-effort: metadata is diagnostic only.
+                        let _ = file.set_len(0);
+                        let _ = std::os::unix::fs::FileExt::write_all_at(
+                            &file,
+                            format!("{}\n", holder.to_json()).as_bytes(),
+                            0,
+                        );
+                    }
+                    return Ok(Box::new(FileGuard { file, mode }));
+                }
                 Err(_e) => {
                     if t0.elapsed() >= Duration::from_millis(timeout_ms) {
+                        let held_by = Self::read_holder(lock_path)
+                            .map(|h| format!("; held by {}", h.describe()))
+                            .unwrap_or_default();
                         return Err(Error {
                             kind: ErrorKind::Policy,
-                            msg: "E_LOCKING: timeout acquiring process lock".to_string(),
+                            msg: format!(
+                                "E_LOCKING: timeout acquiring {} {what}{held_by}",
+                                mode.as_str()
+                            ),
                         });
                     }
                     thread::sleep(Duration::from_millis(LOCK_POLL_MS));
@@ -62,6 +118,53 @@ impl LockManager for FileLockManager {
             }
         }
     }
+
+    fn read_holder(lock_path: &Path) -> Option<LockHolder> {
+        LockHolder::parse(&std::fs::read_to_string(lock_path).ok()?)
+    }
+}
+
+impl LockManager for FileLockManager {
+    fn acquire_process_lock(&self, timeout_ms: u64) -> Result<Box<dyn LockGuard>> {
+        self.acquire_process_lock_as(
+            timeout_ms,
+            LockMode::Exclusive,
+            &LockHolder::current(None, None),
+        )
+    }
+
+    fn acquire_process_lock_as(
+        &self,
+        timeout_ms: u64,
+        mode: LockMode,
+        holder: &LockHolder,
+    ) -> Result<Box<dyn LockGuard>> {
+        Self::lock_file(&self.path, timeout_ms, mode, "process lock", holder)
+    }
+
+    fn supports_path_locks(&self) -> bool {
+        true
+    }
+
+    fn acquire_path_lock(
+        &self,
+        path: &Path,
+        timeout_ms: u64,
+        mode: LockMode,
+        holder: &LockHolder,
+    ) -> Result<Box<dyn LockGuard>> {
+        Self::lock_file(
+            &self.path_lock_file(path),
+            timeout_ms,
+            mode,
+            &format!("path lock for {}", path.display()),
+            holder,
+        )
+    }
+
+    fn holder(&self, path: Option<&Path>) -> Option<LockHolder> {
+        Self::read_holder(&path.map_or_else(|| self.path.clone(), |p| self.path_lock_file(p)))
+    }
 }
 
 #[cfg(test)]
@@ -98,4 +201,94 @@ mod tests {
         let g2 = mgr.acquire_process_lock(200).expect("lock after release");
         drop(g2);
     }
+
+    #[test]
+    fn path_locks_are_independent_per_path() {
+        let td = tempfile::tempdir().unwrap();
+        let mgr = FileLockManager::new(td.path().join("switchyard.lock"));
+        let me = LockHolder::current(None, None);
+        let a = Path::new("/opt/a");
+        let b = Path::new("/usr/bin");
+        assert_ne!(mgr.path_lock_file(a), mgr.path_lock_file(b));
+        let ga = mgr
+            .acquire_path_lock(a, 100, LockMode::Exclusive, &me)
+            .expect("lock a");
+        // Unrelated path and the process lock are not blocked by `a`; apply layers path scopes
+        // beneath a shared process lock itself.
+        let gb = mgr
+            .acquire_path_lock(b, 100, LockMode::Exclusive, &me)
+            .expect("lock b");
+        let gp = mgr.acquire_process_lock(100).expect("process lock");
+        // Same path from another manager instance times out.
+        let other = FileLockManager::new(td.path().join("switchyard.lock"));
+        assert!(other
+            .acquire_path_lock(a, 100, LockMode::Exclusive, &me)
+            .is_err());
+        drop((ga, gb, gp));
+        assert!(other
+            .acquire_path_lock(a, 100, LockMode::Exclusive, &me)
+            .is_ok());
+    }
+
+    #[test]
+    fn holder_metadata_is_recorded_and_reported_on_timeout() {
+        let td = tempfile::tempdir().unwrap();
+        let lock_path = td.path().join("switchyard.lock");
+        let mgr = FileLockManager::new(lock_path.clone());
+        let holder = LockHolder::current(Some("run-1"), Some("plan-1"));
+        let g = mgr
+            .acquire_process_lock_as(100, LockMode::Exclusive, &holder)
+            .unwrap();
+        let seen = mgr.holder(None).expect("holder recorded");
+        assert_eq!(seen, holder);
+        assert!(!seen.is_stale());
+
+        let err = FileLockManager::new(lock_path)
+            .acquire_process_lock(50)
+            .err()
+            .expect("timeout");
+        assert!(err.msg.contains("held by pid") && err.msg.contains("run_id run-1"));
+        drop(g);
+        assert!(mgr.holder(None).is_none(), "metadata cleared on release");
+    }
+
+    #[test]
+    fn shared_locks_coexist_and_exclude_writers() {
+        let td = tempfile::tempdir().unwrap();
+        let lock_path = td.path().join("switchyard.lock");
+        let me = LockHolder::current(None, None);
+        let r1 = FileLockManager::new(lock_path.clone())
+            .acquire_process_lock_as(100, LockMode::Shared, &me)
+            .unwrap();
+        let r2 = FileLockManager::new(lock_path.clone())
+            .acquire_process_lock_as(100, LockMode::Shared, &me)
+            .unwrap();
+        assert_eq!(r1.mode(), LockMode::Shared);
+        let writer = FileLockManager::new(lock_path.clone());
+        let err = writer
+            .acquire_process_lock(50)
+            .err()
+            .expect("readers block writer");
+        assert!(err.msg.contains("exclusive process lock"));
+        drop((r1, r2));
+        let w = writer
+            .acquire_process_lock(50)
+            .expect("writer after readers");
+        assert_eq!(w.mode(), LockMode::Exclusive);
+        assert!(FileLockManager::new(lock_path)
+            .acquire_process_lock_as(50, LockMode::Shared, &me)
+            .is_err());
+    }
+
+    #[test]
+    fn dead_holder_is_stale() {
+        let mut h = LockHolder::current(None, None);
+        h.pid = u32::MAX - 1;
+        assert!(h.is_stale());
+        h.hostname = Some("elsewhere.invalid".to_string());
+        assert!(!h.is_stale(), "remote holders are never judged stale");
+        let round = LockHolder::parse(&h.to_json().to_string()).unwrap();
+        assert_eq!(round, h);
+        assert!(LockHolder::parse("").is_none());
+    }
 }
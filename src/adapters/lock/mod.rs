@@ -1,11 +1,199 @@
 pub mod file;
-use crate::types::errors::Result;
+use std::fmt::Write as _;
+use std::path::Path;
 
-pub trait LockGuard: Send {}
+use serde_json::{json, Value};
+
+use crate::types::errors::{Error, ErrorKind, Result};
+
+/// Lock mode: readers (preflight) share a lock, mutators (apply) hold it exclusively.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LockMode {
+    Shared,
+    #[default]
+    Exclusive,
+}
+
+impl LockMode {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            LockMode::Shared => "shared",
+            LockMode::Exclusive => "exclusive",
+        }
+    }
+}
+
+pub trait LockGuard: Send {
+    /// Mode actually held. Backends without shared locks hold every lock exclusively.
+    fn mode(&self) -> LockMode {
+        LockMode::Exclusive
+    }
+}
+
+/// Identity of a lock holder, written into lock files so that a contender that times out can
+/// report who holds the lock and whether that holder is still alive.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct LockHolder {
+    pub pid: u32,
+    /// Process start time in clock ticks since boot (`/proc/<pid>/stat` field 22); guards
+    /// against pid reuse when checking liveness.
+    pub start_time: Option<u64>,
+    pub hostname: Option<String>,
+    pub run_id: Option<String>,
+    pub plan_id: Option<String>,
+    /// RFC3339 timestamp of acquisition.
+    pub acquired_at: String,
+}
+
+impl LockHolder {
+    /// Holder describing the current process.
+    #[must_use]
+    pub fn current(run_id: Option<&str>, plan_id: Option<&str>) -> Self {
+        let pid = std::process::id();
+        Self {
+            pid,
+            start_time: process_start_time(pid),
+            hostname: hostname(),
+            run_id: run_id.map(ToString::to_string),
+            plan_id: plan_id.map(ToString::to_string),
+            acquired_at: crate::logging::redact::now_iso(),
+        }
+    }
+
+    #[must_use]
+    pub fn to_json(&self) -> Value {
+        json!({
+            "pid": self.pid,
+            "start_time": self.start_time,
+            "hostname": self.hostname,
+            "run_id": self.run_id,
+            "plan_id": self.plan_id,
+            "acquired_at": self.acquired_at,
+        })
+    }
+
+    /// Parse holder metadata as written by [`to_json`](Self::to_json); `None` for empty or
+    /// foreign lock file contents.
+    #[must_use]
+    pub fn parse(s: &str) -> Option<Self> {
+        let v: Value = serde_json::from_str(s.trim()).ok()?;
+        let text = |k: &str| v.get(k).and_then(Value::as_str).map(ToString::to_string);
+        Some(Self {
+            pid: u32::try_from(v.get("pid")?.as_u64()?).ok()?,
+            start_time: v.get("start_time").and_then(Value::as_u64),
+            hostname: text("hostname"),
+            run_id: text("run_id"),
+            plan_id: text("plan_id"),
+            acquired_at: text("acquired_at").unwrap_or_default(),
+        })
+    }
+
+    /// Whether the holder is known to be gone: it ran on this host and its pid no longer exists
+    /// (or now belongs to a process with a different start time). Holders on other hosts are
+    /// never reported stale.
+    #[must_use]
+    pub fn is_stale(&self) -> bool {
+        if self.hostname.is_some() && self.hostname != hostname() {
+            return false;
+        }
+        match process_start_time(self.pid) {
+            None => !Path::new(&format!("/proc/{}", self.pid)).exists(),
+            Some(now) => self.start_time.is_some_and(|t| t != now),
+        }
+    }
+
+    /// One-line description for error messages.
+    #[must_use]
+    pub fn describe(&self) -> String {
+        let mut s = format!("pid {}", self.pid);
+        if let Some(h) = &self.hostname {
+            let _ = write!(s, " on {h}");
+        }
+        if let Some(r) = &self.run_id {
+            let _ = write!(s, ", run_id {r}");
+        }
+        if let Some(p) = &self.plan_id {
+            let _ = write!(s, ", plan_id {p}");
+        }
+        if !self.acquired_at.is_empty() {
+            let _ = write!(s, ", since {}", self.acquired_at);
+        }
+        if self.is_stale() {
+            s.push_str(" (stale: process no longer exists)");
+        }
+        s
+    }
+}
+
+pub(crate) fn hostname() -> Option<String> {
+    std::fs::read_to_string("/proc/sys/kernel/hostname")
+        .ok()
+        .map(|h| h.trim().to_string())
+        .filter(|h| !h.is_empty())
+        .or_else(|| std::env::var("HOSTNAME").ok())
+}
+
+fn process_start_time(pid: u32) -> Option<u64> {
+    let stat = std::fs::read_to_string(format!("/proc/{pid}/stat")).ok()?;
+    // Fields after the parenthesised comm; starttime is field 22 overall.
+    let rest = &stat[stat.rfind(')')? + 1..];
+    rest.split_whitespace().nth(19)?.parse().ok()
+}
 
 pub trait LockManager: Send + Sync {
     /// Acquire a process lock with the specified timeout.
     /// # Errors
     /// Returns an error if the lock cannot be acquired within the timeout period.
     fn acquire_process_lock(&self, timeout_ms: u64) -> Result<Box<dyn LockGuard>>;
+
+    /// Acquire the process lock in `mode` on behalf of `holder`, recording it where the backend
+    /// allows. Defaults to the exclusive [`acquire_process_lock`](Self::acquire_process_lock),
+    /// which also satisfies shared requests.
+    /// # Errors
+    /// Returns an error if the lock cannot be acquired within the timeout period.
+    fn acquire_process_lock_as(
+        &self,
+        timeout_ms: u64,
+        _mode: LockMode,
+        _holder: &LockHolder,
+    ) -> Result<Box<dyn LockGuard>> {
+        self.acquire_process_lock(timeout_ms)
+    }
+
+    /// Whether [`acquire_path_lock`](Self::acquire_path_lock) is implemented. When `false`,
+    /// per-path lock scopes fall back to the process lock.
+    fn supports_path_locks(&self) -> bool {
+        false
+    }
+
+    /// Acquire a lock on one canonical path in `mode` on behalf of `holder` with the specified
+    /// timeout.
+    ///
+    /// Callers acquire several path locks in canonical (sorted) order, so implementations only
+    /// need to serialize holders of the same path.
+    /// # Errors
+    /// Returns an error if the lock cannot be acquired within the timeout period, or when the
+    /// manager does not support path locks.
+    fn acquire_path_lock(
+        &self,
+        path: &Path,
+        _timeout_ms: u64,
+        _mode: LockMode,
+        _holder: &LockHolder,
+    ) -> Result<Box<dyn LockGuard>> {
+        Err(Error {
+            kind: ErrorKind::Policy,
+            msg: format!(
+                "path locks not supported by this lock manager: {}",
+                path.display()
+            ),
+        })
+    }
+
+    /// Current holder of the process lock (`None`) or of a path lock, when recorded.
+    fn holder(&self, _path: Option<&Path>) -> Option<LockHolder> {
+        None
+    }
 }
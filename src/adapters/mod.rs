@@ -1,13 +1,16 @@
-pub mod attest;
+pub mod attest; // contains mod.rs and ed25519.rs
 pub mod lock; // contains mod.rs and file.rs
-pub mod ownership; // contains mod.rs and fs.rs
+pub mod ownership; // contains mod.rs, fs.rs and package database oracles
 pub mod path;
-pub mod smoke;
+pub mod smoke; // contains mod.rs, command.rs and spec.rs
 
 pub use attest::*;
 pub use lock::file::FileLockManager;
 pub use lock::*;
+pub use ownership::dpkg::DpkgOwnershipOracle;
 pub use ownership::fs::FsOwnershipOracle;
+pub use ownership::pacman::PacmanOwnershipOracle;
+pub use ownership::rpm::RpmOwnershipOracle;
 pub use ownership::*;
 pub use path::*;
 pub use smoke::*;
@@ -0,0 +1,57 @@
+//! `OwnershipOracle` answering from dpkg's file lists (`/var/lib/dpkg/info/*.list`).
+
+use crate::adapters::OwnershipOracle;
+use crate::types::errors::Result;
+use crate::types::safepath::SafePath;
+use crate::types::OwnershipInfo;
+use std::path::Path;
+
+use super::pkgdb::{owner_info, IndexCache, PackageIndex};
+
+/// dpkg rewrites `status` on every operation and renames new `.list` files into `info/`.
+const DB_FILES: [&str; 2] = ["var/lib/dpkg/status", "var/lib/dpkg/info"];
+
+/// Reads `<root>/var/lib/dpkg/info/*.list`; `pkg` is the list's stem (e.g. `coreutils`,
+/// `libc6:amd64`). Paths not listed by any package fail with a policy error.
+#[derive(Debug, Default)]
+pub struct DpkgOwnershipOracle {
+    cache: IndexCache,
+}
+
+impl DpkgOwnershipOracle {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Drop cached indexes so the next lookup re-reads the database even if it looks unchanged.
+    pub fn refresh(&self) {
+        self.cache.clear();
+    }
+}
+
+fn build(root: &Path) -> std::io::Result<PackageIndex> {
+    let mut idx = PackageIndex::new(root);
+    for ent in std::fs::read_dir(root.join("var/lib/dpkg/info"))?.flatten() {
+        let name = ent.file_name();
+        let Some(pkg) = name.to_str().and_then(|n| n.strip_suffix(".list")) else {
+            continue;
+        };
+        let Ok(list) = std::fs::read_to_string(ent.path()) else {
+            continue;
+        };
+        for line in list.lines() {
+            if line.starts_with('/') && line != "/." {
+                idx.insert(line, pkg);
+            }
+        }
+    }
+    Ok(idx)
+}
+
+impl OwnershipOracle for DpkgOwnershipOracle {
+    fn owner_of(&self, path: &SafePath) -> Result<OwnershipInfo> {
+        let idx = self.cache.get_or_build(path.root(), &DB_FILES, build)?;
+        owner_info(&idx, "dpkg", path)
+    }
+}
@@ -1,4 +1,8 @@
+pub mod dpkg;
 pub mod fs;
+pub mod pacman;
+mod pkgdb;
+pub mod rpm;
 use crate::types::{errors::Result, safepath::SafePath, OwnershipInfo};
 
 pub trait OwnershipOracle: Send + Sync {
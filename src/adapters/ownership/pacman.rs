@@ -0,0 +1,69 @@
+//! `OwnershipOracle` answering from pacman's local database (`/var/lib/pacman/local/*/files`).
+
+use crate::adapters::OwnershipOracle;
+use crate::types::errors::Result;
+use crate::types::safepath::SafePath;
+use crate::types::OwnershipInfo;
+use std::path::Path;
+
+use super::pkgdb::{owner_info, IndexCache, PackageIndex};
+
+/// Every install, upgrade or removal adds or removes a `<pkg>-<ver>-<rel>` directory here.
+const DB_FILES: [&str; 1] = ["var/lib/pacman/local"];
+
+/// Reads `<root>/var/lib/pacman/local/<pkg>-<ver>-<rel>/{desc,files}`; `pkg` is `%NAME%` from
+/// `desc`. Directory entries are ignored since many packages share them.
+#[derive(Debug, Default)]
+pub struct PacmanOwnershipOracle {
+    cache: IndexCache,
+}
+
+impl PacmanOwnershipOracle {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Drop cached indexes so the next lookup re-reads the database even if it looks unchanged.
+    pub fn refresh(&self) {
+        self.cache.clear();
+    }
+}
+
+/// Lines of the `%SECTION%` block in a pacman database file.
+fn section<'a>(text: &'a str, name: &'a str) -> impl Iterator<Item = &'a str> {
+    text.lines()
+        .skip_while(move |l| *l != name)
+        .skip(1)
+        .take_while(|l| !l.is_empty() && !l.starts_with('%'))
+}
+
+fn build(root: &Path) -> std::io::Result<PackageIndex> {
+    let mut idx = PackageIndex::new(root);
+    for ent in std::fs::read_dir(root.join("var/lib/pacman/local"))?.flatten() {
+        let dir = ent.path();
+        let Ok(files) = std::fs::read_to_string(dir.join("files")) else {
+            continue;
+        };
+        let desc = std::fs::read_to_string(dir.join("desc")).unwrap_or_default();
+        // Fall back to stripping `-<ver>-<rel>` from the directory name.
+        let pkg = section(&desc, "%NAME%").next().map_or_else(
+            || {
+                let d = ent.file_name().to_string_lossy().into_owned();
+                d.rsplitn(3, '-').nth(2).unwrap_or(&d).to_string()
+            },
+            str::to_string,
+        );
+        for f in section(&files, "%FILES%").filter(|f| !f.ends_with('/')) {
+            idx.insert(f, &pkg);
+        }
+    }
+    Ok(idx)
+}
+
+impl OwnershipOracle for PacmanOwnershipOracle {
+    fn owner_of(&self, path: &SafePath) -> Result<OwnershipInfo> {
+        let idx = self.cache.get_or_build(path.root(), &DB_FILES, build)?;
+        owner_info(&idx, "pacman", path)
+    }
+}
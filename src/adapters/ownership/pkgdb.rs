@@ -0,0 +1,227 @@
+//! Shared plumbing for ownership oracles backed by a package manager's local database.
+//!
+//! Each backend turns its database (under the `SafePath` root) into a [`PackageIndex`] mapping
+//! absolute in-root paths to package names. Indexes are built per root on first lookup and
+//! cached together with the modification times of the backend's database files; a lookup after
+//! the database changed (a package was installed, upgraded or removed) rebuilds the index, so a
+//! long-lived oracle never answers from a stale database. `refresh()` on the oracle drops the
+//! cache unconditionally.
+//!
+//! Merged-`/usr` layouts are handled by normalizing top-level directory symlinks in the root
+//! (e.g. `/bin -> usr/bin`), so `/bin/ls` listed by the database matches a `/usr/bin/ls` target
+//! and vice versa. Link targets are resolved under the root, never against the host.
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+use crate::types::errors::{Error, ErrorKind, Result};
+use crate::types::safepath::SafePath;
+use crate::types::OwnershipInfo;
+
+/// Absolute in-root path → owning package.
+#[derive(Debug, Default)]
+pub(crate) struct PackageIndex {
+    owners: HashMap<String, String>,
+    /// `("/bin/", "/usr/bin/")` pairs for top-level directory symlinks in the root.
+    aliases: Vec<(String, String)>,
+}
+
+impl PackageIndex {
+    pub(crate) fn new(root: &Path) -> Self {
+        PackageIndex {
+            owners: HashMap::new(),
+            aliases: dir_aliases(root),
+        }
+    }
+
+    /// Record that `path` (absolute or root-relative) belongs to `pkg`. First owner wins.
+    pub(crate) fn insert(&mut self, path: &str, pkg: &str) {
+        let key = self.normalize(path);
+        self.owners.entry(key).or_insert_with(|| pkg.to_string());
+    }
+
+    pub(crate) fn owner(&self, rel: &Path) -> Option<&str> {
+        let key = self.normalize(&rel.to_string_lossy());
+        self.owners.get(&key).map(String::as_str)
+    }
+
+    fn normalize(&self, path: &str) -> String {
+        let mut p = format!("/{}", path.trim_start_matches('/'));
+        if p.len() > 1 {
+            while p.ends_with('/') {
+                p.pop();
+            }
+        }
+        for (alias, real) in &self.aliases {
+            if let Some(rest) = p.strip_prefix(alias.as_str()) {
+                return format!("{real}{rest}");
+            }
+        }
+        p
+    }
+}
+
+/// Top-level symlinks to directories inside the root, as `("/bin/", "/usr/bin/")`.
+fn dir_aliases(root: &Path) -> Vec<(String, String)> {
+    let Ok(rd) = std::fs::read_dir(root) else {
+        return Vec::new();
+    };
+    let mut out = Vec::new();
+    for ent in rd.flatten() {
+        if !ent.file_type().is_ok_and(|t| t.is_symlink()) {
+            continue;
+        }
+        let name = ent.file_name();
+        let Some(dest) = crate::fs::paths::resolve_in_root(root, Path::new(&name)) else {
+            continue;
+        };
+        let Ok(rel) = dest.strip_prefix(root) else {
+            continue;
+        };
+        if !dest.is_dir() || rel.as_os_str().is_empty() {
+            continue;
+        }
+        out.push((
+            format!("/{}/", name.to_string_lossy()),
+            format!("/{}/", rel.to_string_lossy()),
+        ));
+    }
+    out
+}
+
+/// Modification times of the database files a backend reads, relative to the root.
+type DbStamp = Vec<Option<SystemTime>>;
+
+fn db_stamp(root: &Path, files: &[&str]) -> DbStamp {
+    files
+        .iter()
+        .map(|f| {
+            std::fs::metadata(root.join(f))
+                .and_then(|m| m.modified())
+                .ok()
+        })
+        .collect()
+}
+
+/// Per-root cache of built indexes, invalidated when the database changes.
+#[derive(Debug, Default)]
+pub(crate) struct IndexCache {
+    by_root: Mutex<HashMap<PathBuf, (DbStamp, Arc<PackageIndex>)>>,
+}
+
+impl IndexCache {
+    /// The cached index for `root`, rebuilt when any of `db_files` (root-relative files or
+    /// directories the backend's database lives in) changed since it was built.
+    pub(crate) fn get_or_build(
+        &self,
+        root: &Path,
+        db_files: &[&str],
+        build: impl FnOnce(&Path) -> std::io::Result<PackageIndex>,
+    ) -> Result<Arc<PackageIndex>> {
+        let stamp = db_stamp(root, db_files);
+        let mut map = self
+            .by_root
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        if let Some((built, idx)) = map.get(root) {
+            if *built == stamp {
+                return Ok(Arc::clone(idx));
+            }
+        }
+        let idx = Arc::new(build(root).map_err(|e| Error {
+            kind: ErrorKind::Io,
+            msg: format!("package database: {e}"),
+        })?);
+        map.insert(root.to_path_buf(), (stamp, Arc::clone(&idx)));
+        Ok(idx)
+    }
+
+    pub(crate) fn clear(&self) {
+        self.by_root
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .clear();
+    }
+}
+
+/// Resolve `path` against `index`, filling uid/gid from the filesystem.
+///
+/// Unowned paths are a policy error so `risks.ownership_strict` stops on them.
+pub(crate) fn owner_info(
+    index: &PackageIndex,
+    manager: &str,
+    path: &SafePath,
+) -> Result<OwnershipInfo> {
+    use std::os::unix::fs::MetadataExt;
+    let Some(pkg) = index.owner(path.rel()) else {
+        return Err(Error {
+            kind: ErrorKind::Policy,
+            msg: format!(
+                "{} is not owned by any {manager} package",
+                path.as_path().display()
+            ),
+        });
+    };
+    let md = std::fs::symlink_metadata(path.as_path()).map_err(|e| Error {
+        kind: ErrorKind::Io,
+        msg: format!("metadata: {e}"),
+    })?;
+    Ok(OwnershipInfo {
+        uid: md.uid(),
+        gid: md.gid(),
+        pkg: pkg.to_string(),
+    })
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used, clippy::panic)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merged_usr_aliases_normalize_both_ways() {
+        let td = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(td.path().join("usr/bin")).unwrap();
+        std::os::unix::fs::symlink("usr/bin", td.path().join("bin")).unwrap();
+        let mut idx = PackageIndex::new(td.path());
+        idx.insert("/bin/ls", "coreutils");
+        idx.insert("usr/bin/cat/", "coreutils");
+        assert_eq!(idx.owner(Path::new("usr/bin/ls")), Some("coreutils"));
+        assert_eq!(idx.owner(Path::new("bin/cat")), Some("coreutils"));
+        assert_eq!(idx.owner(Path::new("usr/bin/dd")), None);
+    }
+
+    #[test]
+    fn absolute_alias_resolves_inside_root() {
+        let td = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(td.path().join("usr/sbin")).unwrap();
+        // On the host `/usr/bin` exists, but not in this root.
+        std::os::unix::fs::symlink("/usr/bin", td.path().join("bin")).unwrap();
+        std::os::unix::fs::symlink("/usr/sbin", td.path().join("sbin")).unwrap();
+        assert_eq!(
+            dir_aliases(td.path()),
+            vec![("/sbin/".to_string(), "/usr/sbin/".to_string())]
+        );
+    }
+
+    #[test]
+    fn cached_index_is_rebuilt_when_the_database_changes() {
+        let td = tempfile::tempdir().unwrap();
+        let db = td.path().join("db");
+        std::fs::write(&db, "a").unwrap();
+        let cache = IndexCache::default();
+        let builds = std::cell::Cell::new(0);
+        let build = |root: &Path| {
+            builds.set(builds.get() + 1);
+            Ok(PackageIndex::new(root))
+        };
+        cache.get_or_build(td.path(), &["db"], build).unwrap();
+        cache.get_or_build(td.path(), &["db"], build).unwrap();
+        assert_eq!(builds.get(), 1);
+        let f = std::fs::File::options().write(true).open(&db).unwrap();
+        f.set_modified(SystemTime::UNIX_EPOCH).unwrap();
+        cache.get_or_build(td.path(), &["db"], build).unwrap();
+        assert_eq!(builds.get(), 2);
+    }
+}
@@ -0,0 +1,98 @@
+//! `OwnershipOracle` answering from the rpm database via `rpm --root <root> -qa`.
+//!
+//! The rpm database is a Berkeley DB or `SQLite` file depending on the distribution, so rather
+//! than parsing it this oracle asks the `rpm` binary for every package's file list once per root.
+
+use crate::adapters::OwnershipOracle;
+use crate::types::errors::Result;
+use crate::types::safepath::SafePath;
+use crate::types::OwnershipInfo;
+use std::path::{Path, PathBuf};
+
+use super::pkgdb::{owner_info, IndexCache, PackageIndex};
+
+/// Database locations relative to the root, newest layout first.
+const RPMDB_DIRS: [&str; 2] = ["usr/lib/sysimage/rpm", "var/lib/rpm"];
+
+/// Database files rpm updates in place (`SQLite` and Berkeley DB layouts), plus the directories.
+const DB_FILES: [&str; 8] = [
+    "usr/lib/sysimage/rpm",
+    "usr/lib/sysimage/rpm/rpmdb.sqlite",
+    "usr/lib/sysimage/rpm/rpmdb.sqlite-wal",
+    "usr/lib/sysimage/rpm/Packages",
+    "var/lib/rpm",
+    "var/lib/rpm/rpmdb.sqlite",
+    "var/lib/rpm/rpmdb.sqlite-wal",
+    "var/lib/rpm/Packages",
+];
+
+#[derive(Debug)]
+pub struct RpmOwnershipOracle {
+    rpm: PathBuf,
+    cache: IndexCache,
+}
+
+impl Default for RpmOwnershipOracle {
+    fn default() -> Self {
+        Self {
+            rpm: PathBuf::from("rpm"),
+            cache: IndexCache::default(),
+        }
+    }
+}
+
+impl RpmOwnershipOracle {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Use a specific `rpm` executable instead of the one on `PATH`.
+    #[must_use]
+    pub fn with_rpm_binary(mut self, rpm: impl Into<PathBuf>) -> Self {
+        self.rpm = rpm.into();
+        self
+    }
+
+    /// Drop cached indexes so the next lookup re-queries the database even if it looks unchanged.
+    pub fn refresh(&self) {
+        self.cache.clear();
+    }
+
+    fn build(&self, root: &Path) -> std::io::Result<PackageIndex> {
+        if !RPMDB_DIRS.iter().any(|d| root.join(d).is_dir()) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "no rpm database under root",
+            ));
+        }
+        let out = std::process::Command::new(&self.rpm)
+            .arg("--root")
+            .arg(root)
+            .args(["-qa", "--queryformat", "[%{FILENAMES}\t%{NAME}\n]"])
+            .stderr(std::process::Stdio::null())
+            .output()?;
+        if !out.status.success() {
+            return Err(std::io::Error::other(format!(
+                "rpm exited with {}",
+                out.status
+            )));
+        }
+        let mut idx = PackageIndex::new(root);
+        for line in String::from_utf8_lossy(&out.stdout).lines() {
+            if let Some((file, pkg)) = line.split_once('\t') {
+                idx.insert(file, pkg);
+            }
+        }
+        Ok(idx)
+    }
+}
+
+impl OwnershipOracle for RpmOwnershipOracle {
+    fn owner_of(&self, path: &SafePath) -> Result<OwnershipInfo> {
+        let idx = self
+            .cache
+            .get_or_build(path.root(), &DB_FILES, |r| self.build(r))?;
+        owner_info(&idx, "rpm", path)
+    }
+}
@@ -0,0 +1,367 @@
+//! Command-based smoke runner implementing the SPEC §11 normative minimal suite.
+//!
+//! For every plan target whose file name is one of the suite's tools (`ls`, `cp`, `mv`, `rm`,
+//! `ln`, `stat`, `readlink`, `sha256sum`, `sort`, `date`), the runner executes that target with
+//! the normative arguments inside a fresh scratch directory and validates exit code and output.
+//! Targets that are not suite tools are only checked for link integrity (see
+//! [`DefaultSmokeRunner`]).
+//!
+//! Each command runs with `LC_ALL=C` and a per-command timeout; a command still running at the
+//! deadline is killed and counts as a failure.
+use std::os::unix::fs::{MetadataExt as _, PermissionsExt as _};
+use std::os::unix::process::CommandExt as _;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+use super::{outcome, DefaultSmokeRunner, SmokeCheck, SmokeFailure, SmokeTestRunner};
+use crate::types::plan::{Action, Plan};
+
+/// Default per-command timeout.
+pub const DEFAULT_SMOKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Upper bound on the stderr excerpt kept per check.
+const STDERR_EXCERPT_BYTES: usize = 512;
+
+/// Fixture payload written into each scratch directory.
+const FIXTURE: &[u8] = b"switchyard smoke fixture\n";
+
+/// One SPEC §11 command.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SmokeCommand {
+    Ls,
+    Cp,
+    Mv,
+    Rm,
+    Ln,
+    Stat,
+    Readlink,
+    Sha256sum,
+    Sort,
+    Date,
+}
+
+impl SmokeCommand {
+    pub const ALL: [SmokeCommand; 10] = [
+        SmokeCommand::Ls,
+        SmokeCommand::Cp,
+        SmokeCommand::Mv,
+        SmokeCommand::Rm,
+        SmokeCommand::Ln,
+        SmokeCommand::Stat,
+        SmokeCommand::Readlink,
+        SmokeCommand::Sha256sum,
+        SmokeCommand::Sort,
+        SmokeCommand::Date,
+    ];
+
+    /// Tool name, matched against target file names.
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            SmokeCommand::Ls => "ls",
+            SmokeCommand::Cp => "cp",
+            SmokeCommand::Mv => "mv",
+            SmokeCommand::Rm => "rm",
+            SmokeCommand::Ln => "ln",
+            SmokeCommand::Stat => "stat",
+            SmokeCommand::Readlink => "readlink",
+            SmokeCommand::Sha256sum => "sha256sum",
+            SmokeCommand::Sort => "sort",
+            SmokeCommand::Date => "date",
+        }
+    }
+
+    /// Suite command for a tool name.
+    #[must_use]
+    pub fn for_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|c| c.name() == name)
+    }
+
+    /// Normative arguments, relative to the scratch directory.
+    #[must_use]
+    pub const fn args(self) -> &'static [&'static str] {
+        match self {
+            SmokeCommand::Ls => &["-l", "a"],
+            SmokeCommand::Cp => &["--reflink=auto", "a", "b"],
+            SmokeCommand::Mv => &["-T", "a", "b"],
+            SmokeCommand::Rm => &["-f", "a"],
+            SmokeCommand::Ln => &["-sT", "a", "link"],
+            SmokeCommand::Stat => &["-c", "%a,%u,%g", "a"],
+            SmokeCommand::Readlink => &["-e", "link"],
+            SmokeCommand::Sha256sum => &["-c", "CHECKSUMS"],
+            SmokeCommand::Sort => &["-V", "versions"],
+            SmokeCommand::Date => &["+%s"],
+        }
+    }
+
+    /// Prepare the scratch directory beyond the common fixture file `a`.
+    fn setup(self, dir: &Path) -> std::io::Result<()> {
+        match self {
+            SmokeCommand::Readlink => std::os::unix::fs::symlink("a", dir.join("link")),
+            SmokeCommand::Sha256sum => {
+                let hex = crate::fs::meta::sha256_hex_of(&dir.join("a"))
+                    .ok_or_else(|| std::io::Error::other("cannot hash fixture"))?;
+                std::fs::write(dir.join("CHECKSUMS"), format!("{hex}  a\n"))
+            }
+            SmokeCommand::Sort => std::fs::write(dir.join("versions"), "1.10\n1.2\n1.9\n"),
+            _ => Ok(()),
+        }
+    }
+
+    /// Validate command effects and output (exit status is checked by the caller).
+    fn validate(self, dir: &Path, stdout: &str) -> Result<(), String> {
+        let read = |n: &str| std::fs::read(dir.join(n)).ok();
+        match self {
+            SmokeCommand::Ls => expect(stdout.contains('a'), "listing does not mention `a`"),
+            SmokeCommand::Cp => expect(
+                read("b").as_deref() == Some(FIXTURE) && read("a").is_some(),
+                "copy missing or differs",
+            ),
+            SmokeCommand::Mv => expect(
+                read("b").as_deref() == Some(FIXTURE) && !dir.join("a").exists(),
+                "rename did not move `a` to `b`",
+            ),
+            SmokeCommand::Rm => expect(
+                std::fs::symlink_metadata(dir.join("a")).is_err(),
+                "`a` still exists",
+            ),
+            SmokeCommand::Ln => expect(
+                std::fs::read_link(dir.join("link")).ok() == Some(PathBuf::from("a")),
+                "`link` is not a symlink to `a`",
+            ),
+            SmokeCommand::Stat => {
+                let md = std::fs::metadata(dir.join("a")).map_err(|e| e.to_string())?;
+                let want = format!("{:o},{},{}", md.mode() & 0o7777, md.uid(), md.gid());
+                expect(stdout.trim() == want, &format!("expected `{want}`"))
+            }
+            SmokeCommand::Readlink => {
+                let want = dir.join("a").canonicalize().map_err(|e| e.to_string())?;
+                expect(
+                    Path::new(stdout.trim_end_matches('\n')) == want,
+                    &format!("expected `{}`", want.display()),
+                )
+            }
+            SmokeCommand::Sha256sum => expect(stdout.contains("a: OK"), "checksum not OK"),
+            SmokeCommand::Sort => expect(stdout == "1.2\n1.9\n1.10\n", "wrong version order"),
+            SmokeCommand::Date => {
+                let now = std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map_or(0, |d| d.as_secs());
+                let got: u64 = stdout
+                    .trim()
+                    .parse()
+                    .map_err(|_| "not an epoch".to_string())?;
+                expect(
+                    got.abs_diff(now) <= 300,
+                    "clock differs by more than 5 minutes",
+                )
+            }
+        }
+    }
+}
+
+fn expect(ok: bool, why: &str) -> Result<(), String> {
+    if ok {
+        Ok(())
+    } else {
+        Err(why.to_string())
+    }
+}
+
+/// Output of one command run under a timeout.
+#[derive(Debug)]
+pub(super) struct Ran {
+    pub(super) status: Option<i32>,
+    pub(super) stdout: String,
+    pub(super) stderr: String,
+    pub(super) timed_out: bool,
+}
+
+/// Drain a child pipe on a helper thread so a chatty command cannot block on a full pipe.
+fn drain(pipe: Option<impl std::io::Read + Send + 'static>) -> std::thread::JoinHandle<String> {
+    std::thread::spawn(move || {
+        let mut buf = Vec::new();
+        if let Some(mut p) = pipe {
+            let _ = p.read_to_end(&mut buf);
+        }
+        String::from_utf8_lossy(&buf).into_owned()
+    })
+}
+
+/// Run `exe args` in `cwd`, killing it after `timeout`.
+pub(super) fn run_with_timeout(
+    exe: &Path,
+    args: &[&str],
+    cwd: &Path,
+    timeout: Duration,
+) -> std::io::Result<Ran> {
+    let mut child = Command::new(exe)
+        .args(args)
+        .current_dir(cwd)
+        .env("LC_ALL", "C")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .process_group(0)
+        .spawn()?;
+    let out = drain(child.stdout.take());
+    let err = drain(child.stderr.take());
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        if let Some(st) = child.try_wait()? {
+            break Some(st);
+        }
+        if Instant::now() >= deadline {
+            // Kill the whole group so grandchildren do not keep the pipes open.
+            if let Some(pgid) = rustix::process::Pid::from_raw(child.id().cast_signed()) {
+                let _ = rustix::process::kill_process_group(pgid, rustix::process::Signal::Kill);
+            }
+            let _ = child.kill();
+            let _ = child.wait();
+            break None;
+        }
+        std::thread::sleep(Duration::from_millis(5));
+    };
+    Ok(Ran {
+        status: status.and_then(|s| s.code()),
+        stdout: out.join().unwrap_or_default(),
+        stderr: err.join().unwrap_or_default(),
+        timed_out: status.is_none(),
+    })
+}
+
+/// Redacted tail of captured stderr, at most [`STDERR_EXCERPT_BYTES`] long.
+pub(super) fn stderr_excerpt(stderr: &str) -> Option<String> {
+    let trimmed = stderr.trim_end();
+    if trimmed.is_empty() {
+        return None;
+    }
+    let mut start = trimmed.len().saturating_sub(STDERR_EXCERPT_BYTES);
+    while !trimmed.is_char_boundary(start) {
+        start += 1;
+    }
+    Some(crate::logging::redact_text(&trimmed[start..]))
+}
+
+/// Runs the SPEC §11 command suite against plan targets that are suite tools.
+#[derive(Debug, Clone)]
+pub struct CommandSmokeRunner {
+    timeout: Duration,
+    scratch_parent: Option<PathBuf>,
+}
+
+impl Default for CommandSmokeRunner {
+    fn default() -> Self {
+        Self {
+            timeout: DEFAULT_SMOKE_TIMEOUT,
+            scratch_parent: None,
+        }
+    }
+}
+
+impl CommandSmokeRunner {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Per-command timeout (default [`DEFAULT_SMOKE_TIMEOUT`]).
+    #[must_use]
+    pub const fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Directory under which scratch directories are created (default: the system temp dir).
+    #[must_use]
+    pub fn with_scratch_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.scratch_parent = Some(dir.into());
+        self
+    }
+
+    /// Suite commands selected for `plan`, with the target path to execute.
+    #[must_use]
+    pub fn selected(plan: &Plan) -> Vec<(SmokeCommand, PathBuf)> {
+        plan.actions
+            .iter()
+            .filter_map(|act| {
+                let target = match act {
+                    Action::EnsureSymlink { target, .. } | Action::RestoreFromBackup { target } => {
+                        target.as_path()
+                    }
+                };
+                let cmd = SmokeCommand::for_name(target.file_name()?.to_str()?)?;
+                Some((cmd, target))
+            })
+            .collect()
+    }
+
+    /// Run one command against `exe` in a fresh scratch directory.
+    fn check(&self, cmd: SmokeCommand, exe: &Path) -> SmokeCheck {
+        let t0 = Instant::now();
+        let mut check = SmokeCheck {
+            name: cmd.name().to_string(),
+            target: exe.display().to_string(),
+            command: Some(
+                std::iter::once(exe.display().to_string())
+                    .chain(cmd.args().iter().map(ToString::to_string))
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            ),
+            ..SmokeCheck::default()
+        };
+        let parent = self
+            .scratch_parent
+            .clone()
+            .unwrap_or_else(std::env::temp_dir);
+        let res = (|| {
+            // Created exclusively (never an existing directory or symlink) with a random name and
+            // mode 0700: commands run as root and must not share a path another user prepared.
+            let scratch = tempfile::Builder::new()
+                .prefix(&format!(".switchyard-smoke.{}.", cmd.name()))
+                .permissions(std::fs::Permissions::from_mode(0o700))
+                .tempdir_in(&parent)
+                .map_err(|e| format!("scratch: {e}"))?;
+            let dir = scratch.path();
+            std::fs::write(dir.join("a"), FIXTURE).map_err(|e| format!("fixture: {e}"))?;
+            cmd.setup(dir).map_err(|e| format!("fixture: {e}"))?;
+            let ran = run_with_timeout(exe, cmd.args(), dir, self.timeout)
+                .map_err(|e| format!("spawn: {e}"))?;
+            check.exit_status = ran.status;
+            check.stderr = stderr_excerpt(&ran.stderr);
+            if ran.timed_out {
+                return Err(format!("timed out after {:?}", self.timeout));
+            }
+            if ran.status != Some(0) {
+                return Err(format!("exit status {:?}", ran.status));
+            }
+            cmd.validate(dir, &ran.stdout)
+        })();
+        check.duration_ms = u64::try_from(t0.elapsed().as_millis()).unwrap_or(u64::MAX);
+        check.ok = res.is_ok();
+        check.error = res.err();
+        check
+    }
+}
+
+impl SmokeTestRunner for CommandSmokeRunner {
+    fn run(&self, plan: &Plan) -> Result<(), SmokeFailure> {
+        self.run_checks(plan).map(drop)
+    }
+
+    fn run_checks(&self, plan: &Plan) -> Result<Vec<SmokeCheck>, SmokeFailure> {
+        let mut checks = match DefaultSmokeRunner.run_checks(plan) {
+            Ok(c) => c,
+            Err(f) => f.checks,
+        };
+        for (cmd, exe) in Self::selected(plan) {
+            let check = self.check(cmd, &exe);
+            if let Some(why) = &check.error {
+                log::warn!("smoke: {} ({}) failed: {why}", cmd.name(), exe.display());
+            }
+            checks.push(check);
+        }
+        outcome(checks)
+    }
+}
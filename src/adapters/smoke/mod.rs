@@ -0,0 +1,130 @@
+//! Smoke test adapter used to validate post-apply health.
+//!
+//! Minimal expectations for integrators:
+//! - Implement `SmokeTestRunner` and inject it via `Switchyard::with_smoke_runner(...)`.
+//! - Ensure the runner is deterministic and safe to execute repeatedly.
+//! - At minimum, validate that each `EnsureSymlink` target resolves to the intended source.
+//! - Optionally perform additional invariant checks (e.g., permissions, executable bits).
+//!
+//! Behavior in `ApplyMode::Commit` when `policy.require_smoke_in_commit=true`:
+//! - If no runner is configured, apply fails with `E_SMOKE` and auto-rollback (unless `disable_auto_rollback`).
+//! - If the runner returns `Err(SmokeFailure)`, apply fails with `E_SMOKE` and auto-rollback (unless disabled).
+//!
+//! Per-check results ([`SmokeCheck`]) are emitted in a `smoke.result` fact and returned in
+//! `ApplyReport::smoke_checks`. Captured stderr is bounded and passed through
+//! [`redact_text`](crate::logging::redact_text) before it leaves the runner.
+//!
+use std::path::Path;
+use std::time::Instant;
+
+use crate::types::plan::Plan;
+pub use crate::types::report::SmokeCheck;
+
+pub mod command;
+pub mod spec;
+pub use command::{CommandSmokeRunner, SmokeCommand};
+pub use spec::{SmokeSpec, SmokeSpecCheck, SmokeSpecError, SpecSmokeRunner};
+
+/// Smoke failure with the per-check results that led to it (passing checks included).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SmokeFailure {
+    pub checks: Vec<SmokeCheck>,
+}
+
+impl SmokeFailure {
+    #[must_use]
+    pub const fn new(checks: Vec<SmokeCheck>) -> Self {
+        Self { checks }
+    }
+
+    /// Checks that did not pass.
+    pub fn failed(&self) -> impl Iterator<Item = &SmokeCheck> {
+        self.checks.iter().filter(|c| !c.ok)
+    }
+}
+
+/// `Ok` when every check passed, otherwise a [`SmokeFailure`] carrying all of them.
+fn outcome(checks: Vec<SmokeCheck>) -> Result<Vec<SmokeCheck>, SmokeFailure> {
+    if checks.iter().all(|c| c.ok) {
+        Ok(checks)
+    } else {
+        Err(SmokeFailure::new(checks))
+    }
+}
+
+pub trait SmokeTestRunner: Send + Sync {
+    /// Run smoke tests for the given plan.
+    /// # Errors
+    /// Returns `SmokeFailure` if smoke tests fail.
+    fn run(&self, plan: &Plan) -> Result<(), SmokeFailure>;
+
+    /// Run smoke tests and return per-check results on success as well.
+    ///
+    /// Apply calls this method; the default delegates to [`run`](Self::run) and reports no checks
+    /// on success. Runners that record checks should override it and implement `run` on top.
+    /// # Errors
+    /// Returns `SmokeFailure` if smoke tests fail.
+    fn run_checks(&self, plan: &Plan) -> Result<Vec<SmokeCheck>, SmokeFailure> {
+        self.run(plan).map(|()| Vec::new())
+    }
+}
+
+/// `DefaultSmokeRunner` implements a minimal smoke suite: link integrity only.
+/// See [`CommandSmokeRunner`] for the SPEC §11 command set and [`SpecSmokeRunner`] for
+/// declarative per-binary probes.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct DefaultSmokeRunner;
+
+impl DefaultSmokeRunner {
+    /// Check that `target` is a symlink resolving to `source`.
+    fn link_error(source: &Path, target: &Path) -> Option<String> {
+        let Ok(md) = std::fs::symlink_metadata(target) else {
+            return Some("target missing".to_string());
+        };
+        if !md.file_type().is_symlink() {
+            return Some("target is not a symlink".to_string());
+        }
+        let Ok(link) = std::fs::read_link(target) else {
+            return Some("target link unreadable".to_string());
+        };
+        // Resolve relative link against target parent
+        let resolved = if link.is_relative() {
+            match target.parent() {
+                Some(parent) => parent.join(link),
+                None => link,
+            }
+        } else {
+            link
+        };
+        // Compare canonicalized paths where possible
+        let want = std::fs::canonicalize(source).unwrap_or_else(|_| source.to_path_buf());
+        let got = std::fs::canonicalize(&resolved).unwrap_or(resolved);
+        (want != got).then(|| format!("target resolves to {}", got.display()))
+    }
+}
+
+impl SmokeTestRunner for DefaultSmokeRunner {
+    fn run(&self, plan: &Plan) -> Result<(), SmokeFailure> {
+        self.run_checks(plan).map(drop)
+    }
+
+    fn run_checks(&self, plan: &Plan) -> Result<Vec<SmokeCheck>, SmokeFailure> {
+        // Deterministic subset: validate that each EnsureSymlink target points to the source.
+        let mut checks = Vec::new();
+        for act in &plan.actions {
+            if let crate::types::Action::EnsureSymlink { source, target } = act {
+                let t0 = Instant::now();
+                let error = Self::link_error(&source.as_path(), &target.as_path());
+                checks.push(SmokeCheck {
+                    name: "link".to_string(),
+                    target: target.as_path().display().to_string(),
+                    duration_ms: u64::try_from(t0.elapsed().as_millis()).unwrap_or(u64::MAX),
+                    ok: error.is_none(),
+                    error,
+                    ..SmokeCheck::default()
+                });
+            }
+        }
+        outcome(checks)
+    }
+}
@@ -0,0 +1,294 @@
+//! Declarative smoke checks loaded from a YAML or TOML specification.
+//!
+//! Each check is keyed by a target path or glob and names the command to run, its arguments, the
+//! expected exit code, an optional stdout regex and a timeout. Checks are selected for the plan's
+//! targets (matched against both the absolute path and the root-relative path, so `/usr/bin/ls`
+//! matches a target under any `SafePath` root). `{target}` in `command` or `args` expands to the
+//! matched target path; without `command` the target itself is executed.
+//!
+//! ```yaml
+//! checks:
+//!   - target: /usr/bin/ls
+//!     args: ["--version"]
+//!     stdout_regex: "uutils"
+//!   - target: "/usr/bin/sudo*"
+//!     name: sudo-version
+//!     args: ["-V"]
+//!     timeout_ms: 2000
+//! ```
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use serde::Deserialize;
+use thiserror::Error;
+
+use super::command::{run_with_timeout, stderr_excerpt, DEFAULT_SMOKE_TIMEOUT};
+use super::{outcome, DefaultSmokeRunner, SmokeCheck, SmokeFailure, SmokeTestRunner};
+use crate::types::errors::{Error, ErrorKind};
+use crate::types::plan::{Action, Plan};
+
+#[derive(Debug, Error)]
+pub enum SmokeSpecError {
+    #[error("cannot read smoke spec {path}: {msg}")]
+    Read { path: String, msg: String },
+    #[error("invalid smoke spec: {msg}")]
+    Parse { msg: String },
+    #[error("invalid target pattern `{pattern}`: {msg}")]
+    Pattern { pattern: String, msg: String },
+    #[error("invalid stdout_regex `{regex}`: {msg}")]
+    Regex { regex: String, msg: String },
+}
+
+impl From<SmokeSpecError> for Error {
+    fn from(e: SmokeSpecError) -> Self {
+        Error {
+            kind: ErrorKind::Policy,
+            msg: e.to_string(),
+        }
+    }
+}
+
+/// Top-level smoke specification document.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct SmokeSpec {
+    #[serde(default)]
+    pub checks: Vec<SmokeSpecCheck>,
+}
+
+/// One declarative check.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct SmokeSpecCheck {
+    /// Target path or glob (`*`, `?`, `[...]`, `**`).
+    pub target: String,
+    /// Check name reported in results; defaults to the target pattern.
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Program to run; defaults to the matched target.
+    #[serde(default)]
+    pub command: Option<String>,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub expect_exit: i32,
+    /// Regex that stdout must match (unanchored).
+    #[serde(default)]
+    pub stdout_regex: Option<String>,
+    /// Per-check timeout; defaults to the runner's timeout.
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+}
+
+impl SmokeSpec {
+    /// Parse a YAML document.
+    ///
+    /// # Errors
+    /// Returns `SmokeSpecError::Parse` on malformed input.
+    pub fn from_yaml_str(s: &str) -> Result<Self, SmokeSpecError> {
+        serde_yaml::from_str(s).map_err(|e| SmokeSpecError::Parse { msg: e.to_string() })
+    }
+
+    /// Parse a TOML document (`[[checks]]` tables).
+    ///
+    /// # Errors
+    /// Returns `SmokeSpecError::Parse` on malformed input.
+    pub fn from_toml_str(s: &str) -> Result<Self, SmokeSpecError> {
+        toml::from_str(s).map_err(|e| SmokeSpecError::Parse { msg: e.to_string() })
+    }
+
+    /// Load a spec file; `.toml` files are parsed as TOML, anything else as YAML.
+    ///
+    /// # Errors
+    /// Returns `SmokeSpecError` when the file cannot be read or parsed.
+    pub fn load(path: &Path) -> Result<Self, SmokeSpecError> {
+        let s = std::fs::read_to_string(path).map_err(|e| SmokeSpecError::Read {
+            path: path.display().to_string(),
+            msg: e.to_string(),
+        })?;
+        if path.extension().is_some_and(|e| e == "toml") {
+            Self::from_toml_str(&s)
+        } else {
+            Self::from_yaml_str(&s)
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Compiled {
+    check: SmokeSpecCheck,
+    pattern: glob::Pattern,
+    regex: Option<regex::Regex>,
+}
+
+impl Compiled {
+    fn matches(&self, target: &crate::types::SafePath) -> bool {
+        let opts = glob::MatchOptions {
+            require_literal_separator: true,
+            ..glob::MatchOptions::new()
+        };
+        self.pattern.matches_path_with(&target.as_path(), opts)
+            || self
+                .pattern
+                .matches_path_with(&Path::new("/").join(target.rel()), opts)
+    }
+}
+
+/// Runs declarative checks from a [`SmokeSpec`] against the plan's targets.
+#[derive(Debug)]
+pub struct SpecSmokeRunner {
+    checks: Vec<Compiled>,
+    timeout: Duration,
+}
+
+impl SpecSmokeRunner {
+    /// Compile target globs and stdout regexes.
+    ///
+    /// # Errors
+    /// Returns `SmokeSpecError` for an invalid glob or regex.
+    pub fn new(spec: SmokeSpec) -> Result<Self, SmokeSpecError> {
+        let checks = spec
+            .checks
+            .into_iter()
+            .map(|check| {
+                let pattern =
+                    glob::Pattern::new(&check.target).map_err(|e| SmokeSpecError::Pattern {
+                        pattern: check.target.clone(),
+                        msg: e.to_string(),
+                    })?;
+                let regex = check
+                    .stdout_regex
+                    .as_deref()
+                    .map(|r| {
+                        regex::Regex::new(r).map_err(|e| SmokeSpecError::Regex {
+                            regex: r.to_string(),
+                            msg: e.to_string(),
+                        })
+                    })
+                    .transpose()?;
+                Ok(Compiled {
+                    check,
+                    pattern,
+                    regex,
+                })
+            })
+            .collect::<Result<Vec<_>, SmokeSpecError>>()?;
+        Ok(Self {
+            checks,
+            timeout: DEFAULT_SMOKE_TIMEOUT,
+        })
+    }
+
+    /// Load and compile a spec file (see [`SmokeSpec::load`]).
+    ///
+    /// # Errors
+    /// Returns `SmokeSpecError` when the file cannot be read, parsed or compiled.
+    pub fn load(path: &Path) -> Result<Self, SmokeSpecError> {
+        Self::new(SmokeSpec::load(path)?)
+    }
+
+    /// Timeout for checks without `timeout_ms` (default [`DEFAULT_SMOKE_TIMEOUT`]).
+    #[must_use]
+    pub const fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// `(check index, target path)` pairs selected for `plan`, in plan order.
+    fn selected(&self, plan: &Plan) -> Vec<(usize, PathBuf)> {
+        let mut out = Vec::new();
+        for act in &plan.actions {
+            let target = match act {
+                Action::EnsureSymlink { target, .. } | Action::RestoreFromBackup { target } => {
+                    target
+                }
+            };
+            for (i, c) in self.checks.iter().enumerate() {
+                let path = target.as_path();
+                if c.matches(target) && !out.iter().any(|(j, p)| *j == i && *p == path) {
+                    out.push((i, path));
+                }
+            }
+        }
+        out
+    }
+
+    fn check(&self, c: &Compiled, target: &Path) -> SmokeCheck {
+        let t0 = Instant::now();
+        let subst = |s: &str| s.replace("{target}", &target.display().to_string());
+        let exe = c
+            .check
+            .command
+            .as_deref()
+            .map_or_else(|| target.to_path_buf(), |cmd| PathBuf::from(subst(cmd)));
+        let args: Vec<String> = c.check.args.iter().map(|a| subst(a)).collect();
+        let timeout = c
+            .check
+            .timeout_ms
+            .map_or(self.timeout, Duration::from_millis);
+        let mut check = SmokeCheck {
+            name: c
+                .check
+                .name
+                .clone()
+                .unwrap_or_else(|| c.check.target.clone()),
+            target: target.display().to_string(),
+            command: Some(
+                std::iter::once(exe.display().to_string())
+                    .chain(args.iter().cloned())
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            ),
+            ..SmokeCheck::default()
+        };
+        let arg_refs: Vec<&str> = args.iter().map(String::as_str).collect();
+        let res = run_with_timeout(&exe, &arg_refs, Path::new("/"), timeout)
+            .map_err(|e| format!("spawn: {e}"))
+            .and_then(|ran| {
+                check.exit_status = ran.status;
+                check.stderr = stderr_excerpt(&ran.stderr);
+                if ran.timed_out {
+                    return Err(format!("timed out after {timeout:?}"));
+                }
+                if ran.status != Some(c.check.expect_exit) {
+                    return Err(format!(
+                        "exit status {:?}, expected {}",
+                        ran.status, c.check.expect_exit
+                    ));
+                }
+                match &c.regex {
+                    Some(re) if !re.is_match(&ran.stdout) => {
+                        Err(format!("stdout does not match `{}`", re.as_str()))
+                    }
+                    _ => Ok(()),
+                }
+            });
+        check.duration_ms = u64::try_from(t0.elapsed().as_millis()).unwrap_or(u64::MAX);
+        check.ok = res.is_ok();
+        check.error = res.err();
+        check
+    }
+}
+
+impl SmokeTestRunner for SpecSmokeRunner {
+    fn run(&self, plan: &Plan) -> Result<(), SmokeFailure> {
+        self.run_checks(plan).map(drop)
+    }
+
+    fn run_checks(&self, plan: &Plan) -> Result<Vec<SmokeCheck>, SmokeFailure> {
+        let mut checks = match DefaultSmokeRunner.run_checks(plan) {
+            Ok(c) => c,
+            Err(f) => f.checks,
+        };
+        for (i, target) in self.selected(plan) {
+            if let Some(c) = self.checks.get(i) {
+                let check = self.check(c, &target);
+                if let Some(why) = &check.error {
+                    log::warn!("smoke: {} ({}) failed: {why}", check.name, target.display());
+                }
+                checks.push(check);
+            }
+        }
+        outcome(checks)
+    }
+}
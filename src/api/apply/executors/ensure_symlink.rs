@@ -44,21 +44,21 @@ impl<E: FactsEmitter, A: AuditSink> ActionExecutor<E, A> for EnsureSymlinkExec {
 
         let aid = action_id(pid, act, idx);
         // Attempt fact
-        {
-            let slog = StageLogger::new(tctx);
-            slog.apply_attempt()
-                .merge(&json!({
-                    "action_id": aid.to_string(),
-                    "path": target.as_path().display().to_string(),
-                    "safepath_validation": "success",
-                    "backup_durable": api.policy.durability.backup_durability,
-                }))
-                .emit_success();
+        let attempt = json!({
+            "action_id": aid.to_string(),
+            "path": target.as_path().display().to_string(),
+            "safepath_validation": "success",
+            "backup_durable": api.policy.durability.backup_durability,
+        });
+        if let Err(msg) = super::emit_attempt(api, tctx, &attempt, dry) {
+            return (None, Some(msg), PerfAgg::default());
         }
 
         let degraded_used: bool;
         let mut fsync_ms: u64 = 0;
         let before_kind = kind_of(&target.as_path());
+        // Filesystem the target lives on, so audits show which guarantees actually held
+        let fs_type = crate::fs::fstype::fs_type_at(&target.as_path()).map(|t| t.name);
         // Compute before/after hashes (time the operation)
         let th0 = Instant::now();
         let before_hash = match resolve_symlink_target(&target.as_path()) {
@@ -76,6 +76,7 @@ impl<E: FactsEmitter, A: AuditSink> ActionExecutor<E, A> for EnsureSymlinkExec {
                 crate::policy::types::ExdevPolicy::DegradedFallback
             ),
             &api.policy.backup.tag,
+            api.policy.preservation_tier,
             api.overrides().force_exdev,
         ) {
             Ok((d, ms)) => {
@@ -103,6 +104,7 @@ impl<E: FactsEmitter, A: AuditSink> ActionExecutor<E, A> for EnsureSymlinkExec {
                     "fsync_ms": fsync_ms,
                     "lock_wait_ms": 0u64,
                     "before_kind": before_kind,
+                    "fs_type": fs_type,
                     "after_kind": if dry { "symlink".to_string() } else { kind_of(&target.as_path()).to_string() },
                 });
                 // Attach ownership provenance best-effort
@@ -150,6 +152,7 @@ impl<E: FactsEmitter, A: AuditSink> ActionExecutor<E, A> for EnsureSymlinkExec {
             "fsync_ms": fsync_ms,
             "lock_wait_ms": 0u64,
             "before_kind": before_kind,
+            "fs_type": fs_type,
             "after_kind": if dry { "symlink".to_string() } else { kind_of(&target.as_path()).to_string() },
             "backup_durable": api.policy.durability.backup_durability,
         });
@@ -1,4 +1,9 @@
-use crate::logging::{AuditSink, FactsEmitter};
+use serde_json::Value;
+
+use crate::api::errors::{ErrorId, AUDIT_NOT_DURABLE};
+use crate::logging::audit::AuditCtx;
+use crate::logging::{AuditSink, Decision, FactsEmitter, StageLogger};
+use crate::policy::types::AuditDurability;
 
 pub(crate) mod ensure_symlink;
 pub(crate) mod restore;
@@ -8,7 +13,7 @@ pub(crate) trait ActionExecutor<E: FactsEmitter, A: AuditSink> {
     fn execute(
         &self,
         api: &super::super::Switchyard<E, A>,
-        tctx: &crate::logging::audit::AuditCtx<'_>,
+        tctx: &AuditCtx<'_>,
         pid: &uuid::Uuid,
         act: &crate::types::Action,
         idx: usize,
@@ -19,3 +24,33 @@ pub(crate) trait ActionExecutor<E: FactsEmitter, A: AuditSink> {
         super::perf::PerfAgg,
     );
 }
+
+/// Emit the per-action `apply.attempt`. Under `AuditDurability::Required` in Commit mode the fact
+/// must be durable before the action mutates anything; otherwise a best-effort `apply.result`
+/// failure with `E_AUDIT` is emitted and the error returned.
+pub(crate) fn emit_attempt<E: FactsEmitter, A: AuditSink>(
+    api: &super::super::Switchyard<E, A>,
+    tctx: &AuditCtx<'_>,
+    fields: &Value,
+    dry: bool,
+) -> Result<(), String> {
+    let slog = StageLogger::new(tctx);
+    let attempt = slog.apply_attempt().merge(fields);
+    if dry || api.policy.governance.audit_durability == AuditDurability::BestEffort {
+        attempt.emit_success();
+        return Ok(());
+    }
+    attempt.emit_durable(Decision::Success).map_err(|e| {
+        let path = fields
+            .get("path")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        slog.apply_result()
+            .merge(fields)
+            .field("error_detail", Value::from(e.to_string()))
+            .error_id(ErrorId::E_AUDIT)
+            .exit_code_for(ErrorId::E_AUDIT)
+            .emit_failure();
+        format!("{AUDIT_NOT_DURABLE} before mutating {path}: {e}")
+    })
+}
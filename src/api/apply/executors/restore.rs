@@ -40,17 +40,19 @@ impl<E: FactsEmitter, A: AuditSink> ActionExecutor<E, A> for RestoreFromBackupEx
         };
         let aid = action_id(pid, act, idx);
 
-        StageLogger::new(tctx)
-            .apply_attempt()
-            .merge(&json!({
-                "action_id": aid.to_string(),
-                "path": target.as_path().display().to_string(),
-                "safepath_validation": "success",
-                "backup_durable": api.policy.durability.backup_durability,
-            }))
-            .emit_success();
+        let attempt = json!({
+            "action_id": aid.to_string(),
+            "path": target.as_path().display().to_string(),
+            "safepath_validation": "success",
+            "backup_durable": api.policy.durability.backup_durability,
+        });
+        if let Err(msg) = super::emit_attempt(api, tctx, &attempt, dry) {
+            return (None, Some(msg), PerfAgg::default());
+        }
 
         let before_kind = kind_of(&target.as_path());
+        // Filesystem the target lives on, so audits show which guarantees actually held
+        let fs_type = crate::fs::fstype::fs_type_at(&target.as_path()).map(|t| t.name);
         let mut backup_ms = 0u64;
         let force =
             api.policy.apply.best_effort_restore || !api.policy.durability.sidecar_integrity;
@@ -91,6 +93,7 @@ impl<E: FactsEmitter, A: AuditSink> ActionExecutor<E, A> for RestoreFromBackupEx
                             "action_id": aid.to_string(),
                             "path": target.as_path().display().to_string(),
                             "before_kind": before_kind,
+                            "fs_type": fs_type,
                             "after_kind": before_kind,
                             "idempotent": true,
                             "backup_durable": api.policy.durability.backup_durability,
@@ -119,7 +122,11 @@ impl<E: FactsEmitter, A: AuditSink> ActionExecutor<E, A> for RestoreFromBackupEx
         // so that the inverse plan can later restore the pre-restore state via the latest snapshot.
         if !dry && api.policy.apply.capture_restore_snapshot {
             let t_backup_start = Instant::now();
-            let _ = crate::fs::backup::create_snapshot(&target.as_path(), &api.policy.backup.tag);
+            let _ = crate::fs::backup::create_snapshot_with_tier(
+                &target.as_path(),
+                &api.policy.backup.tag,
+                api.policy.preservation_tier,
+            );
             backup_ms = backup_ms.saturating_add(
                 u64::try_from(t_backup_start.elapsed().as_millis()).unwrap_or(u64::MAX),
             );
@@ -141,6 +148,7 @@ impl<E: FactsEmitter, A: AuditSink> ActionExecutor<E, A> for RestoreFromBackupEx
                     "action_id": aid.to_string(),
                     "path": target.as_path().display().to_string(),
                     "before_kind": before_kind,
+                    "fs_type": fs_type,
                     "after_kind": if dry { before_kind } else { kind_of(&target.as_path()) },
                 });
                 // Attach ownership provenance best-effort
@@ -187,6 +195,7 @@ impl<E: FactsEmitter, A: AuditSink> ActionExecutor<E, A> for RestoreFromBackupEx
             "action_id": aid.to_string(),
             "path": target.as_path().display().to_string(),
             "before_kind": before_kind,
+            "fs_type": fs_type,
             "after_kind": if dry { before_kind } else { kind_of(&target.as_path()) },
             "backup_durable": api.policy.durability.backup_durability,
         });
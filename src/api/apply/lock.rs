@@ -1,14 +1,18 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 use std::time::Instant;
 
 use log::Level;
 use serde_json::json;
 use uuid::Uuid;
 
+use crate::adapters::lock::{LockGuard, LockHolder, LockMode};
 use crate::api::errors::ErrorId;
 use crate::api::Switchyard;
 use crate::constants::LOCK_POLL_MS;
 use crate::logging::{AuditSink, FactsEmitter, StageLogger};
-use crate::types::{ApplyMode, ApplyReport};
+use crate::policy::types::LockScope;
+use crate::types::{Action, ApplyMode, ApplyReport, Plan};
 
 use super::util::lock_backend_label;
 
@@ -16,10 +20,161 @@ pub(crate) struct LockInfo {
     pub lock_backend: String,
     pub lock_wait_ms: Option<u64>,
     pub approx_attempts: u64,
-    pub guard: Option<Box<dyn crate::adapters::lock::LockGuard>>,
+    /// Mode held (`None` without a lock manager).
+    pub lock_mode: Option<LockMode>,
+    /// Effective scope (`global` when the manager cannot lock paths).
+    pub lock_scope: LockScope,
+    /// Locks taken (or attempted) in acquisition order.
+    pub lock_paths: Vec<LockedPath>,
+    pub guard: Option<Box<dyn LockGuard>>,
     pub early_report: Option<ApplyReport>,
 }
 
+/// One lock taken for the plan, with the mode requested and the time spent waiting for it.
+#[derive(Clone, Debug)]
+pub(crate) struct LockedPath {
+    pub path: String,
+    pub mode: LockMode,
+    pub wait_ms: u64,
+    pub acquired: bool,
+}
+
+impl LockedPath {
+    fn to_json(&self) -> serde_json::Value {
+        json!({
+            "path": self.path,
+            "mode": self.mode.as_str(),
+            "wait_ms": self.wait_ms,
+            "acquired": self.acquired,
+        })
+    }
+}
+
+pub(crate) fn lock_paths_json(paths: &[LockedPath]) -> serde_json::Value {
+    serde_json::Value::Array(paths.iter().map(LockedPath::to_json).collect())
+}
+
+/// Guards for several path locks, released in reverse acquisition order.
+struct LockSet(Vec<Box<dyn LockGuard>>);
+
+impl Drop for LockSet {
+    fn drop(&mut self) {
+        while let Some(g) = self.0.pop() {
+            drop(g);
+        }
+    }
+}
+
+impl LockGuard for LockSet {
+    /// Exclusive when any member is: path scopes hold the process lock shared beneath
+    /// exclusive keys.
+    fn mode(&self) -> LockMode {
+        if self.0.iter().all(|g| g.mode() == LockMode::Shared) {
+            LockMode::Shared
+        } else {
+            LockMode::Exclusive
+        }
+    }
+}
+
+fn holder_json(h: &LockHolder) -> serde_json::Value {
+    let mut v = h.to_json();
+    if let Some(obj) = v.as_object_mut() {
+        obj.insert("stale".to_string(), json!(h.is_stale()));
+    }
+    v
+}
+
+/// Effective scope for `api`: path scopes fall back to `Global` when unsupported.
+fn effective_scope<E: FactsEmitter, A: AuditSink>(api: &Switchyard<E, A>) -> LockScope {
+    match &api.lock {
+        Some(mgr) if mgr.supports_path_locks() => api.policy.governance.lock_scope,
+        _ => LockScope::Global,
+    }
+}
+
+/// Shared lock taken by read-only stages over the same keys apply locks exclusively.
+pub(crate) struct SharedLock {
+    /// Lock telemetry (`lock_backend`, `lock_mode`, `lock_wait_ms`, `lock_attempts`,
+    /// `lock_scope`, `lock_paths`, and `lock_holder` on timeout) for the stage's summary fact.
+    pub fields: serde_json::Value,
+    pub guard: Option<Box<dyn LockGuard>>,
+    pub error: Option<String>,
+}
+
+/// Take a shared lock for `plan` so a read-only stage never observes a half-applied plan from a
+/// concurrent apply. `None` without a lock manager.
+pub(crate) fn acquire_shared<E: FactsEmitter, A: AuditSink>(
+    api: &Switchyard<E, A>,
+    plan: &Plan,
+    tctx: &crate::logging::audit::AuditCtx<'_>,
+) -> Option<SharedLock> {
+    api.lock.as_ref()?;
+    let scope = effective_scope(api);
+    let holder = LockHolder::current(Some(&tctx.run_id), Some(&tctx.plan_id));
+    let outcome = LockOrchestrator::acquire(
+        api,
+        ApplyMode::DryRun,
+        scope,
+        &lock_keys(plan, scope, LockMode::Shared),
+        LockMode::Shared,
+        &holder,
+    );
+    let mut fields = json!({
+        "lock_backend": lock_backend_label(api.lock.as_deref()),
+        "lock_mode": outcome.guard.as_ref().map_or(LockMode::Shared, |g| g.mode()).as_str(),
+        "lock_wait_ms": outcome.lock_wait_ms,
+        "lock_attempts": outcome.approx_attempts,
+        "lock_scope": scope.as_str(),
+        "lock_paths": lock_paths_json(&outcome.lock_paths),
+    });
+    if let (Some(h), Some(obj)) = (&outcome.holder, fields.as_object_mut()) {
+        obj.insert("lock_holder".to_string(), holder_json(h));
+    }
+    Some(SharedLock {
+        fields,
+        guard: outcome.guard,
+        error: outcome.err_msg,
+    })
+}
+
+/// Canonical, sorted and de-duplicated lock keys for `plan` under `scope`, each with the mode
+/// it is taken in.
+///
+/// `Targets` also takes every parent directory `Shared`, so it excludes a `ParentDirs` holder of
+/// that directory while targets in one directory stay independent of each other. A key needed
+/// in both modes is taken `Exclusive`. Parents sort before their children.
+pub(crate) fn lock_keys(plan: &Plan, scope: LockScope, mode: LockMode) -> Vec<(PathBuf, LockMode)> {
+    let mut keys: BTreeMap<PathBuf, LockMode> = BTreeMap::new();
+    let mut add = |key: PathBuf, m: LockMode| {
+        let held = keys.entry(key).or_insert(m);
+        if m == LockMode::Exclusive {
+            *held = m;
+        }
+    };
+    for act in &plan.actions {
+        let target = match act {
+            Action::EnsureSymlink { target, .. } | Action::RestoreFromBackup { target } => {
+                target.as_path()
+            }
+        };
+        let parent = target.parent().unwrap_or_else(|| Path::new("/"));
+        let parent = std::fs::canonicalize(parent).unwrap_or_else(|_| parent.to_path_buf());
+        match scope {
+            LockScope::Global => {}
+            LockScope::ParentDirs => add(parent, mode),
+            LockScope::Targets => {
+                let key = target
+                    .file_name()
+                    .map_or_else(|| parent.clone(), |n| parent.join(n));
+                add(parent, LockMode::Shared);
+                add(key, mode);
+            }
+        }
+    }
+    keys.into_iter().collect()
+}
+
 impl LockInfo {
     #[must_use]
     #[allow(dead_code, reason = "deferred cleanup")]
@@ -30,6 +185,7 @@ impl LockInfo {
 
 pub(crate) fn acquire<E: FactsEmitter, A: AuditSink>(
     api: &Switchyard<E, A>,
+    plan: &Plan,
     t0: Instant,
     pid: Uuid,
     mode: ApplyMode,
@@ -38,13 +194,25 @@ pub(crate) fn acquire<E: FactsEmitter, A: AuditSink>(
     let dry = matches!(mode, ApplyMode::DryRun);
     let lock_backend = lock_backend_label(api.lock.as_deref());
 
-    if let Some(_mgr) = &api.lock {
-        let outcome = LockOrchestrator::acquire(api, mode);
+    if api.lock.is_some() {
+        let scope = effective_scope(api);
+        let holder = LockHolder::current(Some(&tctx.run_id), Some(&tctx.plan_id));
+        let outcome = LockOrchestrator::acquire(
+            api,
+            mode,
+            scope,
+            &lock_keys(plan, scope, LockMode::Exclusive),
+            LockMode::Exclusive,
+            &holder,
+        );
         if let Some(g) = outcome.guard {
             return LockInfo {
                 lock_backend,
                 lock_wait_ms: outcome.lock_wait_ms,
                 approx_attempts: outcome.approx_attempts,
+                lock_mode: Some(g.mode()),
+                lock_scope: scope,
+                lock_paths: outcome.lock_paths,
                 guard: Some(g),
                 early_report: None,
             };
@@ -55,6 +223,8 @@ pub(crate) fn acquire<E: FactsEmitter, A: AuditSink>(
             &lock_backend,
             outcome.lock_wait_ms,
             outcome.approx_attempts,
+            Some((scope, LockMode::Exclusive, &outcome.lock_paths)),
+            outcome.holder.as_ref(),
         );
         api.audit
             .log(Level::Error, "apply: lock acquisition failed (E_LOCKING)");
@@ -63,6 +233,9 @@ pub(crate) fn acquire<E: FactsEmitter, A: AuditSink>(
             lock_backend,
             lock_wait_ms: outcome.lock_wait_ms,
             approx_attempts: outcome.approx_attempts,
+            lock_mode: Some(LockMode::Exclusive),
+            lock_scope: scope,
+            lock_paths: outcome.lock_paths,
             guard: None,
             early_report: Some(LockOrchestrator::early_report(
                 pid,
@@ -77,21 +250,21 @@ pub(crate) fn acquire<E: FactsEmitter, A: AuditSink>(
             crate::policy::types::LockingPolicy::Required
         ) || !api.policy.governance.allow_unlocked_commit;
         if must_fail {
-            LockOrchestrator::emit_failure(&StageLogger::new(tctx), "none", None, 0);
+            LockOrchestrator::emit_failure(&StageLogger::new(tctx), "none", None, 0, None, None);
             let duration_ms = u64::try_from(t0.elapsed().as_millis()).unwrap_or(u64::MAX);
             return LockInfo {
                 lock_backend: "none".to_string(),
                 lock_wait_ms: None,
                 approx_attempts: 0,
+                lock_mode: None,
+                lock_scope: LockScope::Global,
+                lock_paths: Vec::new(),
                 guard: None,
-                early_report: Some(ApplyReport {
-                    executed: Vec::new(),
+                early_report: Some(LockOrchestrator::early_report(
+                    pid,
                     duration_ms,
-                    errors: vec!["lock manager required in Commit mode".to_string()],
-                    plan_uuid: Some(pid),
-                    rolled_back: false,
-                    rollback_errors: Vec::new(),
-                }),
+                    "lock manager required in Commit mode".to_string(),
+                )),
             };
         }
         // Optional + allowed unlocked: emit a WARN attempt to signal visibility, then proceed.
@@ -116,6 +289,9 @@ pub(crate) fn acquire<E: FactsEmitter, A: AuditSink>(
         lock_backend,
         lock_wait_ms: None,
         approx_attempts,
+        lock_mode: None,
+        lock_scope: LockScope::Global,
+        lock_paths: Vec::new(),
         guard: None,
         early_report: None,
     }
@@ -127,75 +303,147 @@ struct LockOrchestrator;
 struct LockOutcome {
     lock_wait_ms: Option<u64>,
     approx_attempts: u64,
-    guard: Option<Box<dyn crate::adapters::lock::LockGuard>>,
+    lock_paths: Vec<LockedPath>,
+    guard: Option<Box<dyn LockGuard>>,
     err_msg: Option<String>,
+    /// Holder of the lock that could not be acquired, when the backend records one.
+    holder: Option<LockHolder>,
+}
+
+const fn approx_attempts(wait_ms: u64) -> u64 {
+    let n = 1 + (wait_ms / LOCK_POLL_MS);
+    if n < 2 {
+        2
+    } else {
+        n
+    }
+}
+
+fn elapsed_ms(t: Instant) -> u64 {
+    u64::try_from(t.elapsed().as_millis()).unwrap_or(u64::MAX)
 }
 
 impl LockOrchestrator {
+    /// Acquire the process lock in `lock_mode` (`Global`), or the process lock `Shared` followed
+    /// by every key in order, under one overall deadline. Every scope thus meets at the process
+    /// lock: a `Global` apply excludes path-scoped runs, which only exclude each other by key.
     fn acquire<E: FactsEmitter, A: AuditSink>(
         api: &Switchyard<E, A>,
         mode: ApplyMode,
+        scope: LockScope,
+        keys: &[(PathBuf, LockMode)],
+        lock_mode: LockMode,
+        holder: &LockHolder,
     ) -> LockOutcome {
-        if let Some(mgr) = &api.lock {
-            let lt0 = Instant::now();
-            match mgr.acquire_process_lock(api.lock_timeout_ms) {
-                Ok(g) => {
-                    let lock_wait_ms =
-                        Some(u64::try_from(lt0.elapsed().as_millis()).unwrap_or(u64::MAX));
-                    let mut approx_attempts = lock_wait_ms.map_or(1, |ms| 1 + (ms / LOCK_POLL_MS));
-                    if approx_attempts < 2 {
-                        approx_attempts = 2;
-                    }
-                    LockOutcome {
-                        lock_wait_ms,
-                        approx_attempts,
-                        guard: Some(g),
-                        err_msg: None,
-                    }
-                }
-                Err(e) => {
-                    let lock_wait_ms =
-                        Some(u64::try_from(lt0.elapsed().as_millis()).unwrap_or(u64::MAX));
-                    let mut approx_attempts = lock_wait_ms.map_or(1, |ms| 1 + (ms / LOCK_POLL_MS));
-                    if approx_attempts < 2 {
-                        approx_attempts = 2;
-                    }
-                    LockOutcome {
-                        lock_wait_ms,
-                        approx_attempts,
-                        guard: None,
-                        err_msg: Some(format!("lock: {e}")),
-                    }
-                }
-            }
-        } else {
+        let Some(mgr) = &api.lock else {
             // No lock manager. In DryRun this is allowed; otherwise policy may require lock.
             let dry = matches!(mode, ApplyMode::DryRun);
-            if dry {
-                LockOutcome {
-                    lock_wait_ms: None,
-                    approx_attempts: 0,
-                    guard: None,
+            return LockOutcome {
+                lock_wait_ms: None,
+                approx_attempts: 0,
+                lock_paths: Vec::new(),
+                guard: None,
+                err_msg: (!dry).then(|| "lock manager required in Commit mode".to_string()),
+                holder: None,
+            };
+        };
+        let lt0 = Instant::now();
+        if scope == LockScope::Global || keys.is_empty() {
+            let res = mgr.acquire_process_lock_as(api.lock_timeout_ms, lock_mode, holder);
+            let wait_ms = elapsed_ms(lt0);
+            let lock_paths = vec![LockedPath {
+                path: "global".to_string(),
+                mode: lock_mode,
+                wait_ms,
+                acquired: res.is_ok(),
+            }];
+            return match res {
+                Ok(g) => LockOutcome {
+                    lock_wait_ms: Some(wait_ms),
+                    approx_attempts: approx_attempts(wait_ms),
+                    lock_paths,
+                    guard: Some(g),
                     err_msg: None,
-                }
-            } else {
-                LockOutcome {
-                    lock_wait_ms: None,
-                    approx_attempts: 0,
+                    holder: None,
+                },
+                Err(e) => LockOutcome {
+                    lock_wait_ms: Some(wait_ms),
+                    approx_attempts: approx_attempts(wait_ms),
+                    lock_paths,
                     guard: None,
-                    err_msg: Some("lock manager required in Commit mode".to_string()),
+                    err_msg: Some(format!("lock: {}", e.msg)),
+                    holder: mgr.holder(None),
+                },
+            };
+        }
+        let mut guards = Vec::with_capacity(keys.len() + 1);
+        let mut lock_paths = Vec::with_capacity(keys.len() + 1);
+        let mut attempts = 0u64;
+        let process = std::iter::once((None, LockMode::Shared));
+        for (key, key_mode) in process.chain(keys.iter().map(|(k, m)| (Some(k), *m))) {
+            let remaining = api.lock_timeout_ms.saturating_sub(elapsed_ms(lt0));
+            let kt0 = Instant::now();
+            let res = match key {
+                Some(k) => mgr.acquire_path_lock(k, remaining, key_mode, holder),
+                None => mgr.acquire_process_lock_as(remaining, key_mode, holder),
+            };
+            let wait_ms = elapsed_ms(kt0);
+            attempts += approx_attempts(wait_ms);
+            lock_paths.push(LockedPath {
+                path: key.map_or_else(|| "global".to_string(), |k| k.display().to_string()),
+                mode: key_mode,
+                wait_ms,
+                acquired: res.is_ok(),
+            });
+            match res {
+                Ok(g) => guards.push(g),
+                Err(e) => {
+                    // Release what we hold before reporting.
+                    drop(LockSet(guards));
+                    return LockOutcome {
+                        lock_wait_ms: Some(elapsed_ms(lt0)),
+                        approx_attempts: attempts,
+                        lock_paths,
+                        guard: None,
+                        err_msg: Some(format!("lock: {}", e.msg)),
+                        holder: mgr.holder(key.map(PathBuf::as_path)),
+                    };
                 }
             }
         }
+        LockOutcome {
+            lock_wait_ms: Some(elapsed_ms(lt0)),
+            approx_attempts: attempts,
+            lock_paths,
+            guard: Some(Box::new(LockSet(guards))),
+            err_msg: None,
+            holder: None,
+        }
     }
 
-    fn emit_failure(slog: &StageLogger<'_>, backend: &str, wait_ms: Option<u64>, attempts: u64) {
-        slog.apply_attempt()
-            .merge(&json!({
-                "lock_backend": backend,
-                "lock_wait_ms": wait_ms,
-                "lock_attempts": attempts,
-            }))
+    fn emit_failure(
+        slog: &StageLogger<'_>,
+        backend: &str,
+        wait_ms: Option<u64>,
+        attempts: u64,
+        locks: Option<(LockScope, LockMode, &[LockedPath])>,
+        holder: Option<&LockHolder>,
+    ) {
+        let mut attempt = slog.apply_attempt().merge(&json!({
+            "lock_backend": backend,
+            "lock_wait_ms": wait_ms,
+            "lock_attempts": attempts,
+        }));
+        if let Some((scope, lock_mode, paths)) = locks {
+            attempt = attempt
+                .field("lock_mode", json!(lock_mode.as_str()))
+                .field("lock_scope", json!(scope.as_str()))
+                .field("lock_paths", lock_paths_json(paths));
+        }
+        if let Some(h) = holder {
+            attempt = attempt.field("lock_holder", holder_json(h));
+        }
+        attempt
             .error_id(ErrorId::E_LOCKING)
             .exit_code_for(ErrorId::E_LOCKING)
             .emit_failure();
@@ -224,6 +472,9 @@ impl LockOrchestrator {
             plan_uuid: Some(pid),
             rolled_back: false,
             rollback_errors: Vec::new(),
+            stale_processes: Vec::new(),
+            smoke_checks: Vec::new(),
+            audit_failures: Vec::new(),
         }
     }
 }
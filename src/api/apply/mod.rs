@@ -4,27 +4,34 @@
 //! - Emits Audit v2 facts for `apply.attempt` and `apply.result` per action, plus a summary.
 //! - Enforces locking policy and maps failures to `E_LOCKING` with bounded wait.
 //! - Enforces policy gating (unless `override_preflight=true`).
-//! - Optionally runs smoke tests post-apply and triggers auto-rollback on failures.
+//! - Optionally runs smoke tests post-apply, emits per-check results in a `smoke.result` fact,
+//!   and triggers auto-rollback on failures.
 //! - Optionally emits an attestation bundle on success.
+//! - Lists processes still running a replaced target's old inode (`stale_processes`).
+//! - With `governance.audit_durability=Required`, refuses to mutate for an action whose
+//!   `apply.attempt` was not durably recorded (`E_AUDIT`); later emission failures are listed in
+//!   `audit_failures`.
 
 use std::time::Instant;
 
 use crate::logging::audit::new_run_id;
 use serde_json::json;
 
+use crate::api::errors::ErrorId;
 use crate::logging::ts_for_mode;
 use crate::logging::{AuditSink, FactsEmitter};
 use crate::types::ids::plan_id;
-use crate::types::{Action, ApplyMode, ApplyReport, Plan};
+use crate::types::{Action, ApplyMode, ApplyReport, Plan, SmokeCheck, StaleProcess};
 use log::Level;
 
 use crate::api::Switchyard;
 use crate::logging::audit::{AuditCtx, AuditMode};
 use crate::logging::StageLogger;
+use crate::policy::types::AuditDurability;
 mod audit_fields;
 mod executors;
 mod handlers;
-mod lock;
+pub(crate) mod lock;
 mod perf;
 mod policy_gate;
 mod rollback;
@@ -64,15 +71,19 @@ pub(crate) fn run<E: FactsEmitter, A: AuditSink>(
         AuditMode {
             dry_run: dry,
             redact: dry,
+            hash_chain: api.policy.audit.hash_chain,
+            redaction: std::sync::Arc::clone(&api.redaction),
+            metrics: api.metrics.clone(),
         },
     );
     let slog = StageLogger::new(&tctx);
 
     // Locking (required by default in Commit): acquire process lock with bounded wait; emit telemetry via apply.attempt
     api.audit.log(Level::Info, "apply: starting");
-    let linfo = lock::acquire(api, t0, pid, mode, &tctx);
+    let linfo = lock::acquire(api, plan, t0, pid, mode, &tctx);
     let mut _lock_guard: Option<Box<dyn crate::adapters::lock::LockGuard>> = linfo.guard;
-    if let Some(early) = linfo.early_report {
+    if let Some(mut early) = linfo.early_report {
+        early.audit_failures = tctx.take_emit_failures();
         return early;
     }
 
@@ -87,20 +98,42 @@ pub(crate) fn run<E: FactsEmitter, A: AuditSink>(
             "lock_backend": linfo.lock_backend,
             "lock_wait_ms": linfo.lock_wait_ms,
             "lock_attempts": approx_attempts,
+            "lock_mode": linfo.lock_mode.map(crate::adapters::lock::LockMode::as_str),
+            "lock_scope": linfo.lock_scope.as_str(),
+            "lock_paths": lock::lock_paths_json(&linfo.lock_paths),
         }))
         .emit_success();
 
     // Policy gating: refuse to proceed when preflight would STOP, unless override is set.
-    if let Some(report) = policy_gate::enforce(api, plan, pid, dry, t0, &slog) {
+    if let Some(mut report) = policy_gate::enforce(api, plan, pid, dry, t0, &slog) {
+        report.audit_failures = tctx.take_emit_failures();
         return report;
     }
 
+    // Record the inode each target resolves to before mutation, so processes still running
+    // the old binary can be listed once the swap is done.
+    let old_inodes: Vec<Option<crate::preflight::busy::InodeKey>> = if dry {
+        Vec::new()
+    } else {
+        plan.actions
+            .iter()
+            .map(|a| match a {
+                Action::EnsureSymlink { target, .. } | Action::RestoreFromBackup { target } => {
+                    crate::preflight::busy::inode_key_of(&target.as_path())
+                }
+            })
+            .collect()
+    };
+
     let mut perf_total = PerfAgg::default();
     for (idx, act) in plan.actions.iter().enumerate() {
         match act {
             Action::EnsureSymlink { .. } => {
                 let (exec, err, perf) =
                     handlers::handle_ensure_symlink(api, &tctx, &pid, act, idx, dry, &slog);
+                if let (Some(m), false) = (&api.metrics, dry) {
+                    m.record_action("ensure_symlink", err.is_none());
+                }
                 perf_total.hash += perf.hash;
                 perf_total.backup += perf.backup;
                 perf_total.swap += perf.swap;
@@ -115,6 +148,9 @@ pub(crate) fn run<E: FactsEmitter, A: AuditSink>(
             Action::RestoreFromBackup { .. } => {
                 let (exec, err, perf) =
                     handlers::handle_restore(api, &tctx, &pid, act, idx, dry, &slog);
+                if let (Some(m), false) = (&api.metrics, dry) {
+                    m.record_action("restore_from_backup", err.is_none());
+                }
                 perf_total.hash += perf.hash;
                 perf_total.backup += perf.backup;
                 perf_total.swap += perf.swap;
@@ -153,9 +189,16 @@ pub(crate) fn run<E: FactsEmitter, A: AuditSink>(
     }
 
     // Optional smoke tests post-apply (only in Commit mode)
+    let mut smoke_checks = Vec::new();
     if errors.is_empty() && !dry {
         if let Some(smoke) = &api.smoke {
-            if smoke.run(plan).is_err() {
+            let outcome = smoke.run_checks(plan);
+            smoke_checks = match &outcome {
+                Ok(checks) => checks.clone(),
+                Err(f) => f.checks.clone(),
+            };
+            emit_smoke_result(&slog, &smoke_checks, outcome.is_ok());
+            if outcome.is_err() {
                 errors.push("smoke tests failed".to_string());
                 let auto_rb = match api.policy.governance.smoke {
                     crate::policy::types::SmokePolicy::Require { auto_rollback } => auto_rollback,
@@ -203,6 +246,8 @@ pub(crate) fn run<E: FactsEmitter, A: AuditSink>(
         }
     }
 
+    let stale_processes = stale_processes(plan, &executed_indices, &old_inodes);
+
     // Final apply.result summary (after smoke tests/rollback)
     let decision = if errors.is_empty() {
         "success"
@@ -213,7 +258,7 @@ pub(crate) fn run<E: FactsEmitter, A: AuditSink>(
     let mut builder = summary::ApplySummary::new(&linfo.lock_backend, linfo.lock_wait_ms);
     // Optional attestation when an attestor is configured (non-dry-run)
     if !dry {
-        builder = builder.attestation(api, pid, executed.len(), rolled_back);
+        builder = builder.attestation(api, pid, executed.len(), rolled_back, tctx.chain_head());
     }
 
     // we already include ts/stage in helper
@@ -230,10 +275,24 @@ pub(crate) fn run<E: FactsEmitter, A: AuditSink>(
     // Always include simple counts for observability
     let executed_count = executed.len();
     let rolled_back_count = rolled_paths_opt.as_ref().map_or(0, Vec::len);
-    builder = builder.executed_counts(executed_count, rolled_back_count);
+    builder = builder
+        .executed_counts(executed_count, rolled_back_count)
+        .stale_processes(&stale_processes);
     builder.perf(perf_total).emit(&slog, decision);
     api.audit.log(Level::Info, "apply: finished");
 
+    // Mutations cannot be undone for want of a fact; report what was not recorded instead.
+    let mut audit_failures = tctx.take_emit_failures();
+    if !dry && api.policy.governance.audit_durability == AuditDurability::Required {
+        if let Err(e) = api.facts.flush() {
+            audit_failures.push(format!("flush: {e}"));
+        }
+    }
+    for f in &audit_failures {
+        api.audit
+            .log(Level::Warn, &format!("apply: audit fact not recorded: {f}"));
+    }
+
     // Compute total duration
     let duration_ms = u64::try_from(t0.elapsed().as_millis()).unwrap_or(u64::MAX);
     ApplyReport {
@@ -243,5 +302,68 @@ pub(crate) fn run<E: FactsEmitter, A: AuditSink>(
         plan_uuid: Some(pid),
         rolled_back,
         rollback_errors,
+        stale_processes,
+        smoke_checks,
+        audit_failures,
+    }
+}
+
+/// Emit the `smoke.result` fact with per-check results.
+fn emit_smoke_result(slog: &StageLogger<'_>, checks: &[SmokeCheck], ok: bool) {
+    let failed = checks.iter().filter(|c| !c.ok).count();
+    let evt = slog.smoke_result().field(
+        "smoke",
+        json!({
+            "checks": checks.iter().map(SmokeCheck::to_json).collect::<Vec<_>>(),
+            "total": checks.len(),
+            "failed": failed,
+        }),
+    );
+    if ok {
+        evt.emit_success();
+    } else {
+        evt.error_id(ErrorId::E_SMOKE)
+            .exit_code_for(ErrorId::E_SMOKE)
+            .emit_failure();
+    }
+}
+
+/// List processes that still execute or map the pre-apply inode of an executed target.
+fn stale_processes(
+    plan: &Plan,
+    executed_indices: &[usize],
+    old_inodes: &[Option<crate::preflight::busy::InodeKey>],
+) -> Vec<StaleProcess> {
+    let replaced: Vec<(usize, crate::preflight::busy::InodeKey)> = executed_indices
+        .iter()
+        .filter_map(|&i| old_inodes.get(i).copied().flatten().map(|k| (i, k)))
+        .collect();
+    if replaced.is_empty() {
+        return Vec::new();
+    }
+    let index = crate::preflight::busy::BusyIndex::scan();
+    let mut out = Vec::new();
+    for (i, key) in replaced {
+        let Some(act) = plan.actions.get(i) else {
+            continue;
+        };
+        let target = match act {
+            Action::EnsureSymlink { target, .. } | Action::RestoreFromBackup { target } => {
+                target.as_path()
+            }
+        };
+        // Unchanged inode (e.g., no-op swap) means nothing is stale.
+        if crate::preflight::busy::inode_key_of(&target) == Some(key) {
+            continue;
+        }
+        for p in index.lookup_key(key) {
+            out.push(StaleProcess {
+                path: target.display().to_string(),
+                pid: p.pid,
+                comm: p.comm,
+                via: p.via.as_str().to_string(),
+            });
+        }
     }
+    out
 }
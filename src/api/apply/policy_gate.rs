@@ -9,7 +9,7 @@ use uuid::Uuid;
 use crate::logging::{AuditSink, FactsEmitter, StageLogger};
 use crate::types::{Action, ApplyReport, Plan};
 
-use crate::api::errors::{exit_code_for, ErrorId};
+use crate::api::errors::{exit_code_for, id_str, ErrorId};
 use log::Level;
 
 pub(crate) fn enforce<E: FactsEmitter, A: AuditSink>(
@@ -24,15 +24,26 @@ pub(crate) fn enforce<E: FactsEmitter, A: AuditSink>(
         return None;
     }
     let gating_errors =
-        crate::policy::gating::gating_errors(&api.policy, api.owner.as_deref(), plan);
+        crate::policy::gating::gating_errors(&api.policy, api.owner.as_deref(), &api.checks, plan);
     if gating_errors.is_empty() {
         return None;
     }
+    // Running out of room for backups has a dedicated reason code.
+    let eid = if gating_errors
+        .iter()
+        .any(|e| e.starts_with(crate::preflight::space::INSUFFICIENT_SPACE))
+    {
+        ErrorId::E_NO_SPACE
+    } else {
+        ErrorId::E_POLICY
+    };
     // Parity: audit log at warn level when policy gating rejects
-    api.audit
-        .log(Level::Warn, "apply: policy gating rejected plan (E_POLICY)");
+    api.audit.log(
+        Level::Warn,
+        &format!("apply: policy gating rejected plan ({})", id_str(eid)),
+    );
     // Emit per-action failures with action_id for visibility
-    let ec = exit_code_for(ErrorId::E_POLICY);
+    let ec = exit_code_for(eid);
     for (idx, act) in plan.actions.iter().enumerate() {
         let aid = crate::types::ids::action_id(&pid, act, idx).to_string();
         let path = match act {
@@ -44,14 +55,14 @@ pub(crate) fn enforce<E: FactsEmitter, A: AuditSink>(
             .merge(&json!({
                 "action_id": aid,
                 "path": path,
-                "error_id": "E_POLICY",
+                "error_id": id_str(eid),
                 "exit_code": ec,
             }))
             .emit_failure();
     }
     slog.apply_result()
         .merge(&json!({
-            "error_id": "E_POLICY",
+            "error_id": id_str(eid),
             "exit_code": ec,
             "perf": {"hash_ms": 0u64, "backup_ms": 0u64, "swap_ms": 0u64},
         }))
@@ -65,5 +76,8 @@ pub(crate) fn enforce<E: FactsEmitter, A: AuditSink>(
         plan_uuid: Some(pid),
         rolled_back: false,
         rollback_errors: Vec::new(),
+        stale_processes: Vec::new(),
+        smoke_checks: Vec::new(),
+        audit_failures: Vec::new(),
     })
 }
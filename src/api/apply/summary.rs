@@ -65,6 +65,21 @@ impl ApplySummary {
         self
     }
 
+    /// Record processes still executing or mapping a replaced inode (restart candidates).
+    pub(crate) fn stale_processes(mut self, procs: &[crate::types::StaleProcess]) -> Self {
+        if procs.is_empty() {
+            return self;
+        }
+        if let Some(obj) = self.fields.as_object_mut() {
+            let list: Vec<Value> = procs
+                .iter()
+                .map(|p| json!({"path": p.path, "pid": p.pid, "comm": p.comm, "via": p.via}))
+                .collect();
+            obj.insert("stale_processes".to_string(), Value::Array(list));
+        }
+        self
+    }
+
     pub(crate) fn errors(mut self, errors: &[String]) -> Self {
         if let Some(obj) = self.fields.as_object_mut() {
             // Compute chain best-effort from collected error messages
@@ -82,7 +97,9 @@ impl ApplySummary {
             // Derive classification from the summary chain (case-insensitive mapping)
             let chain = crate::api::errors::infer_summary_error_ids(errors);
             let has = |s: &str| chain.contains(&s);
-            let pick = if has(id_str(ErrorId::E_SMOKE)) {
+            let pick = if has(id_str(ErrorId::E_AUDIT)) {
+                ErrorId::E_AUDIT
+            } else if has(id_str(ErrorId::E_SMOKE)) {
                 ErrorId::E_SMOKE
             } else if has(id_str(ErrorId::E_EXDEV)) {
                 ErrorId::E_EXDEV
@@ -109,17 +126,22 @@ impl ApplySummary {
         pid: uuid::Uuid,
         executed_len: usize,
         rolled_back: bool,
+        chain_head: Option<String>,
     ) -> Self {
         if let Some(att) = &api.attest {
-            let bundle_json = json!({
-                "plan_id": pid.to_string(),
-                "executed": executed_len,
-                "rolled_back": rolled_back,
-            });
-            let bundle: Vec<u8> = serde_json::to_vec(&bundle_json).unwrap_or_default();
-            if let Some(att_json) =
+            // With hash chaining, the signature also covers every fact emitted before this one.
+            let bundle = crate::adapters::attest::attestation_bundle(
+                &pid.to_string(),
+                executed_len,
+                rolled_back,
+                chain_head.as_deref(),
+            );
+            if let Some(mut att_json) =
                 crate::adapters::attest::build_attestation_fields(&**att, &bundle)
             {
+                if let (Some(head), Some(obj)) = (chain_head, att_json.as_object_mut()) {
+                    obj.insert("chain_head".to_string(), json!(head));
+                }
                 if let Some(obj) = self.fields.as_object_mut() {
                     obj.insert("attestation".to_string(), att_json);
                 }
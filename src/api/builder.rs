@@ -1,5 +1,9 @@
-use crate::api::{DebugAttestor, DebugLockManager, DebugOwnershipOracle, DebugSmokeTestRunner};
+use crate::api::{
+    DebugAttestor, DebugLockManager, DebugOwnershipOracle, DebugPolicyCheck, DebugSmokeTestRunner,
+};
 use crate::constants::DEFAULT_LOCK_TIMEOUT_MS;
+use crate::logging::metrics::Metrics;
+use crate::logging::redact::RedactionPolicy;
 use crate::logging::{AuditSink, FactsEmitter};
 use crate::policy::Policy;
 
@@ -15,6 +19,9 @@ pub struct ApiBuilder<E: FactsEmitter, A: AuditSink> {
     owner: Option<Box<dyn DebugOwnershipOracle>>, // strict ownership gating
     attest: Option<Box<dyn DebugAttestor>>,  // final summary attestation
     smoke: Option<Box<dyn DebugSmokeTestRunner>>, // post-apply health verification
+    checks: Vec<Box<dyn DebugPolicyCheck>>,  // custom preflight/apply gates
+    redaction: RedactionPolicy,              // operator redaction of every fact
+    metrics: Option<Metrics>,                // counters/histograms fed by facts
     lock_timeout_ms: Option<u64>,
 }
 
@@ -28,6 +35,9 @@ impl<E: FactsEmitter, A: AuditSink> ApiBuilder<E, A> {
             owner: None,
             attest: None,
             smoke: None,
+            checks: Vec::new(),
+            redaction: RedactionPolicy::default(),
+            metrics: None,
             lock_timeout_ms: None,
         }
     }
@@ -57,6 +67,9 @@ impl<E: FactsEmitter, A: AuditSink> ApiBuilder<E, A> {
             owner: None,
             attest: None,
             smoke: None,
+            checks: self.checks,
+            redaction: std::sync::Arc::new(self.redaction),
+            metrics: self.metrics,
             lock_timeout_ms: self.lock_timeout_ms.unwrap_or(DEFAULT_LOCK_TIMEOUT_MS),
         };
         if let Some(lock) = self.lock {
@@ -98,6 +111,29 @@ impl<E: FactsEmitter, A: AuditSink> ApiBuilder<E, A> {
         self
     }
 
+    /// Register a custom [`PolicyCheck`](crate::policy::PolicyCheck). May be called multiple
+    /// times; checks run in registration order after the built-in gates.
+    #[must_use]
+    pub fn with_policy_check(mut self, check: Box<dyn DebugPolicyCheck>) -> Self {
+        self.checks.push(check);
+        self
+    }
+
+    /// Redact every emitted fact (`DryRun` and `Commit`) with `redaction`; see
+    /// [`RedactionPolicy`].
+    #[must_use]
+    pub fn with_redaction_policy(mut self, redaction: RedactionPolicy) -> Self {
+        self.redaction = redaction;
+        self
+    }
+
+    /// Feed `metrics` from every emitted fact and executed action; keep a clone to render it.
+    #[must_use]
+    pub fn with_metrics(mut self, metrics: Metrics) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
     #[must_use]
     pub const fn with_lock_timeout_ms(mut self, timeout_ms: u64) -> Self {
         self.lock_timeout_ms = Some(timeout_ms);
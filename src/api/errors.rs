@@ -19,12 +19,18 @@ pub enum ApiError {
     AttestationFailed(String),
 }
 
+/// Prefix of apply errors raised when a pre-mutation fact could not be durably recorded.
+pub(crate) const AUDIT_NOT_DURABLE: &str = "audit not durable";
+
 /// Best-effort mapping from apply-stage error strings to a chain of stable summary error IDs.
 /// Always includes a top-level classification; may include co-emitted categories like `E_OWNERSHIP`.
 #[must_use]
 pub fn infer_summary_error_ids(errors: &[String]) -> Vec<&'static str> {
     let mut out: Vec<&'static str> = Vec::new();
     let joined = errors.join("; ").to_lowercase();
+    if joined.contains(AUDIT_NOT_DURABLE) {
+        out.push(id_str(ErrorId::E_AUDIT));
+    }
     if joined.contains("smoke") {
         out.push(id_str(ErrorId::E_SMOKE));
     }
@@ -52,6 +58,9 @@ pub fn infer_summary_error_ids(errors: &[String]) -> Vec<&'static str> {
     if joined.contains("backup") && joined.contains("missing") {
         out.push(id_str(ErrorId::E_BACKUP_MISSING));
     }
+    if joined.contains(crate::preflight::space::INSUFFICIENT_SPACE) {
+        out.push(id_str(ErrorId::E_NO_SPACE));
+    }
     if joined.contains("restore") && joined.contains("failed") {
         out.push(id_str(ErrorId::E_RESTORE_FAILED));
     }
@@ -82,7 +91,7 @@ impl From<crate::types::errors::Error> for ApiError {
     non_camel_case_types,
     reason = "Error IDs must match SPEC/error_codes.toml format"
 )]
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub enum ErrorId {
     E_POLICY,
     E_OWNERSHIP,
@@ -92,6 +101,8 @@ pub enum ErrorId {
     E_BACKUP_MISSING,
     E_RESTORE_FAILED,
     E_SMOKE,
+    E_NO_SPACE,
+    E_AUDIT,
     E_GENERIC,
 }
 
@@ -106,6 +117,8 @@ pub const fn id_str(id: ErrorId) -> &'static str {
         ErrorId::E_BACKUP_MISSING => "E_BACKUP_MISSING",
         ErrorId::E_RESTORE_FAILED => "E_RESTORE_FAILED",
         ErrorId::E_SMOKE => "E_SMOKE",
+        ErrorId::E_NO_SPACE => "E_NO_SPACE",
+        ErrorId::E_AUDIT => "E_AUDIT",
         ErrorId::E_GENERIC => "E_GENERIC",
     }
 }
@@ -121,6 +134,8 @@ pub const fn exit_code_for(id: ErrorId) -> i32 {
         ErrorId::E_BACKUP_MISSING => 60,
         ErrorId::E_RESTORE_FAILED => 70,
         ErrorId::E_SMOKE => 80,
+        ErrorId::E_NO_SPACE => 90,
+        ErrorId::E_AUDIT => 100,
         ErrorId::E_GENERIC => 1,
     }
 }
@@ -136,6 +151,8 @@ pub fn exit_code_for_id_str(s: &str) -> Option<i32> {
         "E_BACKUP_MISSING" => Some(60),
         "E_RESTORE_FAILED" => Some(70),
         "E_SMOKE" => Some(80),
+        "E_NO_SPACE" => Some(90),
+        "E_AUDIT" => Some(100),
         _ => None,
     }
 }
@@ -15,10 +15,13 @@
 
 use crate::adapters::{Attestor, LockManager, OwnershipOracle, SmokeTestRunner};
 use crate::logging::audit::new_run_id;
+use crate::logging::metrics::Metrics;
+use crate::logging::redact::RedactionPolicy;
 use crate::logging::{AuditSink, FactsEmitter, StageLogger};
-use crate::policy::Policy;
+use crate::policy::{Policy, PolicyCheck};
 use crate::types::{ApplyMode, ApplyReport, Plan, PlanInput, PreflightReport};
 use serde_json::json;
+use std::sync::Arc;
 
 // Internal API submodules (idiomatic; directory module)
 mod apply;
@@ -56,6 +59,10 @@ impl<T: Attestor + std::fmt::Debug> DebugAttestor for T {}
 pub trait DebugSmokeTestRunner: SmokeTestRunner + std::fmt::Debug {}
 impl<T: SmokeTestRunner + std::fmt::Debug> DebugSmokeTestRunner for T {}
 
+/// Trait marker to bound `PolicyCheck` with `Debug` for use inside the public API.
+pub trait DebugPolicyCheck: PolicyCheck + std::fmt::Debug {}
+impl<T: PolicyCheck + std::fmt::Debug> DebugPolicyCheck for T {}
+
 /// Facade for orchestrating Switchyard stages over a configured `Policy` and adapters.
 ///
 /// Construct via [`ApiBuilder`] or `Switchyard::builder` and then call `plan`,
@@ -71,6 +78,9 @@ pub struct Switchyard<E: FactsEmitter, A: AuditSink> {
     owner: Option<Box<dyn DebugOwnershipOracle>>, // for strict ownership gating
     attest: Option<Box<dyn DebugAttestor>>,  // for final summary attestation
     smoke: Option<Box<dyn DebugSmokeTestRunner>>, // for post-apply health verification
+    checks: Vec<Box<dyn DebugPolicyCheck>>,  // custom preflight/apply gates
+    redaction: Arc<RedactionPolicy>,         // operator redaction of every fact
+    metrics: Option<Metrics>,                // counters/histograms fed by facts
     lock_timeout_ms: u64,
 }
 
@@ -139,6 +149,27 @@ impl<E: FactsEmitter, A: AuditSink> Switchyard<E, A> {
         self
     }
 
+    /// Configure via `ApiBuilder::with_policy_check`.
+    #[must_use]
+    pub fn with_policy_check(mut self, check: Box<dyn DebugPolicyCheck>) -> Self {
+        self.checks.push(check);
+        self
+    }
+
+    /// Configure via `ApiBuilder::with_redaction_policy`.
+    #[must_use]
+    pub fn with_redaction_policy(mut self, redaction: RedactionPolicy) -> Self {
+        self.redaction = Arc::new(redaction);
+        self
+    }
+
+    /// Configure via `ApiBuilder::with_metrics`.
+    #[must_use]
+    pub fn with_metrics(mut self, metrics: Metrics) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
     /// Configure via `ApiBuilder::with_lock_timeout_ms`.
     #[must_use]
     pub const fn with_lock_timeout_ms(mut self, timeout_ms: u64) -> Self {
@@ -183,9 +214,7 @@ impl<E: FactsEmitter, A: AuditSink> Switchyard<E, A> {
         if matches!(mode, ApplyMode::Commit) && !report.errors.is_empty() {
             let joined = report.errors.join("; ").to_lowercase();
             if joined.contains("lock") {
-                return Err(errors::ApiError::LockingTimeout(
-                    "lock manager required or acquisition failed".to_string(),
-                ));
+                return Err(errors::ApiError::LockingTimeout(report.errors.join("; ")));
             }
         }
         Ok(report)
@@ -214,6 +243,9 @@ impl<E: FactsEmitter, A: AuditSink> Switchyard<E, A> {
             crate::logging::audit::AuditMode {
                 dry_run: false,
                 redact: false,
+                hash_chain: self.policy.audit.hash_chain,
+                redaction: Arc::clone(&self.redaction),
+                metrics: self.metrics.clone(),
             },
         );
         StageLogger::new(&tctx)
@@ -260,6 +292,9 @@ impl<E: FactsEmitter, A: AuditSink> Switchyard<E, A> {
             crate::logging::audit::AuditMode {
                 dry_run: false,
                 redact: false,
+                hash_chain: self.policy.audit.hash_chain,
+                redaction: Arc::clone(&self.redaction),
+                metrics: self.metrics.clone(),
             },
         );
 
@@ -288,7 +323,7 @@ impl<E: FactsEmitter, A: AuditSink> Switchyard<E, A> {
                     .merge(&json!({
                         "path": target.as_path().display().to_string(),
                         "backup_tag": self.policy.backup.tag,
-                        "error": e.to_string(),
+                        "error_detail": e.to_string(),
                         "error_id": errors::id_str(errors::ErrorId::E_GENERIC),
                         "exit_code": errors::exit_code_for(errors::ErrorId::E_GENERIC),
                     }))
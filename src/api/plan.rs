@@ -56,6 +56,9 @@ pub(crate) fn build<E: FactsEmitter, A: crate::logging::AuditSink>(
         AuditMode {
             dry_run: true,
             redact: true,
+            hash_chain: api.policy.audit.hash_chain,
+            redaction: std::sync::Arc::clone(&api.redaction),
+            metrics: api.metrics.clone(),
         },
     );
     let slog = StageLogger::new(&tctx);
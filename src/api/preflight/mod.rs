@@ -1,8 +1,19 @@
 //! Preflight stage: policy gating, preservation probes, and per-action rows emission.
 //!
 //! Side-effects:
+//! - With `governance.preflight_shared_lock`, holds a shared lock for the whole stage (lock
+//!   fields in the summary); a timeout STOPs with `E_LOCKING` before any inspection.
 //! - Emits one preflight fact per action with core fields and optional provenance/notes/preservation.
-//! - Emits a preflight summary with a `rescue_profile` status.
+//! - Reports processes executing or mapping each target (`busy`), gated by `risks.busy_binaries`.
+//! - Reports the target's mount (`mount`: fs type, id, nosuid/nodev, bind, overlay layers, and
+//!   whether the backup location shares its device), warning on predicted EXDEV/nosuid issues.
+//! - Reports the target's filesystem type (`fs_type`), gated per class by `risks.fs_classes`.
+//! - Reports `preservation_effective`: dimensions both supported for the path and selected by
+//!   `Policy.preservation_tier`.
+//! - Estimates per-filesystem bytes/inodes needed for backups (`space_budget` in the summary);
+//!   a shortfall is a STOP reported as `E_NO_SPACE`.
+//! - Emits a preflight summary with a `rescue_profile` status and `rescue_excluded` tools
+//!   (rescue candidates the plan itself replaces or links to).
 //! - Returns a `PreflightReport` with stable row ordering suitable for YAML export via `preflight::to_yaml()`.
 //!
 //! This module is the stage orchestrator. Low-level helper checks and the YAML
@@ -42,26 +53,82 @@ pub(crate) fn run<E: FactsEmitter, A: crate::logging::AuditSink>(
         AuditMode {
             dry_run: true,
             redact: true,
+            hash_chain: api.policy.audit.hash_chain,
+            redaction: std::sync::Arc::clone(&api.redaction),
+            metrics: api.metrics.clone(),
         },
     );
 
+    // Optional shared lock: wait for a concurrent apply rather than report on a half-applied
+    // plan. The guard is held until the report is built.
+    let shared_lock = if api.policy.governance.preflight_shared_lock {
+        super::apply::lock::acquire_shared(api, plan, &ctx)
+    } else {
+        None
+    };
+    let lock_fields = shared_lock.as_ref().map(|l| l.fields.clone());
+    if let Some(err) = shared_lock.as_ref().and_then(|l| l.error.clone()) {
+        crate::logging::StageLogger::new(&ctx)
+            .preflight_summary()
+            .merge(&lock_fields.unwrap_or_default())
+            .field("summary_error_ids", json!(["E_LOCKING"]))
+            .error_id(crate::api::errors::ErrorId::E_LOCKING)
+            .exit_code_for(crate::api::errors::ErrorId::E_LOCKING)
+            .emit_failure();
+        return PreflightReport {
+            ok: false,
+            warnings,
+            stops: vec![err],
+            rows,
+        };
+    }
+    let _lock_guard = shared_lock.and_then(|l| l.guard);
+
     // Global rescue verification: if required by policy, STOP when unavailable.
-    // Prefer per-instance override when provided; otherwise run normal probe.
-    let rescue_ok = crate::policy::rescue::verify_rescue_min_with_override(
-        api.policy.rescue.exec_check,
-        api.policy.rescue.min_count,
+    // Prefer per-instance override when provided; otherwise run the plan-aware probe, which
+    // ignores rescue tools the plan itself replaces.
+    let rescue = crate::policy::rescue::verify_rescue_for_plan(
+        &api.policy.rescue,
+        &api.policy.rescue_profiles,
+        plan,
         api.overrides().force_rescue_ok,
-    )
-    .is_ok();
-    if api.policy.rescue.require && !rescue_ok {
-        stops.push("rescue profile unavailable".to_string());
+    );
+    let rescue_ok = rescue.status.is_ok();
+    if api.policy.rescue.require {
+        for ex in &rescue.excluded {
+            warnings.push(format!(
+                "rescue tool excluded: {} at {} is touched by the plan",
+                ex.tool,
+                ex.path.display()
+            ));
+        }
+        if !rescue_ok {
+            stops.push("rescue profile unavailable".to_string());
+        }
     }
 
+    // Plan-level custom checks contribute global stops/warnings.
+    let plan_eval = gating::evaluate_plan(&api.policy, &api.checks, plan);
+    stops.extend(plan_eval.stops.iter().cloned());
+    warnings.extend(plan_eval.warnings.iter().cloned());
+
+    // One /proc scan for the whole plan; per-target lookups are by inode.
+    let busy_index = crate::preflight::busy::BusyIndex::scan();
+    // Likewise one mountinfo read; an unreadable table only loses the `mount` row field.
+    let mounts = crate::fs::mount::MountTable::read().unwrap_or_default();
+
     let emitter = RowEmitter { api, plan };
     for act in &plan.actions {
         match act {
             Action::EnsureSymlink { source, target } => {
-                let eval = gating::evaluate_action(&api.policy, api.owner.as_deref(), act);
+                let mut eval =
+                    gating::evaluate_action(&api.policy, api.owner.as_deref(), &api.checks, act);
+                let (busy_eval, busy) = gating::evaluate_busy(&api.policy, &busy_index, act);
+                eval.absorb(busy_eval);
+                let (mount_eval, mount) = gating::evaluate_mount(&mounts, act);
+                eval.absorb(mount_eval);
+                let (fs_eval, fs_type) = gating::evaluate_fs_type(&api.policy, act);
+                eval.absorb(fs_eval);
                 if !eval.stops.is_empty() {
                     stops.extend(eval.stops.clone());
                 }
@@ -71,6 +138,7 @@ pub(crate) fn run<E: FactsEmitter, A: crate::logging::AuditSink>(
                         .filter(|n| n.contains("allowed by policy"))
                         .cloned(),
                 );
+                warnings.extend(eval.warnings.iter().cloned());
                 // Additional world-writable check at preflight orchestrator level to ensure STOP when required
                 #[cfg(unix)]
                 {
@@ -119,15 +187,35 @@ pub(crate) fn run<E: FactsEmitter, A: crate::logging::AuditSink>(
                         } else {
                             Some(eval.notes)
                         },
+                        checks: if eval.checks.is_empty() {
+                            None
+                        } else {
+                            Some(eval.checks)
+                        },
+                        preservation_effective: Some(effective_preservation(
+                            api.policy.preservation_tier,
+                            &preservation,
+                        )),
+                        preservation_tier: Some(api.policy.preservation_tier.as_str().to_string()),
                         preservation: Some(preservation),
                         preservation_supported: Some(preservation_supported),
+                        busy: busy_rows(&busy),
+                        mount,
+                        fs_type: fs_type.map(|t| t.name.to_string()),
                         restore_ready: None,
                     },
                 );
                 // Warnings: promote policy-allowed notes as warnings
             }
             Action::RestoreFromBackup { target } => {
-                let eval = gating::evaluate_action(&api.policy, api.owner.as_deref(), act);
+                let mut eval =
+                    gating::evaluate_action(&api.policy, api.owner.as_deref(), &api.checks, act);
+                let (busy_eval, busy) = gating::evaluate_busy(&api.policy, &busy_index, act);
+                eval.absorb(busy_eval);
+                let (mount_eval, mount) = gating::evaluate_mount(&mounts, act);
+                eval.absorb(mount_eval);
+                let (fs_eval, fs_type) = gating::evaluate_fs_type(&api.policy, act);
+                eval.absorb(fs_eval);
                 if !eval.stops.is_empty() {
                     stops.extend(eval.stops.clone());
                 }
@@ -137,6 +225,7 @@ pub(crate) fn run<E: FactsEmitter, A: crate::logging::AuditSink>(
                         .filter(|n| n.contains("allowed by policy"))
                         .cloned(),
                 );
+                warnings.extend(eval.warnings.iter().cloned());
                 let (preservation, preservation_supported) =
                     detect_preservation_capabilities(&target.as_path());
                 // Annotate whether backup artifacts are present (payload and/or sidecar)
@@ -174,8 +263,21 @@ pub(crate) fn run<E: FactsEmitter, A: crate::logging::AuditSink>(
                         } else {
                             Some(eval.notes)
                         },
+                        checks: if eval.checks.is_empty() {
+                            None
+                        } else {
+                            Some(eval.checks)
+                        },
+                        preservation_effective: Some(effective_preservation(
+                            api.policy.preservation_tier,
+                            &preservation,
+                        )),
+                        preservation_tier: Some(api.policy.preservation_tier.as_str().to_string()),
                         preservation: Some(preservation),
                         preservation_supported: Some(preservation_supported),
+                        busy: busy_rows(&busy),
+                        mount,
+                        fs_type: fs_type.map(|t| t.name.to_string()),
                         restore_ready: Some(backup_present),
                     },
                 );
@@ -183,6 +285,10 @@ pub(crate) fn run<E: FactsEmitter, A: crate::logging::AuditSink>(
         }
     }
 
+    // Backups land next to their targets; make sure each filesystem can hold them.
+    let space = crate::preflight::space::SpaceBudget::estimate(&api.policy, plan);
+    stops.extend(space.shortfalls());
+
     // Respect explicit override knob for preflight STOP conditions.
     if api.policy.apply.override_preflight {
         stops.clear();
@@ -200,24 +306,54 @@ pub(crate) fn run<E: FactsEmitter, A: crate::logging::AuditSink>(
     } else {
         Some("none")
     };
-    let mut extra = json!({ "rescue_profile": prof });
+    let mut extra = json!({ "rescue_profile": prof, "space_budget": space.to_json() });
+    if !rescue.excluded.is_empty() {
+        if let Some(obj) = extra.as_object_mut() {
+            let ex: Vec<_> = rescue
+                .excluded
+                .iter()
+                .map(|e| json!({ "tool": e.tool, "path": e.path.display().to_string() }))
+                .collect();
+            obj.insert("rescue_excluded".to_string(), json!(ex));
+        }
+    }
+    if let (Some(serde_json::Value::Object(lock)), Some(obj)) = (lock_fields, extra.as_object_mut())
+    {
+        obj.extend(lock);
+    }
+    if !plan_eval.checks.is_empty() {
+        if let Some(obj) = extra.as_object_mut() {
+            obj.insert("checks".to_string(), json!(plan_eval.checks));
+        }
+    }
     if !stops.is_empty() {
         if let Some(obj) = extra.as_object_mut() {
+            // Running out of room for backups has a dedicated reason code.
+            let no_space = stops
+                .iter()
+                .any(|s| s.starts_with(crate::preflight::space::INSUFFICIENT_SPACE));
+            let primary = if no_space {
+                crate::api::errors::ErrorId::E_NO_SPACE
+            } else {
+                crate::api::errors::ErrorId::E_POLICY
+            };
             obj.insert(
                 "error_id".to_string(),
-                json!(crate::api::errors::id_str(
-                    crate::api::errors::ErrorId::E_POLICY
-                )),
+                json!(crate::api::errors::id_str(primary)),
             );
             obj.insert(
                 "exit_code".to_string(),
-                json!(crate::api::errors::exit_code_for(
-                    crate::api::errors::ErrorId::E_POLICY
-                )),
+                json!(crate::api::errors::exit_code_for(primary)),
             );
             let mut chain = vec![crate::api::errors::id_str(
                 crate::api::errors::ErrorId::E_POLICY,
             )];
+            if no_space {
+                chain.insert(
+                    0,
+                    crate::api::errors::id_str(crate::api::errors::ErrorId::E_NO_SPACE),
+                );
+            }
             // Best-effort: co-emit E_OWNERSHIP if any stop references ownership
             if stops.iter().any(|s| s.to_lowercase().contains("ownership")) {
                 chain.push(crate::api::errors::id_str(
@@ -258,4 +394,35 @@ pub(crate) fn run<E: FactsEmitter, A: crate::logging::AuditSink>(
     }
 }
 
+fn busy_rows(procs: &[crate::preflight::busy::BusyProcess]) -> Option<Vec<serde_json::Value>> {
+    if procs.is_empty() {
+        None
+    } else {
+        Some(
+            procs
+                .iter()
+                .map(crate::preflight::busy::BusyProcess::to_json)
+                .collect(),
+        )
+    }
+}
+
+/// Intersect what the platform can preserve for a path with what the configured tier captures.
+fn effective_preservation(
+    tier: crate::policy::config::PreservationTier,
+    supported: &serde_json::Value,
+) -> serde_json::Value {
+    let can = |k: &str| supported.get(k).and_then(serde_json::Value::as_bool) == Some(true);
+    let ext = tier.includes_owner_and_timestamps();
+    let full = tier.includes_xattrs();
+    json!({
+        "owner": ext && can("owner"),
+        "mode": can("mode"),
+        "timestamps": ext && can("timestamps"),
+        "xattrs": full && can("xattrs"),
+        "acls": full && can("acls"),
+        "caps": full && can("caps"),
+    })
+}
+
 // YAML exporter intentionally lives in crate::preflight to avoid duplication.
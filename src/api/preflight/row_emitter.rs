@@ -14,8 +14,14 @@ pub(crate) struct PreflightRowArgs {
     pub policy_ok: Option<bool>,
     pub provenance: Option<Value>,
     pub notes: Option<Vec<String>>,
+    pub checks: Option<Vec<Value>>,
     pub preservation: Option<Value>,
     pub preservation_supported: Option<bool>,
+    pub preservation_tier: Option<String>,
+    pub preservation_effective: Option<Value>,
+    pub busy: Option<Vec<Value>>,
+    pub mount: Option<Value>,
+    pub fs_type: Option<String>,
     pub restore_ready: Option<bool>,
 }
 
@@ -51,8 +57,14 @@ impl<E: FactsEmitter, A: AuditSink> RowEmitter<'_, E, A> {
             policy_ok: args.policy_ok,
             provenance: args.provenance.clone(),
             notes: args.notes.clone(),
+            checks: args.checks.clone(),
             preservation: args.preservation.clone(),
             preservation_supported: args.preservation_supported,
+            preservation_tier: args.preservation_tier.clone(),
+            preservation_effective: args.preservation_effective.clone(),
+            busy: args.busy.clone(),
+            mount: args.mount.clone(),
+            fs_type: args.fs_type.clone(),
             restore_ready: args.restore_ready,
             backup_tag: Some(self.api.policy.backup.tag.clone()),
         };
@@ -77,12 +89,30 @@ impl<E: FactsEmitter, A: AuditSink> RowEmitter<'_, E, A> {
         if let Some(n) = args.notes {
             evt = evt.field("notes", json!(n));
         }
+        if let Some(c) = args.checks {
+            evt = evt.field("checks", json!(c));
+        }
         if let Some(p) = args.preservation {
             evt = evt.field("preservation", p);
         }
         if let Some(ps) = args.preservation_supported {
             evt = evt.field("preservation_supported", json!(ps));
         }
+        if let Some(t) = args.preservation_tier {
+            evt = evt.field("preservation_tier", json!(t));
+        }
+        if let Some(pe) = args.preservation_effective {
+            evt = evt.field("preservation_effective", pe);
+        }
+        if let Some(b) = args.busy {
+            evt = evt.field("busy", json!(b));
+        }
+        if let Some(m) = args.mount {
+            evt = evt.field("mount", m);
+        }
+        if let Some(t) = args.fs_type {
+            evt = evt.field("fs_type", json!(t));
+        }
         // Carry backup tag for traceability per TESTPLAN (long/coreutils/empty tag cases)
         evt = evt.field("backup_tag", json!(self.api.policy.backup.tag.clone()));
         evt.emit_success();
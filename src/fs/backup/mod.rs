@@ -1,11 +1,13 @@
 //! Backup subsystem — idiomatic directory module
 
 pub mod index;
+pub mod preserve;
 pub mod prune;
 pub mod sidecar;
 pub mod snapshot;
 
 pub(crate) use index::*;
+pub use preserve::PreservedMeta;
 pub use prune::*;
 pub(crate) use sidecar::*;
 pub use snapshot::*;
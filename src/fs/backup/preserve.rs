@@ -0,0 +1,225 @@
+//! Metadata preservation for file snapshots.
+//!
+//! `capture` records the metadata dimensions selected by a [`PreservationTier`] into a
+//! [`PreservedMeta`] that is flattened into the backup sidecar. `apply_to_file` re-applies the
+//! captured dimensions onto a restored file.
+//!
+//! Notes:
+//! - Capture is best-effort: dimensions that cannot be read are omitted from the sidecar.
+//! - `apply_to_file` reports the dimensions it could not write (e.g., `chown` as non-root);
+//!   restore fails on them when the sidecar was captured under the `full` tier.
+//! - Extended attributes are stored base64-encoded by name. POSIX ACLs and file capabilities
+//!   are carried as the `system.posix_acl_*` and `security.capability` attributes.
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::os::unix::fs::MetadataExt as _;
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use base64::Engine as _;
+use serde::{Deserialize, Serialize};
+
+use crate::policy::config::PreservationTier;
+
+/// Timestamp stored as seconds and nanoseconds relative to the Unix epoch.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SidecarTime {
+    pub sec: i64,
+    pub nsec: i64,
+}
+
+impl SidecarTime {
+    fn to_system_time(self) -> Option<SystemTime> {
+        let nsec = u32::try_from(self.nsec).ok()?;
+        if let Ok(sec) = u64::try_from(self.sec) {
+            UNIX_EPOCH.checked_add(Duration::new(sec, nsec))
+        } else {
+            let back = Duration::from_secs(self.sec.unsigned_abs());
+            UNIX_EPOCH
+                .checked_sub(back)?
+                .checked_add(Duration::from_nanos(u64::from(nsec)))
+        }
+    }
+}
+
+/// Metadata captured beyond `mode`, according to the configured preservation tier.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PreservedMeta {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preservation_tier: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uid: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gid: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub atime: Option<SidecarTime>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mtime: Option<SidecarTime>,
+    /// Extended attribute name → base64-encoded value.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub xattrs: Option<BTreeMap<String, String>>,
+}
+
+impl PreservedMeta {
+    /// True when nothing beyond `mode` was captured.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.uid.is_none()
+            && self.gid.is_none()
+            && self.atime.is_none()
+            && self.mtime.is_none()
+            && self.xattrs.is_none()
+    }
+}
+
+/// Capture the metadata of `path` selected by `tier`. Does not follow symlinks.
+#[must_use]
+pub fn capture(path: &Path, tier: PreservationTier) -> PreservedMeta {
+    let mut out = PreservedMeta {
+        preservation_tier: Some(tier.as_str().to_string()),
+        ..PreservedMeta::default()
+    };
+    let Ok(md) = std::fs::symlink_metadata(path) else {
+        return out;
+    };
+    if tier.includes_owner_and_timestamps() {
+        out.uid = Some(md.uid());
+        out.gid = Some(md.gid());
+        out.atime = Some(SidecarTime {
+            sec: md.atime(),
+            nsec: md.atime_nsec(),
+        });
+        out.mtime = Some(SidecarTime {
+            sec: md.mtime(),
+            nsec: md.mtime_nsec(),
+        });
+    }
+    if tier.includes_xattrs() {
+        if let Ok(names) = xattr::list(path) {
+            let mut map = BTreeMap::new();
+            for name in names {
+                let Some(key) = name.to_str() else {
+                    continue;
+                };
+                if let Ok(Some(value)) = xattr::get(path, &name) {
+                    map.insert(
+                        key.to_string(),
+                        base64::engine::general_purpose::STANDARD.encode(value),
+                    );
+                }
+            }
+            out.xattrs = Some(map);
+        }
+    }
+    out
+}
+
+/// Re-apply captured metadata onto an open file, then restore `mode`.
+///
+/// Ordering matters: ownership changes clear setuid/setgid bits and file capabilities, so
+/// `chown` runs first, then `chmod`, then xattrs, and timestamps last.
+///
+/// Returns the dimensions that could not be written (`owner`, `mode`, `xattr:<name>`,
+/// `timestamps`); empty when everything was applied.
+pub(crate) fn apply_to_file(file: &File, meta: &PreservedMeta, mode: Option<u32>) -> Vec<String> {
+    use std::os::unix::fs::PermissionsExt as _;
+    use xattr::FileExt as _;
+
+    let mut failed = Vec::new();
+    if (meta.uid.is_some() || meta.gid.is_some())
+        && std::os::unix::fs::fchown(file, meta.uid, meta.gid).is_err()
+    {
+        failed.push("owner".to_string());
+    }
+    if let Some(m) = mode {
+        if file
+            .set_permissions(std::fs::Permissions::from_mode(m))
+            .is_err()
+        {
+            failed.push("mode".to_string());
+        }
+    }
+    if let Some(xattrs) = &meta.xattrs {
+        for (name, b64) in xattrs {
+            let applied = base64::engine::general_purpose::STANDARD
+                .decode(b64)
+                .is_ok_and(|value| file.set_xattr(name, &value).is_ok());
+            if !applied {
+                failed.push(format!("xattr:{name}"));
+            }
+        }
+    }
+    let mut times = std::fs::FileTimes::new();
+    let mut any_time = false;
+    if let Some(t) = meta.atime.and_then(SidecarTime::to_system_time) {
+        times = times.set_accessed(t);
+        any_time = true;
+    }
+    if let Some(t) = meta.mtime.and_then(SidecarTime::to_system_time) {
+        times = times.set_modified(t);
+        any_time = true;
+    }
+    if any_time && file.set_times(times).is_err() {
+        failed.push("timestamps".to_string());
+    }
+    failed
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used, clippy::panic)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn basic_tier_captures_nothing_beyond_mode() {
+        let td = tempfile::tempdir().unwrap();
+        let p = td.path().join("f");
+        std::fs::write(&p, b"x").unwrap();
+        let m = capture(&p, PreservationTier::Basic);
+        assert!(m.is_empty());
+        assert_eq!(m.preservation_tier.as_deref(), Some("basic"));
+    }
+
+    #[test]
+    fn extended_tier_round_trips_timestamps() {
+        let td = tempfile::tempdir().unwrap();
+        let p = td.path().join("f");
+        std::fs::write(&p, b"x").unwrap();
+        let f = File::options().write(true).open(&p).unwrap();
+        let old = UNIX_EPOCH + Duration::new(1_000_000_000, 123);
+        f.set_times(
+            std::fs::FileTimes::new()
+                .set_modified(old)
+                .set_accessed(old),
+        )
+        .unwrap();
+        let m = capture(&p, PreservationTier::Extended);
+        assert_eq!(m.mtime.unwrap().sec, 1_000_000_000);
+        assert!(m.uid.is_some() && m.gid.is_some());
+
+        let q = td.path().join("g");
+        std::fs::write(&q, b"y").unwrap();
+        let failed = apply_to_file(&File::open(&q).unwrap(), &m, Some(0o640));
+        assert!(failed.is_empty(), "unexpected failures: {failed:?}");
+        let md = std::fs::metadata(&q).unwrap();
+        assert_eq!(md.mtime(), 1_000_000_000);
+        assert_eq!(md.mtime_nsec(), 123);
+        assert_eq!(md.mode() & 0o777, 0o640);
+    }
+
+    #[test]
+    fn unwritable_xattr_is_reported() {
+        let td = tempfile::tempdir().unwrap();
+        let p = td.path().join("f");
+        std::fs::write(&p, b"x").unwrap();
+        let mut xattrs = BTreeMap::new();
+        // Only the kernel-defined namespaces are writable; this one never is.
+        xattrs.insert("bogus.ns".to_string(), "eA==".to_string());
+        let m = PreservedMeta {
+            xattrs: Some(xattrs),
+            ..PreservedMeta::default()
+        };
+        let failed = apply_to_file(&File::open(&p).unwrap(), &m, None);
+        assert_eq!(failed, vec!["xattr:bogus.ns".to_string()]);
+    }
+}
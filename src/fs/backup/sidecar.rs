@@ -11,6 +11,9 @@ pub(crate) struct BackupSidecar {
     pub(crate) mode: Option<String>, // octal string for file, e.g. "100644"
     #[serde(skip_serializing_if = "Option::is_none")]
     pub(crate) payload_hash: Option<String>, // sha256 of payload for v2
+    // owner/timestamps/xattrs captured per `Policy.preservation_tier` (absent on older sidecars)
+    #[serde(flatten)]
+    pub(crate) preserved: super::preserve::PreservedMeta,
 }
 
 pub(crate) fn sidecar_path_for_backup(backup: &Path) -> PathBuf {
@@ -6,7 +6,9 @@ use std::fs;
 use std::os::unix;
 use std::os::unix::fs::PermissionsExt as _;
 
+use super::preserve::PreservedMeta;
 use super::sidecar::{write_sidecar, BackupSidecar};
+use crate::policy::config::PreservationTier;
 
 /// Generate a unique backup path for a target file (includes a timestamp).
 /// Public so callers (preflight/tests) can compute expected names.
@@ -33,17 +35,28 @@ pub fn backup_path_with_tag(target: &Path, tag: &str) -> std::path::PathBuf {
 /// - If target is a regular file: copy bytes to a timestamped backup and record mode in sidecar.
 /// - If target is a symlink: create a symlink backup pointing to current dest and write sidecar with `prior_dest`.
 /// - If target is absent: create a tombstone payload and sidecar with `prior_kind="none"`.
+pub fn create_snapshot(target: &Path, backup_tag: &str) -> std::io::Result<()> {
+    create_snapshot_with_tier(target, backup_tag, PreservationTier::Basic)
+}
+
+/// Create a snapshot like [`create_snapshot`], additionally recording the metadata selected by
+/// `tier` (owner, timestamps, xattrs/ACLs/capabilities) into the sidecar for regular files.
+///
+/// # Errors
+///
+/// Returns an IO error if the snapshot creation fails.
 #[allow(
     clippy::too_many_lines,
     reason = "Snapshot creation handles multiple target states (file/symlink/absent) and TOCTOU-safe flows; splitting would hurt clarity and atomicity"
 )]
-pub fn create_snapshot(target: &Path, backup_tag: &str) -> std::io::Result<()> {
+pub fn create_snapshot_with_tier(
+    target: &Path,
+    backup_tag: &str,
+    tier: PreservationTier,
+) -> std::io::Result<()> {
     let metadata = fs::symlink_metadata(target);
     let existed = metadata.is_ok();
-    let is_symlink = metadata
-        .as_ref()
-        .map(|m| m.file_type().is_symlink())
-        .unwrap_or(false);
+    let is_symlink = metadata.as_ref().is_ok_and(|m| m.file_type().is_symlink());
 
     if is_symlink {
         let current_dest = fs::read_link(target).ok();
@@ -79,6 +92,7 @@ pub fn create_snapshot(target: &Path, backup_tag: &str) -> std::io::Result<()> {
                 prior_dest: Some(curr.display().to_string()),
                 mode: None,
                 payload_hash: None,
+                preserved: PreservedMeta::default(),
             };
             write_sidecar(&backup, &sc)
                 .map_err(|e| std::io::Error::other(format!("sidecar write failed: {e}")))?;
@@ -90,6 +104,8 @@ pub fn create_snapshot(target: &Path, backup_tag: &str) -> std::io::Result<()> {
 
     if existed {
         if let Ok(ref meta) = metadata {
+            // Capture tiered metadata before reading the payload (reading may bump atime)
+            let preserved = super::preserve::capture(target, tier);
             // Copy to backup within the same directory using TOCTOU-safe fds
             let parent = target.parent().unwrap_or_else(|| Path::new("."));
             let dirfd = open_dir_nofollow(parent)?;
@@ -164,6 +180,7 @@ pub fn create_snapshot(target: &Path, backup_tag: &str) -> std::io::Result<()> {
                 prior_dest: None,
                 mode: Some(format!("{mode:o}")),
                 payload_hash,
+                preserved,
             };
             write_sidecar(&backup_pb, &sc)
                 .map_err(|e| std::io::Error::other(format!("sidecar write failed: {e}")))?;
@@ -201,6 +218,7 @@ pub fn create_snapshot(target: &Path, backup_tag: &str) -> std::io::Result<()> {
         prior_dest: None,
         mode: None,
         payload_hash: None,
+        preserved: PreservedMeta::default(),
     };
     write_sidecar(&backup, &sc)
         .map_err(|e| std::io::Error::other(format!("sidecar write failed: {e}")))?;
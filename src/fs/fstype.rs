@@ -0,0 +1,148 @@
+//! Filesystem type detection via `statfs(2)` `f_type`.
+//!
+//! Rename atomicity and fsync durability are only as good as the filesystem underneath:
+//! network filesystems depend on the server, FUSE on the userspace daemon, overlayfs copies a
+//! lower-layer file up on first write, and tmpfs does not survive a reboot. [`FsClass`] groups
+//! magic numbers into the classes policy can act on.
+use std::path::Path;
+
+/// Coarse class of a filesystem for safety policy.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FsClass {
+    /// Local disk filesystems (ext4, xfs, btrfs, ...) and anything unrecognized.
+    Local,
+    /// NFS, SMB/CIFS, Ceph, 9p, AFS.
+    Network,
+    /// FUSE filesystems.
+    Fuse,
+    /// overlayfs.
+    Overlay,
+    /// tmpfs/ramfs.
+    Tmpfs,
+}
+
+impl FsClass {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            FsClass::Local => "local",
+            FsClass::Network => "network",
+            FsClass::Fuse => "fuse",
+            FsClass::Overlay => "overlay",
+            FsClass::Tmpfs => "tmpfs",
+        }
+    }
+
+    /// Why this class weakens Switchyard's guarantees; `None` for local filesystems.
+    #[must_use]
+    pub const fn caveat(self) -> Option<&'static str> {
+        match self {
+            FsClass::Local => None,
+            FsClass::Network => Some("rename atomicity and fsync durability depend on the server"),
+            FsClass::Fuse => Some("rename and fsync semantics depend on the userspace filesystem"),
+            FsClass::Overlay => Some("replacing a lower-layer file triggers copy-up"),
+            FsClass::Tmpfs => Some("contents and backups do not survive a reboot"),
+        }
+    }
+}
+
+/// Filesystem type of a path.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FsType {
+    /// `statfs` `f_type` magic.
+    pub magic: u64,
+    /// Short name (e.g. `ext4`, `nfs`); `unknown` for unrecognized magics.
+    pub name: &'static str,
+    pub class: FsClass,
+}
+
+const KNOWN: &[(u64, &str, FsClass)] = &[
+    (0xEF53, "ext4", FsClass::Local),
+    (0x5846_5342, "xfs", FsClass::Local),
+    (0x9123_683E, "btrfs", FsClass::Local),
+    (0x2FC1_2FC1, "zfs", FsClass::Local),
+    (0xF2F5_2010, "f2fs", FsClass::Local),
+    (0x7371_7368, "squashfs", FsClass::Local),
+    (0x4D44, "vfat", FsClass::Local),
+    (0x0102_1994, "tmpfs", FsClass::Tmpfs),
+    (0x8584_58F6, "ramfs", FsClass::Tmpfs),
+    (0x794C_7630, "overlay", FsClass::Overlay),
+    (0x6573_5546, "fuse", FsClass::Fuse),
+    (0x6969, "nfs", FsClass::Network),
+    (0xFF53_4D42, "cifs", FsClass::Network),
+    (0xFE53_4D42, "smb2", FsClass::Network),
+    (0x517B, "smb", FsClass::Network),
+    (0x00C3_6400, "ceph", FsClass::Network),
+    (0x0102_1997, "9p", FsClass::Network),
+    (0x6B41_4653, "afs", FsClass::Network),
+];
+
+impl FsType {
+    /// Classify a `statfs` magic.
+    #[must_use]
+    pub fn from_magic(magic: u64) -> Self {
+        KNOWN.iter().find(|(m, _, _)| *m == magic).map_or(
+            FsType {
+                magic,
+                name: "unknown",
+                class: FsClass::Local,
+            },
+            |&(magic, name, class)| FsType { magic, name, class },
+        )
+    }
+}
+
+/// Filesystem type of `path` (symlinks followed).
+#[must_use]
+pub fn fs_type_of(path: &Path) -> Option<FsType> {
+    let st = rustix::fs::statfs(path).ok()?;
+    #[allow(
+        clippy::useless_conversion,
+        clippy::unnecessary_fallible_conversions,
+        reason = "f_type width and signedness differ between targets"
+    )]
+    let magic = u64::try_from(st.f_type).ok()?;
+    Some(FsType::from_magic(magic & 0xFFFF_FFFF))
+}
+
+/// Filesystem type where `target` itself lives: the target when it is a regular file or
+/// directory (which may be a mount of its own), otherwise its nearest existing ancestor, so a
+/// symlink target is not resolved to its destination and a not-yet-created target (as in a
+/// `DryRun`) reports the filesystem it will be created on.
+#[must_use]
+pub fn fs_type_at(target: &Path) -> Option<FsType> {
+    if let Ok(md) = std::fs::symlink_metadata(target) {
+        if !md.file_type().is_symlink() {
+            return fs_type_of(target);
+        }
+    }
+    target
+        .ancestors()
+        .skip(1)
+        .find(|p| !p.as_os_str().is_empty() && p.exists())
+        .map_or_else(|| fs_type_of(Path::new("/")), fs_type_of)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used, clippy::panic)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn magics_classify() {
+        assert_eq!(FsType::from_magic(0x6969).class, FsClass::Network);
+        assert_eq!(FsType::from_magic(0x794C_7630).name, "overlay");
+        assert_eq!(FsType::from_magic(0x1234).class, FsClass::Local);
+        assert!(fs_type_of(Path::new("/")).is_some());
+    }
+
+    #[test]
+    fn missing_target_reports_nearest_existing_ancestor() {
+        let td = tempfile::tempdir().unwrap();
+        let missing = td.path().join("usr/bin/ls");
+        assert_eq!(
+            fs_type_at(&missing).map(|t| t.name),
+            fs_type_of(td.path()).map(|t| t.name)
+        );
+    }
+}
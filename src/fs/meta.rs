@@ -5,12 +5,16 @@
 //! - `resolve_symlink_target(path)`: resolve symlink target to an absolute path
 //! - `detect_preservation_capabilities(path)`: detect which preservation dimensions are likely
 //!   supported on the current platform and under current privileges.
+//! - `inode_flags(path)`: read immutable/append-only/nodump inode flags via `FS_IOC_GETFLAGS`.
 //!
 //! Notes:
 //! - Owner preservation is reported true only when the effective UID is 0 (root).
 //! - xattrs support is probed via the `xattr` crate by attempting to list attributes.
 //! - Timestamps and mode are reported true when metadata is readable for the path.
-//! - ACLs and capabilities are conservatively reported false.
+//! - ACLs (`system.posix_acl_*` xattrs) are reported true when xattrs are supported and the
+//!   caller is root or owns the path.
+//! - Capabilities (`security.capability`) are reported true when xattrs are supported and the
+//!   caller is root (writing file capabilities requires `CAP_SETFCAP`).
 use sha2::{Digest, Sha256};
 use std::path::{Path, PathBuf};
 
@@ -83,8 +87,8 @@ pub fn kind_of(path: &Path) -> &'static str {
 /// - mode (chmod generally possible if we can access the file)
 /// - timestamps (utimensat typically available; report true if file exists)
 /// - xattrs (Linux extended attributes via getxattr syscall presence; best-effort probe)
-/// - acls (no portable check; report false)
-/// - caps (Linux file capabilities; report false unless libcap is present — we report false)
+/// - acls (POSIX ACLs are xattrs; require xattr support and root or ownership)
+/// - caps (Linux file capabilities are xattrs; require xattr support and root)
 #[must_use]
 pub fn detect_preservation_capabilities(path: &Path) -> (serde_json::Value, bool) {
     // Defaults: everything false.
@@ -95,11 +99,10 @@ pub fn detect_preservation_capabilities(path: &Path) -> (serde_json::Value, bool
     // xattrs varies by platform; start false and probe only where supported.
     let mut xattrs = false;
 
-    // Not (yet) probed in this crate; conservative false.
-    let acls = false;
-    let caps = false;
+    let mut acls = false;
+    let mut caps = false;
 
-    if std::fs::symlink_metadata(path).is_ok() {
+    if let Ok(md) = std::fs::symlink_metadata(path) {
         // If we can stat the node, assume we can preserve mode & timestamps.
         mode = true;
         timestamps = true;
@@ -117,9 +120,19 @@ pub fn detect_preservation_capabilities(path: &Path) -> (serde_json::Value, bool
             target_os = "openbsd"
         ))]
         {
-            xattrs = xattr::list(path).map(|_| true).unwrap_or(false);
+            xattrs = xattr::list(path).is_ok();
         }
         // Other targets: leave xattrs = false (conservative).
+
+        #[cfg(target_os = "linux")]
+        {
+            use std::os::unix::fs::MetadataExt as _;
+            let is_owner = rustix::process::geteuid().as_raw() == md.uid();
+            acls = xattrs && (owner || is_owner);
+            caps = xattrs && owner;
+        }
+        #[cfg(not(target_os = "linux"))]
+        let _ = &md;
     }
 
     let preservation = json!({
@@ -147,3 +160,65 @@ fn effective_uid_is_root() -> bool {
         false
     }
 }
+
+/// Inode attribute flags (as shown by `lsattr`) that affect whether a target can be replaced.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct InodeFlags {
+    /// `FS_IMMUTABLE_FL` (`chattr +i`): cannot be unlinked, renamed over, or written.
+    pub immutable: bool,
+    /// `FS_APPEND_FL` (`chattr +a`): may only be appended to; cannot be unlinked or renamed over.
+    pub append_only: bool,
+    /// `FS_NODUMP_FL` (`chattr +d`): excluded from dump(8) backups; informational.
+    pub nodump: bool,
+}
+
+/// Read inode flags for `path` with `FS_IOC_GETFLAGS`, without spawning `lsattr`.
+///
+/// Returns `Ok(None)` when `path` is missing or is a symlink (symlinks carry no inode flags
+/// of their own). The ioctl requires a readable fd (`O_PATH` fds are rejected with `EBADF`),
+/// so the node is opened `O_RDONLY | O_NOFOLLOW | O_NONBLOCK`.
+///
+/// # Errors
+///
+/// Returns `ErrorKind::Unsupported` when the filesystem does not implement the ioctl, or the
+/// underlying IO error when the node cannot be opened.
+pub fn inode_flags(path: &Path) -> std::io::Result<Option<InodeFlags>> {
+    let Ok(md) = std::fs::symlink_metadata(path) else {
+        return Ok(None);
+    };
+    if md.file_type().is_symlink() {
+        return Ok(None);
+    }
+    #[cfg(target_os = "linux")]
+    {
+        use rustix::fs::{ioctl_getflags, open, IFlags, Mode, OFlags};
+        use rustix::io::Errno;
+        let fd = open(
+            path,
+            OFlags::RDONLY | OFlags::NOFOLLOW | OFlags::NONBLOCK | OFlags::CLOEXEC,
+            Mode::empty(),
+        )
+        .map_err(|e| std::io::Error::from_raw_os_error(e.raw_os_error()))?;
+        match ioctl_getflags(&fd) {
+            Ok(f) => Ok(Some(InodeFlags {
+                immutable: f.contains(IFlags::IMMUTABLE),
+                append_only: f.contains(IFlags::APPEND),
+                nodump: f.contains(IFlags::NODUMP),
+            })),
+            Err(e) if e == Errno::NOTTY || e == Errno::OPNOTSUPP || e == Errno::INVAL => {
+                Err(std::io::Error::new(
+                    std::io::ErrorKind::Unsupported,
+                    "FS_IOC_GETFLAGS not supported by filesystem",
+                ))
+            }
+            Err(e) => Err(std::io::Error::from_raw_os_error(e.raw_os_error())),
+        }
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "inode flags are only probed on Linux",
+        ))
+    }
+}
@@ -7,6 +7,7 @@
 
 pub mod atomic;
 pub mod backup;
+pub mod fstype;
 pub mod meta;
 pub mod mount;
 pub mod paths;
@@ -1,6 +1,13 @@
 //! Filesystem mount inspection and policy helpers.
+//!
+//! Mount topology comes from `/proc/self/mountinfo`, which (unlike `/proc/self/mounts`) carries
+//! mount ids, the bind-mount root, and per-mount vs. superblock options. Fields are unescaped
+//! (`\040` etc.), and the owning mount of a path is the longest matching mount point, with later
+//! (stacked) mounts winning ties.
 
-use crate::types::{MountError, MountFlags};
+use crate::types::{MountEntry, MountError, MountFlags, OverlayLayers};
+use serde_json::{json, Value};
+use std::os::unix::fs::MetadataExt as _;
 use std::path::{Path, PathBuf};
 
 pub trait MountInspector {
@@ -10,9 +17,21 @@ pub trait MountInspector {
     ///
     /// Returns a `MountError` if mount information cannot be determined.
     fn flags_for(&self, path: &Path) -> Result<MountFlags, MountError>;
+
+    /// Get the mount a path lives on.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `MountError` if mount information cannot be determined. The default
+    /// implementation always does, for inspectors that only know flags.
+    fn mount_for(&self, path: &Path) -> Result<MountEntry, MountError> {
+        let _ = path;
+        Err(MountError::Unknown)
+    }
 }
 
-/// Production inspector. Prefer kernel syscalls when available; fall back to parsing /proc/self/mounts.
+/// Production inspector. Prefer kernel syscalls when available; fall back to parsing
+/// `/proc/self/mountinfo`.
 #[derive(Debug, Copy, Clone)]
 pub struct ProcStatfsInspector;
 
@@ -22,57 +41,254 @@ impl ProcStatfsInspector {
         match rustix::fs::statvfs(path) {
             Ok(vfs) => {
                 let flags = vfs.f_flag;
-                let read_only = flags.contains(rustix::fs::StatVfsMountFlags::RDONLY);
-                let no_exec = flags.contains(rustix::fs::StatVfsMountFlags::NOEXEC);
-                Ok(MountFlags { read_only, no_exec })
+                Ok(MountFlags {
+                    read_only: flags.contains(rustix::fs::StatVfsMountFlags::RDONLY),
+                    no_exec: flags.contains(rustix::fs::StatVfsMountFlags::NOEXEC),
+                    no_suid: flags.contains(rustix::fs::StatVfsMountFlags::NOSUID),
+                    no_dev: flags.contains(rustix::fs::StatVfsMountFlags::NODEV),
+                })
             }
             Err(_) => Err(MountError::Unknown),
         }
     }
-    fn parse_proc_mounts(path: &Path) -> Result<MountFlags, MountError> {
-        // Canonicalize best-effort; if it fails, still proceed with the raw path
-        let p = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
-        let content =
-            std::fs::read_to_string("/proc/self/mounts").map_err(|_| MountError::Unknown)?;
-        let mut best: Option<(PathBuf, String)> = None;
-        for line in content.lines() {
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            if parts.len() < 4 {
-                continue;
+}
+
+impl MountInspector for ProcStatfsInspector {
+    fn flags_for(&self, path: &Path) -> Result<MountFlags, MountError> {
+        // Prefer kernel statvfs; fall back to mountinfo on error
+        Self::flags_via_statvfs(path).or_else(|_| self.mount_for(path).map(|m| m.flags))
+    }
+
+    fn mount_for(&self, path: &Path) -> Result<MountEntry, MountError> {
+        MountTable::read()?
+            .find(path)
+            .cloned()
+            .ok_or(MountError::Unknown)
+    }
+}
+
+/// Parsed mount table of the calling process.
+#[derive(Debug, Clone, Default)]
+pub struct MountTable {
+    pub entries: Vec<MountEntry>,
+}
+
+impl MountTable {
+    /// Read `/proc/self/mountinfo`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `MountError::Unknown` if the file cannot be read.
+    pub fn read() -> Result<Self, MountError> {
+        let text =
+            std::fs::read_to_string("/proc/self/mountinfo").map_err(|_| MountError::Unknown)?;
+        Ok(Self::parse(&text))
+    }
+
+    /// Parse mountinfo text; malformed lines are skipped.
+    #[must_use]
+    pub fn parse(text: &str) -> Self {
+        MountTable {
+            entries: text.lines().filter_map(parse_mountinfo_line).collect(),
+        }
+    }
+
+    /// The mount `path` lives on. The final component is not followed, so a symlink is
+    /// attributed to the mount holding the link itself; missing trailing components are
+    /// resolved against their nearest existing ancestor.
+    #[must_use]
+    pub fn find(&self, path: &Path) -> Option<&MountEntry> {
+        let p = resolve_lexically(path);
+        let mut best: Option<&MountEntry> = None;
+        for m in &self.entries {
+            if p.starts_with(&m.mount_point)
+                && best.is_none_or(|b| {
+                    m.mount_point.as_os_str().len() >= b.mount_point.as_os_str().len()
+                })
+            {
+                best = Some(m);
             }
-            let mnt = parts.get(1).map(PathBuf::from).ok_or(MountError::Unknown)?;
-            if p.starts_with(&mnt) {
-                let opts = parts
-                    .get(3)
-                    .ok_or(MountError::Unknown)?
-                    .to_ascii_lowercase();
-                match &best {
-                    None => best = Some((mnt, opts)),
-                    Some((b, _)) => {
-                        if mnt.as_os_str().len() > b.as_os_str().len() {
-                            best = Some((mnt, opts));
-                        }
-                    }
-                }
+        }
+        best
+    }
+}
+
+/// Canonicalize the parent directory (or nearest existing ancestor) and re-attach the rest.
+fn resolve_lexically(path: &Path) -> PathBuf {
+    let mut base = path.parent().map(Path::to_path_buf);
+    let mut tail: Vec<std::ffi::OsString> = path
+        .file_name()
+        .map(|f| vec![f.to_owned()])
+        .unwrap_or_default();
+    while let Some(b) = base {
+        if let Ok(c) = b.canonicalize() {
+            let mut out = c;
+            for t in tail.iter().rev() {
+                out.push(t);
             }
+            return out;
         }
-        if let Some((_mnt, opts)) = best {
-            let has_rw = opts.split(',').any(|o| o == "rw");
-            let noexec = opts.split(',').any(|o| o == "noexec");
-            Ok(MountFlags {
-                read_only: !has_rw,
-                no_exec: noexec,
+        if let Some(f) = b.file_name() {
+            tail.push(f.to_owned());
+        }
+        base = b.parent().map(Path::to_path_buf);
+    }
+    path.to_path_buf()
+}
+
+/// Undo the octal escaping the kernel applies to mountinfo fields (`\040` for space, etc.).
+/// Escapes above `\377` are not bytes and are kept verbatim.
+fn unescape(field: &str) -> String {
+    let b = field.as_bytes();
+    let mut out = Vec::with_capacity(b.len());
+    let mut i = 0;
+    while let Some(&c) = b.get(i) {
+        let escaped = (c == b'\\')
+            .then(|| b.get(i + 1..i + 4))
+            .flatten()
+            .and_then(|digits| {
+                digits.iter().try_fold(0u32, |acc, &d| {
+                    char::from(d).to_digit(8).map(|v| acc * 8 + v)
+                })
             })
+            .and_then(|v| u8::try_from(v).ok());
+        if let Some(byte) = escaped {
+            out.push(byte);
+            i += 4;
         } else {
-            Err(MountError::Unknown)
+            out.push(c);
+            i += 1;
         }
     }
+    String::from_utf8_lossy(&out).into_owned()
 }
 
-impl MountInspector for ProcStatfsInspector {
-    fn flags_for(&self, path: &Path) -> Result<MountFlags, MountError> {
-        // Prefer kernel statvfs; fall back to /proc parsing on error
-        Self::flags_via_statvfs(path).or_else(|_| Self::parse_proc_mounts(path))
+/// Parse one line:
+/// `id parent major:minor root mount_point mount_opts [optional...] - fstype source super_opts`.
+fn parse_mountinfo_line(line: &str) -> Option<MountEntry> {
+    let (pre, post) = line.split_once(" - ")?;
+    let mut it = pre.split(' ');
+    let mount_id = it.next()?.parse().ok()?;
+    let parent_id = it.next()?.parse().ok()?;
+    let (maj, min) = it.next()?.split_once(':')?;
+    let root = PathBuf::from(unescape(it.next()?));
+    let mount_point = PathBuf::from(unescape(it.next()?));
+    let mount_opts = it.next()?;
+    let mut post = post.split(' ');
+    let fs_type = unescape(post.next()?);
+    let source = unescape(post.next().unwrap_or_default());
+    let super_opts = post.next().unwrap_or_default();
+
+    let has = |opts: &str, o: &str| opts.split(',').any(|x| x == o);
+    let flags = MountFlags {
+        read_only: has(mount_opts, "ro") || has(super_opts, "ro"),
+        no_exec: has(mount_opts, "noexec"),
+        no_suid: has(mount_opts, "nosuid"),
+        no_dev: has(mount_opts, "nodev"),
+    };
+    let overlay = (fs_type == "overlay").then(|| parse_overlay_opts(super_opts));
+    Some(MountEntry {
+        mount_id,
+        parent_id,
+        major: maj.parse().ok()?,
+        minor: min.parse().ok()?,
+        root,
+        mount_point,
+        fs_type,
+        source,
+        flags,
+        overlay,
+    })
+}
+
+/// Extract overlay layers; `lowerdir` is colon-separated with `\:` escaping literal colons.
+fn parse_overlay_opts(super_opts: &str) -> OverlayLayers {
+    let mut layers = OverlayLayers::default();
+    for opt in split_unescaped(super_opts, ',') {
+        if let Some(v) = opt.strip_prefix("upperdir=") {
+            layers.upper = Some(PathBuf::from(unescape(v)));
+        } else if let Some(v) = opt.strip_prefix("workdir=") {
+            layers.work = Some(PathBuf::from(unescape(v)));
+        } else if let Some(v) = opt.strip_prefix("lowerdir=") {
+            layers.lower = split_unescaped(v, ':')
+                .into_iter()
+                .map(|l| PathBuf::from(unescape(&l.replace("\\:", ":"))))
+                .collect();
+        }
+    }
+    layers
+}
+
+fn split_unescaped(s: &str, sep: char) -> Vec<&str> {
+    let mut out = Vec::new();
+    let mut start = 0;
+    let mut prev_backslash = false;
+    for (i, c) in s.char_indices() {
+        if c == sep && !prev_backslash {
+            out.push(&s[start..i]);
+            start = i + c.len_utf8();
+        }
+        prev_backslash = c == '\\' && !prev_backslash;
+    }
+    out.push(&s[start..]);
+    out
+}
+
+/// Mount facts for a swap target, as surfaced in preflight rows.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TargetMount {
+    pub entry: MountEntry,
+    /// Whether the target and its backup location (the parent directory) are on the same mount
+    /// and device. `false` predicts EXDEV/EBUSY on rename, e.g. for a bind-mounted file.
+    /// `None` when the target does not exist.
+    pub backup_same_dev: Option<bool>,
+}
+
+impl TargetMount {
+    /// Analyze `target` against `table`.
+    #[must_use]
+    pub fn analyze(table: &MountTable, target: &Path) -> Option<Self> {
+        let entry = table.find(target)?.clone();
+        let parent = target.parent().unwrap_or_else(|| Path::new("/"));
+        let backup_same_dev = std::fs::symlink_metadata(target).ok().map(|md| {
+            let same_dev = std::fs::metadata(parent).is_ok_and(|pm| pm.dev() == md.dev());
+            let same_mount = table
+                .find(&parent.join("."))
+                .is_some_and(|pm| pm.mount_id == entry.mount_id);
+            same_dev && same_mount
+        });
+        Some(TargetMount {
+            entry,
+            backup_same_dev,
+        })
+    }
+
+    /// Structured record for preflight rows.
+    #[must_use]
+    pub fn to_json(&self) -> Value {
+        let e = &self.entry;
+        let mut v = json!({
+            "mount_id": e.mount_id,
+            "mount_point": e.mount_point.display().to_string(),
+            "fs_type": e.fs_type,
+            "source": e.source,
+            "read_only": e.flags.read_only,
+            "no_exec": e.flags.no_exec,
+            "no_suid": e.flags.no_suid,
+            "no_dev": e.flags.no_dev,
+            "bind": e.is_bind(),
+            "backup_same_dev": self.backup_same_dev,
+        });
+        if let (Some(o), Some(obj)) = (&e.overlay, v.as_object_mut()) {
+            obj.insert(
+                "overlay".to_string(),
+                json!({
+                    "upper": o.upper.as_ref().map(|p| p.display().to_string()),
+                    "lower": o.lower.iter().map(|p| p.display().to_string()).collect::<Vec<_>>(),
+                }),
+            );
+        }
+        v
     }
 }
 
@@ -112,6 +328,7 @@ mod tests {
             flags: Ok(MountFlags {
                 read_only: false,
                 no_exec: false,
+                ..MountFlags::default()
             }),
         };
         assert!(ensure_rw_exec(&ins, Path::new("/tmp")).is_ok());
@@ -123,6 +340,7 @@ mod tests {
             flags: Ok(MountFlags {
                 read_only: true,
                 no_exec: false,
+                ..MountFlags::default()
             }),
         };
         assert!(ensure_rw_exec(&ins1, Path::new("/tmp")).is_err());
@@ -130,6 +348,7 @@ mod tests {
             flags: Ok(MountFlags {
                 read_only: false,
                 no_exec: true,
+                ..MountFlags::default()
             }),
         };
         assert!(ensure_rw_exec(&ins2, Path::new("/tmp")).is_err());
@@ -142,4 +361,52 @@ mod tests {
         };
         assert!(ensure_rw_exec(&ins, Path::new("/tmp")).is_err());
     }
+
+    const MOUNTINFO: &str = r"22 1 8:2 / / rw,relatime shared:1 - ext4 /dev/sda2 rw
+30 22 0:25 / /mnt/my\040disk rw,nosuid,nodev,noexec - tmpfs tmpfs rw,size=1024k
+31 22 8:2 /srv/data /data ro,relatime - ext4 /dev/sda2 rw
+40 22 0:40 / /var/lib/ctr rw - overlay overlay rw,lowerdir=/l1:/l\:2,upperdir=/up,workdir=/wk
+41 30 0:26 / /mnt/my\040disk rw - tmpfs tmpfs rw
+";
+
+    #[test]
+    fn mountinfo_fields_escapes_and_bind_roots() {
+        let t = MountTable::parse(MOUNTINFO);
+        assert_eq!(t.entries.len(), 5);
+        let tmp = &t.entries[1];
+        assert_eq!(tmp.mount_point, PathBuf::from("/mnt/my disk"));
+        assert!(tmp.flags.no_suid && tmp.flags.no_dev && tmp.flags.no_exec);
+        assert!(!tmp.flags.read_only);
+        let bind = &t.entries[2];
+        assert!(bind.is_bind() && bind.flags.read_only);
+        assert_eq!((bind.major, bind.minor), (8, 2));
+        assert!(!t.entries[0].is_bind());
+    }
+
+    #[test]
+    fn out_of_range_and_trailing_escapes() {
+        let t = MountTable::parse("50 22 0:50 / /mnt/a\\400b\\134\\040 rw - tmpfs tmpfs rw\n");
+        assert_eq!(t.entries.len(), 1);
+        assert_eq!(t.entries[0].mount_point, PathBuf::from("/mnt/a\\400b\\ "));
+        assert_eq!(unescape("\\777\\04"), "\\777\\04");
+    }
+
+    #[test]
+    fn overlay_layers_are_split() {
+        let t = MountTable::parse(MOUNTINFO);
+        let o = t.entries[3].overlay.clone().unwrap_or_default();
+        assert_eq!(o.upper, Some(PathBuf::from("/up")));
+        assert_eq!(o.work, Some(PathBuf::from("/wk")));
+        assert_eq!(o.lower, vec![PathBuf::from("/l1"), PathBuf::from("/l:2")]);
+    }
+
+    #[test]
+    fn longest_prefix_and_stacked_mounts_win() {
+        let t = MountTable::parse(MOUNTINFO);
+        // Paths under a nonexistent prefix are matched lexically.
+        let id = |p: &str| t.find(Path::new(p)).map(|m| m.mount_id);
+        assert_eq!(id("/nonexistent-switchyard/x"), Some(22));
+        assert_eq!(id("/mnt/my disk/f"), Some(41));
+        assert_eq!(id("/database"), Some(22));
+    }
 }
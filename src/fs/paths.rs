@@ -1,13 +1,14 @@
 //! Path utilities for Switchyard filesystem operations.
 
-use std::path::Path;
+use std::ffi::OsString;
+use std::path::{Component, Path, PathBuf};
 
 /// Validate path to prevent directory traversal attacks.
 /// This is a conservative check used before performing mutations.
 #[must_use]
 pub fn is_safe_path(path: &Path) -> bool {
     for component in path.components() {
-        if let std::path::Component::ParentDir = component {
+        if let Component::ParentDir = component {
             return false;
         }
     }
@@ -18,3 +19,101 @@ pub fn is_safe_path(path: &Path) -> bool {
     }
     true
 }
+
+/// Symlinks followed by [`resolve_in_root`] before giving up, as `MAXSYMLINKS` on Linux.
+const MAX_SYMLINKS: usize = 40;
+
+/// Resolve `path` as if `root` were `/`, the way a process chrooted at `root` would.
+///
+/// Leading `/` in `path` and in absolute symlink targets refers to `root`, and `..` never climbs
+/// above it, so links in a staged root are never judged against the host filesystem. Returns
+/// the resolved host path when every component exists, `None` otherwise (including symlink
+/// loops).
+#[must_use]
+pub fn resolve_in_root(root: &Path, path: &Path) -> Option<PathBuf> {
+    fn push_rev(stack: &mut Vec<OsString>, p: &Path) {
+        for c in p.components().rev() {
+            match c {
+                Component::Normal(s) => stack.push(s.to_os_string()),
+                Component::ParentDir => stack.push(OsString::from("..")),
+                Component::RootDir | Component::CurDir | Component::Prefix(_) => {}
+            }
+        }
+    }
+    let mut pending = Vec::new();
+    push_rev(&mut pending, path);
+    let mut cur = root.to_path_buf();
+    let mut depth = 0usize;
+    let mut hops = 0usize;
+    while let Some(name) = pending.pop() {
+        if name == ".." {
+            if depth > 0 {
+                cur.pop();
+                depth -= 1;
+            }
+            continue;
+        }
+        let next = cur.join(&name);
+        if std::fs::symlink_metadata(&next)
+            .ok()?
+            .file_type()
+            .is_symlink()
+        {
+            hops += 1;
+            if hops > MAX_SYMLINKS {
+                return None;
+            }
+            let dest = std::fs::read_link(&next).ok()?;
+            if dest.is_absolute() {
+                cur = root.to_path_buf();
+                depth = 0;
+            }
+            push_rev(&mut pending, &dest);
+        } else {
+            cur = next;
+            depth += 1;
+        }
+    }
+    Some(cur)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::symlink;
+
+    #[test]
+    fn absolute_links_resolve_under_root() {
+        let td = tempfile::tempdir().unwrap();
+        let root = td.path();
+        std::fs::create_dir_all(root.join("usr/lib")).unwrap();
+        std::fs::write(root.join("usr/lib/ld.so"), b"").unwrap();
+        symlink("/usr/lib", root.join("lib")).unwrap();
+        symlink("../../usr/lib/ld.so", root.join("usr/lib/ld-alias")).unwrap();
+        assert_eq!(
+            resolve_in_root(root, Path::new("/lib/ld.so")),
+            Some(root.join("usr/lib/ld.so"))
+        );
+        assert_eq!(
+            resolve_in_root(root, Path::new("lib/ld-alias")),
+            Some(root.join("usr/lib/ld.so"))
+        );
+        assert_eq!(
+            resolve_in_root(root, Path::new("/../../usr/lib")),
+            Some(root.join("usr/lib"))
+        );
+        assert_eq!(resolve_in_root(root, Path::new("/lib/missing")), None);
+    }
+
+    #[test]
+    fn host_targets_and_loops_do_not_resolve() {
+        let td = tempfile::tempdir().unwrap();
+        let root = td.path();
+        // Exists on the host, not under the root.
+        symlink(root.join("..").canonicalize().unwrap(), root.join("host")).unwrap();
+        symlink("/loop", root.join("loop")).unwrap();
+        assert_eq!(resolve_in_root(root, Path::new("host")), None);
+        assert_eq!(resolve_in_root(root, Path::new("loop")), None);
+    }
+}
@@ -4,6 +4,7 @@ use super::{
     idempotence, integrity, selector, steps,
     types::{RestoreOptions, SnapshotSel},
 };
+use crate::fs::backup::preserve::PreservedMeta;
 use crate::fs::backup::sidecar::read_sidecar;
 use crate::types::safepath::SafePath;
 
@@ -89,6 +90,7 @@ pub enum RestoreAction {
     FileRename {
         backup: PathBuf,
         mode: Option<u32>,
+        meta: PreservedMeta,
     },
     SymlinkTo {
         dest: PathBuf,
@@ -176,7 +178,11 @@ impl RestorePlanner {
                         .mode
                         .as_ref()
                         .and_then(|ms| u32::from_str_radix(ms, 8).ok());
-                    RestoreAction::FileRename { backup, mode }
+                    RestoreAction::FileRename {
+                        backup,
+                        mode,
+                        meta: side.preserved.clone(),
+                    }
                 }
                 "symlink" => {
                     if let Some(dest) = side.prior_dest.as_ref() {
@@ -229,8 +235,8 @@ impl RestorePlanner {
     fn execute(target: &Path, action: RestoreAction) -> std::io::Result<()> {
         match action {
             RestoreAction::Noop => Ok(()),
-            RestoreAction::FileRename { backup, mode } => {
-                steps::restore_file_bytes(target, &backup, mode)
+            RestoreAction::FileRename { backup, mode, meta } => {
+                steps::restore_file_bytes_with_meta(target, &backup, mode, &meta)
             }
             RestoreAction::SymlinkTo {
                 dest,
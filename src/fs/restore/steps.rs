@@ -4,6 +4,8 @@ use std::path::Path;
 use rustix::fs::{fchmod, fsync, openat, renameat, unlinkat, AtFlags, Mode, OFlags};
 
 use crate::fs::atomic::open_dir_nofollow;
+use crate::fs::backup::preserve::{apply_to_file, PreservedMeta};
+use crate::policy::config::PreservationTier;
 
 /// Legacy rename of a backup payload into the target place. Removes target first.
 ///
@@ -42,6 +44,23 @@ pub fn restore_file_bytes(
     target_path: &Path,
     backup: &Path,
     mode_octal: Option<u32>,
+) -> std::io::Result<()> {
+    restore_file_bytes_with_meta(target_path, backup, mode_octal, &PreservedMeta::default())
+}
+
+/// Restore file bytes like [`restore_file_bytes`], then re-apply metadata captured in the
+/// sidecar (owner, timestamps, xattrs).
+///
+/// # Errors
+///
+/// Returns an IO error if the restore operation fails, or if any captured dimension could
+/// not be re-applied and the sidecar was captured under the `full` preservation tier. Lower
+/// tiers stay best-effort.
+pub fn restore_file_bytes_with_meta(
+    target_path: &Path,
+    backup: &Path,
+    mode_octal: Option<u32>,
+    meta: &PreservedMeta,
 ) -> std::io::Result<()> {
     let parent = target_path.parent().unwrap_or_else(|| Path::new("."));
     let fname_os = target_path.file_name().ok_or_else(|| {
@@ -61,13 +80,28 @@ pub fn restore_file_bytes(
         .map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidInput, "invalid cstring"))?;
     renameat(&dirfd, old_c.as_c_str(), &dirfd, new_c.as_c_str())
         .map_err(|e| std::io::Error::from_raw_os_error(e.raw_os_error()))?;
-    if let Some(m) = mode_octal {
+    if mode_octal.is_some() || !meta.is_empty() {
         let fname_c = std::ffi::CString::new(fname_os.as_bytes()).map_err(|_| {
             std::io::Error::new(std::io::ErrorKind::InvalidInput, "invalid cstring")
         })?;
         let tfd = openat(&dirfd, fname_c.as_c_str(), OFlags::RDONLY, Mode::empty())
             .map_err(|e| std::io::Error::from_raw_os_error(e.raw_os_error()))?;
-        let _ = fchmod(&tfd, Mode::from_bits_truncate(m));
+        if meta.is_empty() {
+            if let Some(m) = mode_octal {
+                let _ = fchmod(&tfd, Mode::from_bits_truncate(m));
+            }
+        } else {
+            let failed = apply_to_file(&std::fs::File::from(tfd), meta, mode_octal);
+            if !failed.is_empty()
+                && meta.preservation_tier.as_deref() == Some(PreservationTier::Full.as_str())
+            {
+                let _ = fsync(&dirfd);
+                return Err(std::io::Error::other(format!(
+                    "failed to restore preserved metadata: {}",
+                    failed.join(", ")
+                )));
+            }
+        }
     }
     let _ = fsync(&dirfd);
     Ok(())
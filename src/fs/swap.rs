@@ -6,7 +6,8 @@ use std::os::unix::ffi::OsStrExt;
 use rustix::fs::{unlinkat, AtFlags};
 
 use super::atomic::{atomic_symlink_swap, open_dir_nofollow};
-use super::backup::create_snapshot;
+use super::backup::create_snapshot_with_tier;
+use crate::policy::config::PreservationTier;
 use crate::types::safepath::SafePath;
 
 /// Atomically replace a file with a symlink, creating a backup. Emits no logs; pure mechanism.
@@ -28,11 +29,13 @@ pub fn replace_file_with_symlink(
         dry_run,
         allow_degraded,
         backup_tag,
+        PreservationTier::Basic,
         None,
     )
 }
 
-/// Version of `replace_file_with_symlink` that accepts a per-instance EXDEV override for tests/controlled scenarios.
+/// Version of `replace_file_with_symlink` that accepts the policy preservation tier (recorded in the
+/// backup sidecar) and a per-instance EXDEV override for tests/controlled scenarios.
 ///
 /// # Errors
 ///
@@ -51,6 +54,7 @@ pub fn replace_file_with_symlink_with_override(
     dry_run: bool,
     allow_degraded: bool,
     backup_tag: &str,
+    preservation_tier: PreservationTier,
     force_exdev: Option<bool>,
 ) -> std::io::Result<(bool, u64)> {
     let source_path = source.as_path();
@@ -77,10 +81,7 @@ pub fn replace_file_with_symlink_with_override(
 
     let metadata = fs::symlink_metadata(&target_path);
     let existed = metadata.is_ok();
-    let is_symlink = metadata
-        .as_ref()
-        .map(|m| m.file_type().is_symlink())
-        .unwrap_or(false);
+    let is_symlink = metadata.as_ref().is_ok_and(|m| m.file_type().is_symlink());
     let current_dest = if is_symlink {
         fs::read_link(&target_path).ok()
     } else {
@@ -103,7 +104,7 @@ pub fn replace_file_with_symlink_with_override(
         }
 
         // Snapshot current symlink topology before mutation
-        if let Err(e) = create_snapshot(&target_path, backup_tag) {
+        if let Err(e) = create_snapshot_with_tier(&target_path, backup_tag, preservation_tier) {
             if !dry_run {
                 return Err(e);
             }
@@ -135,7 +136,7 @@ pub fn replace_file_with_symlink_with_override(
     if existed {
         if let Ok(_meta) = metadata {
             // Snapshot current file state before mutation
-            if let Err(e) = create_snapshot(&target_path, backup_tag) {
+            if let Err(e) = create_snapshot_with_tier(&target_path, backup_tag, preservation_tier) {
                 if !dry_run {
                     return Err(e);
                 }
@@ -163,7 +164,7 @@ pub fn replace_file_with_symlink_with_override(
         }
     } else {
         // Create tombstone snapshot
-        if let Err(e) = create_snapshot(&target_path, backup_tag) {
+        if let Err(e) = create_snapshot_with_tier(&target_path, backup_tag, preservation_tier) {
             if !dry_run {
                 return Err(e);
             }
@@ -6,12 +6,17 @@
 //   - `plan`, `preflight` (per-action rows and summary), `apply.attempt`, `apply.result`, and `rollback` steps.
 // - Ensures a minimal envelope is present on every fact: `schema_version`, `ts`, `plan_id`, `path`.
 // - Applies redaction in dry-run to zero timestamps and drop volatile fields.
+// - With `policy.audit.hash_chain`, links each run's facts via `prev_hash`/`hash`.
+// - Emits through `FactsEmitter::try_emit`; failures are collected on the `AuditCtx`.
 //
 // See `SPEC/SPEC.md` for field semantics and Minimal Facts v1 schema.
-use crate::logging::{redact_event, FactsEmitter};
+use crate::logging::metrics::Metrics;
+use crate::logging::redact::RedactionPolicy;
+use crate::logging::{EmitError, FactsEmitter};
 use serde_json::{json, Map, Value};
-use std::cell::Cell;
+use std::cell::{Cell, RefCell};
 use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 use uuid::Uuid;
 
@@ -21,6 +26,12 @@ pub(crate) const SCHEMA_VERSION: i64 = 2;
 pub(crate) struct AuditMode {
     pub dry_run: bool,
     pub redact: bool,
+    /// Link facts into a per-run hash chain (see `logging::chain`).
+    pub hash_chain: bool,
+    /// Operator redaction applied to every fact, whatever `redact` says.
+    pub redaction: Arc<RedactionPolicy>,
+    /// Metrics observing every fact before redaction.
+    pub metrics: Option<Metrics>,
 }
 
 #[derive(Debug)]
@@ -31,6 +42,10 @@ pub(crate) struct AuditCtx<'a> {
     pub ts: String,
     pub mode: AuditMode,
     pub seq: Cell<u64>,
+    /// Hash of the last chained fact of this run.
+    chain_head: RefCell<Option<String>>,
+    /// Facts the emitter reported as not recorded (`event: error`).
+    emit_failures: RefCell<Vec<String>>,
 }
 
 impl<'a> AuditCtx<'a> {
@@ -48,12 +63,24 @@ impl<'a> AuditCtx<'a> {
             ts,
             mode,
             seq: Cell::new(0),
+            chain_head: RefCell::new(None),
+            emit_failures: RefCell::new(Vec::new()),
         }
     }
+
+    /// Hash of the most recent fact when hash chaining is enabled.
+    pub(crate) fn chain_head(&self) -> Option<String> {
+        self.chain_head.borrow().clone()
+    }
+
+    /// Drain the emission failures recorded so far.
+    pub(crate) fn take_emit_failures(&self) -> Vec<String> {
+        self.emit_failures.take()
+    }
 }
 
 /// Stage for typed audit emission.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Stage {
     Plan,
     Preflight,
@@ -62,10 +89,17 @@ pub enum Stage {
     ApplyResult,
     Rollback,
     RollbackSummary,
+    SmokeResult,
     PruneResult,
 }
 
 impl Stage {
+    /// Stage name as emitted in facts (`stage` field).
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        self.as_event()
+    }
+
     const fn as_event(self) -> &'static str {
         match self {
             Stage::Plan => "plan",
@@ -75,13 +109,15 @@ impl Stage {
             Stage::ApplyResult => "apply.result",
             Stage::Rollback => "rollback",
             Stage::RollbackSummary => "rollback.summary",
+            Stage::SmokeResult => "smoke.result",
             Stage::PruneResult => "prune.result",
         }
     }
 }
 
 /// Decision severity for audit events.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Decision {
     Success,
     Failure,
@@ -138,6 +174,10 @@ impl<'a> StageLogger<'a> {
         EventBuilder::new(self.ctx, Stage::RollbackSummary)
     }
     #[must_use]
+    pub fn smoke_result(&'a self) -> EventBuilder<'a> {
+        EventBuilder::new(self.ctx, Stage::SmokeResult)
+    }
+    #[must_use]
     pub fn prune_result(&'a self) -> EventBuilder<'a> {
         EventBuilder::new(self.ctx, Stage::PruneResult)
     }
@@ -227,6 +267,24 @@ impl<'a> EventBuilder<'a> {
     }
 
     pub fn emit(self, decision: Decision) {
+        let (ctx, event) = (self.ctx, self.stage.as_event());
+        if let Err(e) = self.send(decision) {
+            ctx.emit_failures.borrow_mut().push(format!("{event}: {e}"));
+        }
+    }
+
+    /// Emit and `flush` the emitter, returning the error instead of recording it in the context.
+    /// Used where a fact must be durable before work continues (`governance.audit_durability`).
+    ///
+    /// # Errors
+    /// Returns the emitter's error when the fact was not recorded or not made durable.
+    pub fn emit_durable(self, decision: Decision) -> Result<(), EmitError> {
+        let ctx = self.ctx;
+        self.send(decision)?;
+        ctx.facts.flush()
+    }
+
+    fn send(self, decision: Decision) -> Result<(), EmitError> {
         let mut fields = Value::Object(self.fields);
         // Ensure provenance object present by default
         ensure_provenance(&mut fields);
@@ -239,7 +297,7 @@ impl<'a> EventBuilder<'a> {
             self.stage.as_event(),
             decision.as_str(),
             fields,
-        );
+        )
     }
 
     pub fn emit_success(self) {
@@ -259,7 +317,7 @@ fn redact_and_emit(
     event: &str,
     decision: &str,
     mut fields: Value,
-) {
+) -> Result<(), EmitError> {
     // Ensure minimal envelope fields
     if let Some(obj) = fields.as_object_mut() {
         obj.entry("schema_version").or_insert(json!(SCHEMA_VERSION));
@@ -328,13 +386,23 @@ fn redact_and_emit(
         ctx.seq.set(cur.saturating_add(1));
         obj.entry("dry_run").or_insert(json!(ctx.mode.dry_run));
     }
-    // Apply redaction policy in dry-run or when requested
-    let out = if ctx.mode.redact {
-        redact_event(fields)
-    } else {
-        fields
-    };
-    ctx.facts.emit(subsystem, event, decision, out);
+    if let Some(metrics) = &ctx.mode.metrics {
+        metrics.observe(decision, &fields);
+    }
+    // Built-in redaction in dry-run or when requested, then the operator's policy
+    let (mut out, rules) = ctx.mode.redaction.redact(fields, ctx.mode.redact);
+    if !rules.is_empty() {
+        if let Some(obj) = out.as_object_mut() {
+            obj.insert("redacted".into(), json!(true));
+            obj.insert("redaction".into(), json!({"applied": true, "rules": rules}));
+        }
+    }
+    // Chain over the fact as emitted, so verifiers can recompute it from the log.
+    if ctx.mode.hash_chain {
+        let head = crate::logging::chain::link(&mut out, ctx.chain_head().as_deref());
+        ctx.chain_head.replace(head);
+    }
+    ctx.facts.try_emit(subsystem, event, decision, out)
 }
 
 fn new_event_id() -> String {
@@ -0,0 +1,283 @@
+//! Tamper-evident hash chain over audit facts (`policy.audit.hash_chain`).
+//!
+//! Within one run (`run_id`), every fact carries `prev_hash` (the previous fact's `hash`, `null`
+//! for `seq` 0) and `hash`, the hex SHA-256 of the fact's canonical JSON: object keys sorted,
+//! no whitespace, and without `hash` itself or the `subsystem`/`event` keys sinks add around the
+//! fact. The apply attestation bundle records the chain head, so truncating the tail of a signed
+//! run is detectable as well.
+//!
+//! [`verify_chain`] walks facts in log order and reports modified facts, missing sequence numbers,
+//! reordered facts and broken links.
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+/// Keys excluded from the hashed content: the hash itself and the envelope sinks wrap facts in.
+const UNHASHED_KEYS: [&str; 3] = ["hash", "subsystem", "event"];
+
+/// Canonical JSON: sorted object keys, compact separators.
+fn canonical(v: &Value, top: bool, out: &mut String) {
+    match v {
+        Value::Object(m) => {
+            let mut keys: Vec<&String> = m
+                .keys()
+                .filter(|k| !top || !UNHASHED_KEYS.contains(&k.as_str()))
+                .collect();
+            keys.sort();
+            out.push('{');
+            for (i, k) in keys.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&Value::String(k.clone()).to_string());
+                out.push(':');
+                canonical(&m[k], false, out);
+            }
+            out.push('}');
+        }
+        Value::Array(a) => {
+            out.push('[');
+            for (i, x) in a.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                canonical(x, false, out);
+            }
+            out.push(']');
+        }
+        other @ (Value::Null | Value::Bool(_) | Value::Number(_) | Value::String(_)) => {
+            out.push_str(&other.to_string());
+        }
+    }
+}
+
+/// Hash of a fact as recorded in its `hash` field.
+#[must_use]
+pub fn fact_hash(fact: &Value) -> String {
+    let mut s = String::new();
+    canonical(fact, true, &mut s);
+    hex::encode(Sha256::digest(s.as_bytes()))
+}
+
+/// Link `fact` to `prev` (the previous fact's hash in the same run) and return its hash.
+pub(crate) fn link(fact: &mut Value, prev: Option<&str>) -> Option<String> {
+    fact.as_object_mut()?.insert(
+        "prev_hash".to_string(),
+        prev.map_or(Value::Null, Value::from),
+    );
+    let hash = fact_hash(fact);
+    fact.as_object_mut()?
+        .insert("hash".to_string(), Value::from(hash.clone()));
+    Some(hash)
+}
+
+/// What went wrong at a point in the chain.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChainIssueKind {
+    /// The fact's content no longer matches its `hash`.
+    Modified,
+    /// Sequence numbers are missing (dropped facts).
+    Gap,
+    /// A fact appears after one with a higher (or equal) sequence number.
+    Reorder,
+    /// `prev_hash` does not match the preceding fact's `hash` (replaced or inserted fact).
+    BrokenLink,
+}
+
+/// One finding from [`verify_chain`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChainIssue {
+    pub run_id: String,
+    /// Sequence number of the fact the issue was found at (first missing one for gaps).
+    pub seq: u64,
+    /// Zero-based position of the fact in the input (`None` for gaps).
+    pub index: Option<usize>,
+    pub kind: ChainIssueKind,
+    pub detail: String,
+}
+
+/// Result of [`verify_chain`].
+#[derive(Clone, Debug, Default)]
+pub struct ChainReport {
+    /// Chained facts checked.
+    pub facts: usize,
+    /// Distinct runs seen.
+    pub runs: usize,
+    /// Last `hash` per run in sequence order, to compare with attested chain heads.
+    pub heads: BTreeMap<String, String>,
+    pub issues: Vec<ChainIssue>,
+}
+
+impl ChainReport {
+    #[must_use]
+    pub const fn is_intact(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+#[derive(Default)]
+struct Run {
+    /// `seq` -> (`hash` as recorded, `prev_hash`, index)
+    by_seq: BTreeMap<u64, (String, Option<String>, usize)>,
+    max_seq: Option<u64>,
+}
+
+/// Verify the hash chain of `facts` given in log order. Facts without a `hash` (unchained, or
+/// non-fact lines such as audit messages) are skipped; dropping a chained fact still shows up as
+/// a gap and a broken link.
+pub fn verify_chain<I: IntoIterator<Item = Value>>(facts: I) -> ChainReport {
+    let mut report = ChainReport::default();
+    let mut runs: BTreeMap<String, Run> = BTreeMap::new();
+    for (index, fact) in facts.into_iter().enumerate() {
+        let Some(hash) = fact.get("hash").and_then(Value::as_str).map(str::to_string) else {
+            continue;
+        };
+        report.facts += 1;
+        let run_id = fact
+            .get("run_id")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+        let seq = fact.get("seq").and_then(Value::as_u64).unwrap_or_default();
+        let mut issue = |kind, detail: String| {
+            report.issues.push(ChainIssue {
+                run_id: run_id.clone(),
+                seq,
+                index: Some(index),
+                kind,
+                detail,
+            });
+        };
+        if fact_hash(&fact) != hash {
+            issue(
+                ChainIssueKind::Modified,
+                "content does not match hash".into(),
+            );
+        }
+        let run = runs.entry(run_id.clone()).or_default();
+        if let Some(max) = run.max_seq.filter(|&m| seq <= m) {
+            issue(
+                ChainIssueKind::Reorder,
+                format!("seq {seq} after seq {max}"),
+            );
+        }
+        run.max_seq = run.max_seq.max(Some(seq));
+        let prev = fact
+            .get("prev_hash")
+            .and_then(Value::as_str)
+            .map(str::to_string);
+        run.by_seq.entry(seq).or_insert((hash, prev, index));
+    }
+    report.runs = runs.len();
+    for (run_id, run) in runs {
+        let mut expected = 0u64;
+        let mut prev_hash: Option<&str> = None;
+        for (&seq, (hash, prev, index)) in &run.by_seq {
+            if seq > expected {
+                report.issues.push(ChainIssue {
+                    run_id: run_id.clone(),
+                    seq: expected,
+                    index: None,
+                    kind: ChainIssueKind::Gap,
+                    detail: format!("missing seq {expected}..{}", seq - 1),
+                });
+            } else if prev.as_deref() != prev_hash {
+                report.issues.push(ChainIssue {
+                    run_id: run_id.clone(),
+                    seq,
+                    index: Some(*index),
+                    kind: ChainIssueKind::BrokenLink,
+                    detail: "prev_hash does not match the preceding fact".into(),
+                });
+            }
+            expected = seq + 1;
+            prev_hash = Some(hash);
+        }
+        if let Some(head) = prev_hash {
+            report.heads.insert(run_id, head.to_string());
+        }
+    }
+    report
+}
+
+/// Verify a JSONL log file (one fact per line; unparsable lines are skipped like unchained ones).
+///
+/// # Errors
+/// Returns an error when the file cannot be read.
+pub fn verify_jsonl(path: &Path) -> std::io::Result<ChainReport> {
+    let text = std::fs::read_to_string(path)?;
+    Ok(verify_chain(
+        text.lines()
+            .map(|l| serde_json::from_str(l).unwrap_or(Value::Null)),
+    ))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn chain(n: u64) -> Vec<Value> {
+        let mut prev: Option<String> = None;
+        (0..n)
+            .map(|seq| {
+                let mut f = json!({ "run_id": "r", "seq": seq, "stage": "apply.attempt" });
+                prev = link(&mut f, prev.as_deref());
+                f
+            })
+            .collect()
+    }
+
+    #[test]
+    fn canonical_hash_ignores_key_order_and_sink_envelope() {
+        let a = json!({ "b": 1, "a": { "y": [1, "x"], "x": null } });
+        let mut b: Value =
+            serde_json::from_str(r#"{"a":{"x":null,"y":[1,"x"]},"b":1,"event":"e"}"#).unwrap();
+        assert_eq!(fact_hash(&a), fact_hash(&b));
+        b["a"]["x"] = json!(0);
+        assert_ne!(fact_hash(&a), fact_hash(&b));
+    }
+
+    #[test]
+    fn detects_modification_gap_reorder_and_relink() {
+        let good = chain(4);
+        let r = verify_chain(good.clone());
+        assert!(r.is_intact(), "{:?}", r.issues);
+        assert_eq!(r.heads["r"], good[3]["hash"]);
+
+        let mut edited = good.clone();
+        edited[1]["stage"] = json!("apply.result");
+        let kinds: Vec<_> = verify_chain(edited).issues.iter().map(|i| i.kind).collect();
+        assert_eq!(kinds, vec![ChainIssueKind::Modified]);
+
+        let mut dropped = good.clone();
+        dropped.remove(2);
+        let issues = verify_chain(dropped).issues;
+        assert_eq!(issues.len(), 1);
+        assert_eq!((issues[0].kind, issues[0].seq), (ChainIssueKind::Gap, 2));
+
+        let mut swapped = good.clone();
+        swapped.swap(1, 2);
+        let kinds: Vec<_> = verify_chain(swapped)
+            .issues
+            .iter()
+            .map(|i| i.kind)
+            .collect();
+        assert_eq!(kinds, vec![ChainIssueKind::Reorder]);
+
+        // Edited and re-hashed: the next fact's link breaks.
+        let mut rehashed = good;
+        rehashed[1]["stage"] = json!("apply.result");
+        let prev = rehashed[0]["hash"].as_str().map(str::to_string);
+        link(&mut rehashed[1], prev.as_deref());
+        let issues = verify_chain(rehashed).issues;
+        assert_eq!(issues.len(), 1);
+        assert_eq!(
+            (issues[0].kind, issues[0].seq),
+            (ChainIssueKind::BrokenLink, 2)
+        );
+    }
+}
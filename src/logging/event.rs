@@ -0,0 +1,796 @@
+//! Typed audit facts (schema v2, `SPEC/audit_event.v2.schema.json`).
+//!
+//! [`AuditEvent`] has one variant per stage, tagged by the `stage` field. Every stage struct
+//! flattens the common [`Envelope`] and keeps keys it does not model in `extra`, so a parsed
+//! fact serializes back to the same JSON, including explicit `null`s (see [`Nullable`]); hash
+//! chains and signatures computed over the original facts still verify.
+//!
+//! [`parse_jsonl`] reads facts back from a JSONL log (e.g. `FileJsonlSink` output):
+//!
+//! ```rust,ignore
+//! use switchyard::logging::event::{parse_jsonl, AuditEvent};
+//!
+//! let file = std::io::BufReader::new(std::fs::File::open("facts.jsonl")?);
+//! for event in parse_jsonl(file) {
+//!     if let AuditEvent::ApplyResult(r) = event? {
+//!         println!("{:?} {:?}", r.envelope.path.as_option(), r.envelope.error_id.as_option());
+//!     }
+//! }
+//! ```
+use std::io::BufRead;
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{Map, Value};
+use thiserror::Error;
+
+use super::audit::{Decision, Stage, SCHEMA_VERSION};
+use crate::adapters::lock::{LockHolder, LockMode};
+use crate::api::errors::ErrorId;
+use crate::policy::types::LockScope;
+use crate::types::{SmokeCheck, StaleProcess};
+
+/// A field the schema allows to be absent, `null`, or set. Unlike `Option`, absent and `null`
+/// stay distinct so facts round-trip exactly.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum Nullable<T> {
+    #[default]
+    Absent,
+    Null,
+    Set(T),
+}
+
+impl<T> Nullable<T> {
+    #[must_use]
+    pub const fn is_absent(&self) -> bool {
+        matches!(self, Nullable::Absent)
+    }
+
+    /// The value when set; `None` for absent and `null` alike.
+    #[must_use]
+    pub const fn as_option(&self) -> Option<&T> {
+        match self {
+            Nullable::Set(v) => Some(v),
+            Nullable::Absent | Nullable::Null => None,
+        }
+    }
+
+    #[must_use]
+    pub fn into_option(self) -> Option<T> {
+        match self {
+            Nullable::Set(v) => Some(v),
+            Nullable::Absent | Nullable::Null => None,
+        }
+    }
+}
+
+impl<T> From<Option<T>> for Nullable<T> {
+    fn from(v: Option<T>) -> Self {
+        v.map_or(Nullable::Null, Nullable::Set)
+    }
+}
+
+impl<T: Serialize> Serialize for Nullable<T> {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Nullable::Set(v) => s.serialize_some(v),
+            Nullable::Absent | Nullable::Null => s.serialize_none(),
+        }
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Nullable<T> {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        Option::<T>::deserialize(d).map(Nullable::from)
+    }
+}
+
+/// Keys not modelled by a struct, kept verbatim.
+pub type Extra = Map<String, Value>;
+
+/// Fields shared by every stage.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Envelope {
+    pub schema_version: i64,
+    pub ts: String,
+    pub plan_id: String,
+    pub decision: Decision,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub event_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub switchyard_version: Option<String>,
+    /// Per-run sequence number.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seq: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dry_run: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub redacted: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub redaction: Option<Redaction>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub severity: Option<Severity>,
+    #[serde(default, skip_serializing_if = "Nullable::is_absent")]
+    pub path: Nullable<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<Provenance>,
+    #[serde(default, skip_serializing_if = "Nullable::is_absent")]
+    pub error_id: Nullable<ErrorId>,
+    #[serde(default, skip_serializing_if = "Nullable::is_absent")]
+    pub exit_code: Nullable<i32>,
+    #[serde(default, skip_serializing_if = "Nullable::is_absent")]
+    pub error_detail: Nullable<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<ErrorDetails>,
+    /// Error identifiers from specific to general (summary facts).
+    #[serde(default, skip_serializing_if = "Nullable::is_absent")]
+    pub summary_error_ids: Nullable<Vec<ErrorId>>,
+    /// Hash chain link (`policy.audit.hash_chain`); `null` for the first fact of a run.
+    #[serde(default, skip_serializing_if = "Nullable::is_absent")]
+    pub prev_hash: Nullable<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hash: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub host: Option<Host>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub process: Option<ProcessInfo>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub actor: Option<Actor>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub build: Option<Build>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    Warn,
+    Error,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Redaction {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub applied: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rules: Option<Vec<String>>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Provenance {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub origin: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub helper: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uid: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gid: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pkg: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pkg_version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pkg_arch: Option<String>,
+    #[serde(default, skip_serializing_if = "Nullable::is_absent")]
+    pub sig_verified: Nullable<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env_sanitized: Option<bool>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ErrorDetails {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+    #[serde(default, skip_serializing_if = "Nullable::is_absent")]
+    pub errno: Nullable<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remediation: Option<String>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+/// Host snapshot (`envmeta` feature).
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Host {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hostname: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub os: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kernel: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub arch: Option<String>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProcessInfo {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pid: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ppid: Option<u32>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Actor {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub euid: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub egid: Option<u32>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Build {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git_sha: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rustc: Option<String>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+/// Lock telemetry carried by `apply.attempt`, the apply summary and a locked `preflight.summary`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct LockFields {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lock_backend: Option<String>,
+    #[serde(default, skip_serializing_if = "Nullable::is_absent")]
+    pub lock_wait_ms: Nullable<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lock_attempts: Option<u64>,
+    #[serde(default, skip_serializing_if = "Nullable::is_absent")]
+    pub lock_mode: Nullable<LockMode>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lock_scope: Option<LockScope>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lock_paths: Option<Vec<LockPath>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lock_holder: Option<LockHolderFact>,
+}
+
+/// One lock taken (or attempted) under a path scope.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockPath {
+    pub path: String,
+    /// Mode the lock was requested in; `global` is held `shared` under path scopes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mode: Option<LockMode>,
+    pub wait_ms: u64,
+    pub acquired: bool,
+}
+
+/// Holder of a contended lock, as reported on timeout.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockHolderFact {
+    #[serde(flatten)]
+    pub holder: LockHolder,
+    /// The holder's process is gone.
+    pub stale: bool,
+}
+
+/// Metadata dimensions (`preservation`, `preservation_effective`).
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Preservation {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mode: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamps: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub xattrs: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub acls: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub caps: Option<bool>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+/// Mount of a preflight target.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Mount {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mount_id: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mount_point: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fs_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub read_only: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub no_exec: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub no_suid: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub no_dev: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bind: Option<bool>,
+    #[serde(default, skip_serializing_if = "Nullable::is_absent")]
+    pub backup_same_dev: Nullable<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub overlay: Option<Overlay>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Overlay {
+    #[serde(default, skip_serializing_if = "Nullable::is_absent")]
+    pub upper: Nullable<String>,
+    #[serde(default)]
+    pub lower: Vec<String>,
+}
+
+/// A process running a preflight target.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BusyProcess {
+    pub pid: u32,
+    pub comm: String,
+    /// `exe` or `maps`
+    pub via: String,
+}
+
+/// A rescue tool excluded because the plan touches it.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RescueExclusion {
+    pub tool: String,
+    pub path: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Perf {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hash_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backup_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub swap_ms: Option<u64>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Attestation {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sig_alg: Option<String>,
+    /// Base64 signature over the bundle.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bundle_hash: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub public_key_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature_format: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cert_chain: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chain_head: Option<String>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+/// `plan`: one per action.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PlanFact {
+    #[serde(flatten)]
+    pub envelope: Envelope,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub action_id: Option<String>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+/// `preflight`: one row per action.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PreflightFact {
+    #[serde(flatten)]
+    pub envelope: Envelope,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub action_id: Option<String>,
+    pub current_kind: String,
+    pub planned_kind: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub policy_ok: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<Vec<String>>,
+    /// Findings from custom `PolicyCheck`s.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checks: Option<Vec<Value>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preservation: Option<Preservation>,
+    #[serde(default, skip_serializing_if = "Nullable::is_absent")]
+    pub preservation_supported: Nullable<bool>,
+    #[serde(default, skip_serializing_if = "Nullable::is_absent")]
+    pub preservation_tier: Nullable<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preservation_effective: Option<Preservation>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub busy: Option<Vec<BusyProcess>>,
+    #[serde(default, skip_serializing_if = "Nullable::is_absent")]
+    pub mount: Nullable<Mount>,
+    #[serde(default, skip_serializing_if = "Nullable::is_absent")]
+    pub fs_type: Nullable<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backup_tag: Option<String>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+/// `preflight.summary`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PreflightSummaryFact {
+    #[serde(flatten)]
+    pub envelope: Envelope,
+    #[serde(flatten)]
+    pub lock: LockFields,
+    /// `available` or `none`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rescue_profile: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rescue_excluded: Option<Vec<RescueExclusion>>,
+    /// Backup space estimate per filesystem (`preflight::space::SpaceBudget::to_json`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub space_budget: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checks: Option<Vec<Value>>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+/// `apply.attempt`: the lock summary (no `action_id`) or one per action.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ApplyAttemptFact {
+    #[serde(flatten)]
+    pub envelope: Envelope,
+    #[serde(flatten)]
+    pub lock: LockFields,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub action_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub safepath_validation: Option<String>,
+    #[serde(default, skip_serializing_if = "Nullable::is_absent")]
+    pub backup_durable: Nullable<bool>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+/// `apply.result`: one per action, plus the run summary (no `action_id`).
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ApplyResultFact {
+    #[serde(flatten)]
+    pub envelope: Envelope,
+    #[serde(flatten)]
+    pub lock: LockFields,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub action_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub before_kind: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub after_kind: Option<String>,
+    #[serde(default, skip_serializing_if = "Nullable::is_absent")]
+    pub degraded: Nullable<bool>,
+    #[serde(default, skip_serializing_if = "Nullable::is_absent")]
+    pub degraded_reason: Nullable<String>,
+    #[serde(default, skip_serializing_if = "Nullable::is_absent")]
+    pub duration_ms: Nullable<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fsync_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Nullable::is_absent")]
+    pub fs_type: Nullable<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hash_alg: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub before_hash: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub after_hash: Option<String>,
+    #[serde(default, skip_serializing_if = "Nullable::is_absent")]
+    pub backup_durable: Nullable<bool>,
+    #[serde(default, skip_serializing_if = "Nullable::is_absent")]
+    pub sidecar_integrity_verified: Nullable<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub perf: Option<Perf>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attestation: Option<Attestation>,
+    #[serde(default, skip_serializing_if = "Nullable::is_absent")]
+    pub rolled_back: Nullable<bool>,
+    #[serde(default, skip_serializing_if = "Nullable::is_absent")]
+    pub rolled_back_paths: Nullable<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub executed_count: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rolled_back_count: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stale_processes: Option<Vec<StaleProcess>>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+/// `rollback`: one per restored action.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RollbackFact {
+    #[serde(flatten)]
+    pub envelope: Envelope,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub action_id: Option<String>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+/// `rollback.summary`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RollbackSummaryFact {
+    #[serde(flatten)]
+    pub envelope: Envelope,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+/// `smoke.result`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SmokeResultFact {
+    #[serde(flatten)]
+    pub envelope: Envelope,
+    pub smoke: SmokeSummary,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SmokeSummary {
+    pub checks: Vec<SmokeCheck>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failed: Option<u64>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+/// `prune.result`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PruneResultFact {
+    #[serde(flatten)]
+    pub envelope: Envelope,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backup_tag: Option<String>,
+    #[serde(default, skip_serializing_if = "Nullable::is_absent")]
+    pub retention_count_limit: Nullable<u64>,
+    #[serde(default, skip_serializing_if = "Nullable::is_absent")]
+    pub retention_age_limit_ms: Nullable<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pruned_count: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retained_count: Option<u64>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+/// One schema v2 fact, by stage.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "stage")]
+#[allow(
+    clippy::large_enum_variant,
+    reason = "every variant carries the full envelope; boxing would not shrink the enum much"
+)]
+pub enum AuditEvent {
+    #[serde(rename = "plan")]
+    Plan(PlanFact),
+    #[serde(rename = "preflight")]
+    Preflight(PreflightFact),
+    #[serde(rename = "preflight.summary")]
+    PreflightSummary(PreflightSummaryFact),
+    #[serde(rename = "apply.attempt")]
+    ApplyAttempt(ApplyAttemptFact),
+    #[serde(rename = "apply.result")]
+    ApplyResult(ApplyResultFact),
+    #[serde(rename = "rollback")]
+    Rollback(RollbackFact),
+    #[serde(rename = "rollback.summary")]
+    RollbackSummary(RollbackSummaryFact),
+    #[serde(rename = "smoke.result")]
+    SmokeResult(SmokeResultFact),
+    #[serde(rename = "prune.result")]
+    PruneResult(PruneResultFact),
+}
+
+impl AuditEvent {
+    #[must_use]
+    pub const fn stage(&self) -> Stage {
+        match self {
+            AuditEvent::Plan(_) => Stage::Plan,
+            AuditEvent::Preflight(_) => Stage::Preflight,
+            AuditEvent::PreflightSummary(_) => Stage::PreflightSummary,
+            AuditEvent::ApplyAttempt(_) => Stage::ApplyAttempt,
+            AuditEvent::ApplyResult(_) => Stage::ApplyResult,
+            AuditEvent::Rollback(_) => Stage::Rollback,
+            AuditEvent::RollbackSummary(_) => Stage::RollbackSummary,
+            AuditEvent::SmokeResult(_) => Stage::SmokeResult,
+            AuditEvent::PruneResult(_) => Stage::PruneResult,
+        }
+    }
+
+    #[must_use]
+    pub const fn envelope(&self) -> &Envelope {
+        match self {
+            AuditEvent::Plan(e) => &e.envelope,
+            AuditEvent::Preflight(e) => &e.envelope,
+            AuditEvent::PreflightSummary(e) => &e.envelope,
+            AuditEvent::ApplyAttempt(e) => &e.envelope,
+            AuditEvent::ApplyResult(e) => &e.envelope,
+            AuditEvent::Rollback(e) => &e.envelope,
+            AuditEvent::RollbackSummary(e) => &e.envelope,
+            AuditEvent::SmokeResult(e) => &e.envelope,
+            AuditEvent::PruneResult(e) => &e.envelope,
+        }
+    }
+
+    /// Parse a fact, checking `schema_version`.
+    ///
+    /// # Errors
+    /// Returns [`EventError::Json`] when the fact does not match the schema v2 shape and
+    /// [`EventError::SchemaVersion`] for other versions.
+    pub fn from_value(v: Value) -> Result<Self, EventError> {
+        let ev: Self =
+            serde_json::from_value(v).map_err(|source| EventError::Json { line: None, source })?;
+        match ev.envelope().schema_version {
+            SCHEMA_VERSION => Ok(ev),
+            found => Err(EventError::SchemaVersion { line: None, found }),
+        }
+    }
+
+    /// The fact as JSON.
+    ///
+    /// # Errors
+    /// Returns the serializer error (not expected for parsed facts).
+    pub fn to_value(&self) -> serde_json::Result<Value> {
+        serde_json::to_value(self)
+    }
+}
+
+impl FromStr for AuditEvent {
+    type Err = EventError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let v: Value =
+            serde_json::from_str(s).map_err(|source| EventError::Json { line: None, source })?;
+        Self::from_value(v)
+    }
+}
+
+/// Why a fact could not be parsed. `line` is 1-based for [`parse_jsonl`].
+#[derive(Debug, Error)]
+pub enum EventError {
+    #[error("{}invalid fact: {source}", at(*.line))]
+    Json {
+        line: Option<usize>,
+        #[source]
+        source: serde_json::Error,
+    },
+    #[error("{}unsupported schema_version {found}", at(*.line))]
+    SchemaVersion { line: Option<usize>, found: i64 },
+    #[error("read error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+fn at(line: Option<usize>) -> String {
+    line.map(|l| format!("line {l}: ")).unwrap_or_default()
+}
+
+impl EventError {
+    fn with_line(self, n: usize) -> Self {
+        match self {
+            EventError::Json { source, .. } => EventError::Json {
+                line: Some(n),
+                source,
+            },
+            EventError::SchemaVersion { found, .. } => EventError::SchemaVersion {
+                line: Some(n),
+                found,
+            },
+            EventError::Io(e) => EventError::Io(e),
+        }
+    }
+}
+
+/// Parse facts from JSONL. Blank lines and objects without a `stage` (such as `AuditSink`
+/// messages written to the same file) are skipped; sink envelope keys (`subsystem`, `event`)
+/// land in `extra`.
+pub fn parse_jsonl<R: BufRead>(reader: R) -> impl Iterator<Item = Result<AuditEvent, EventError>> {
+    reader.lines().enumerate().filter_map(|(i, line)| {
+        let line = match line {
+            Ok(l) => l,
+            Err(e) => return Some(Err(EventError::Io(e))),
+        };
+        if line.trim().is_empty() {
+            return None;
+        }
+        let v: Value = match serde_json::from_str(&line) {
+            Ok(v) => v,
+            Err(source) => {
+                return Some(Err(EventError::Json {
+                    line: Some(i + 1),
+                    source,
+                }))
+            }
+        };
+        v.get("stage")?;
+        Some(AuditEvent::from_value(v).map_err(|e| e.with_line(i + 1)))
+    })
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn explicit_nulls_and_unknown_keys_round_trip() {
+        let fact = json!({
+            "schema_version": 2, "ts": "t", "plan_id": "p", "stage": "apply.attempt",
+            "decision": "failure", "seq": 0, "prev_hash": null, "lock_wait_ms": null,
+            "lock_backend": "file", "lock_mode": "exclusive", "lock_scope": "parent_dirs",
+            "error_id": "E_LOCKING", "exit_code": 30, "something_new": {"x": 1},
+        });
+        let ev = AuditEvent::from_value(fact.clone()).unwrap();
+        let AuditEvent::ApplyAttempt(a) = &ev else {
+            panic!("wrong stage: {ev:?}");
+        };
+        assert_eq!(a.envelope.prev_hash, Nullable::Null);
+        assert_eq!(a.envelope.error_id.as_option(), Some(&ErrorId::E_LOCKING));
+        assert_eq!(a.lock.lock_mode.as_option(), Some(&LockMode::Exclusive));
+        assert_eq!(a.lock.lock_scope, Some(LockScope::ParentDirs));
+        assert!(a.lock.lock_attempts.is_none());
+        assert_eq!(a.extra["something_new"], json!({"x": 1}));
+        assert_eq!(ev.to_value().unwrap(), fact);
+    }
+
+    #[test]
+    fn jsonl_skips_messages_and_reports_line_numbers() {
+        let log = concat!(
+            r#"{"subsystem":"switchyard","event":"audit","decision":"info","message":"hi"}"#,
+            "\n\n",
+            r#"{"schema_version":2,"ts":"t","plan_id":"p","stage":"plan","decision":"success","path":"/a","event":"plan"}"#,
+            "\n",
+            r#"{"schema_version":1,"ts":"t","plan_id":"p","stage":"plan","decision":"success"}"#,
+            "\n",
+            r#"{"schema_version":2,"ts":"t","plan_id":"p","stage":"preflight","decision":"success"}"#,
+            "\n",
+        );
+        let out: Vec<_> = parse_jsonl(log.as_bytes()).collect();
+        assert_eq!(out.len(), 3);
+        let plan = out[0].as_ref().unwrap();
+        assert_eq!(plan.stage(), Stage::Plan);
+        assert_eq!(plan.envelope().path.as_option().unwrap(), "/a");
+        assert!(matches!(
+            out[1],
+            Err(EventError::SchemaVersion {
+                line: Some(4),
+                found: 1
+            })
+        ));
+        // preflight rows require current_kind/planned_kind
+        let err = out[2].as_ref().unwrap_err().to_string();
+        assert!(err.starts_with("line 5: invalid fact"), "{err}");
+    }
+}
@@ -1,5 +1,6 @@
 use log::Level;
 use serde_json::Value;
+use thiserror::Error;
 
 /// Sink interface for structured fact emission.
 ///
@@ -8,6 +9,43 @@ use serde_json::Value;
 pub trait FactsEmitter: std::fmt::Debug {
     /// Emit a fact under the given subsystem and event name with a decision label.
     fn emit(&self, subsystem: &str, event: &str, decision: &str, fields: Value);
+
+    /// Fallible [`emit`](Self::emit): report when the fact could not be recorded.
+    ///
+    /// The default delegates to `emit` and always succeeds; sinks that can fail should override
+    /// it so `governance.audit_durability` can tell.
+    ///
+    /// # Errors
+    /// Returns [`EmitError`] when the fact was not recorded.
+    fn try_emit(
+        &self,
+        subsystem: &str,
+        event: &str,
+        decision: &str,
+        fields: Value,
+    ) -> Result<(), EmitError> {
+        self.emit(subsystem, event, decision, fields);
+        Ok(())
+    }
+
+    /// Make every fact emitted so far durable (e.g. `fsync`). The default is a no-op.
+    ///
+    /// # Errors
+    /// Returns [`EmitError`] when earlier facts may still be lost.
+    fn flush(&self) -> Result<(), EmitError> {
+        Ok(())
+    }
+}
+
+/// A fact that could not be recorded (or made durable) by a [`FactsEmitter`].
+#[derive(Debug, Error)]
+#[error("{0}")]
+pub struct EmitError(pub String);
+
+impl From<std::io::Error> for EmitError {
+    fn from(e: std::io::Error) -> Self {
+        Self(e.to_string())
+    }
 }
 
 /// Lightweight audit sink for human-readable lines.
@@ -31,75 +69,4 @@ impl AuditSink for JsonlSink {
 // Optional: file-backed JSONL sink for production integration.
 // Enabled via `--features file-logging`.
 #[cfg(feature = "file-logging")]
-#[derive(Debug, Clone)]
-pub struct FileJsonlSink {
-    path: std::path::PathBuf,
-}
-
-#[cfg(feature = "file-logging")]
-impl FileJsonlSink {
-    /// Create a new file-backed sink writing one JSON object per line.
-    pub fn new<P: Into<std::path::PathBuf>>(path: P) -> Self {
-        Self { path: path.into() }
-    }
-    fn write_line(&self, line: &str) {
-        if let Some(parent) = self.path.parent() {
-            let _ = std::fs::create_dir_all(parent);
-        }
-        if let Ok(mut f) = std::fs::OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(&self.path)
-        {
-            use std::io::Write as _;
-            let _ = f.write_all(line.as_bytes());
-            let _ = f.write_all(b"\n");
-        }
-    }
-}
-
-#[cfg(feature = "file-logging")]
-impl FactsEmitter for FileJsonlSink {
-    fn emit(&self, subsystem: &str, event: &str, decision: &str, fields: Value) {
-        // Merge subsystem/event/decision into the JSON object if it's an object; otherwise, wrap.
-        let out = match fields {
-            Value::Object(mut m) => {
-                m.entry("subsystem".to_string())
-                    .or_insert(Value::from(subsystem));
-                m.entry("event".to_string()).or_insert(Value::from(event));
-                m.entry("decision".to_string())
-                    .or_insert(Value::from(decision));
-                Value::Object(m)
-            }
-            other @ (Value::Null
-            | Value::Bool(_)
-            | Value::Number(_)
-            | Value::String(_)
-            | Value::Array(_)) => serde_json::json!({
-                "subsystem": subsystem,
-                "event": event,
-                "decision": decision,
-                "fields": other,
-            }),
-        };
-        if let Ok(line) = serde_json::to_string(&out) {
-            self.write_line(&line);
-        }
-    }
-}
-
-#[cfg(feature = "file-logging")]
-impl AuditSink for FileJsonlSink {
-    fn log(&self, level: Level, msg: &str) {
-        let out = serde_json::json!({
-            "subsystem": "switchyard",
-            "event": "audit",
-            "decision": "info",
-            "level": format!("{}", level),
-            "message": msg,
-        });
-        if let Ok(line) = serde_json::to_string(&out) {
-            self.write_line(&line);
-        }
-    }
-}
+pub use super::file_sink::{FileJsonlSink, FsyncPolicy, Rotation};
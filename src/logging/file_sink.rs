@@ -0,0 +1,493 @@
+//! File-backed JSONL sink (`--features file-logging`).
+//!
+//! The sink keeps one `O_APPEND` handle open and writes each line with a single `write_all` while
+//! holding an exclusive `flock` on the file. Concurrent writers, whether threads, other sink
+//! instances or other processes, therefore never interleave partial lines. A writer that finds
+//! the path now names a different file (rotated by another writer) reopens before writing.
+//!
+//! - Durability: [`FsyncPolicy`] controls when the file is `fdatasync`ed.
+//! - Rotation: [`Rotation`] renames the active file to `<path>.1` (shifting older ones up) once it
+//!   exceeds a size or age limit, keeping at most `keep` rotated files.
+//! - Failures: [`FileJsonlSink::write_fact`] (and `FactsEmitter::try_emit`/`flush`) return write
+//!   errors. `FactsEmitter::emit` cannot, so it logs them and counts them in
+//!   [`FileJsonlSink::failures`].
+use std::fs::{File, OpenOptions};
+use std::io::Write as _;
+use std::os::unix::fs::MetadataExt as _;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, SystemTime};
+
+use fs2::FileExt;
+use log::Level;
+use serde_json::Value;
+
+use super::facts::{AuditSink, EmitError, FactsEmitter};
+
+/// When the sink `fdatasync`s the log file.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FsyncPolicy {
+    /// Leave flushing to the OS.
+    Never,
+    /// After the fact that closes a stage run (`preflight.summary`, the summary `apply.result`,
+    /// `rollback.summary`, `prune.result`), before rotating, and when the sink is dropped.
+    #[default]
+    PerRun,
+    /// After every line.
+    PerFact,
+}
+
+/// Size- and age-based rotation with retention.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rotation {
+    /// Rotate before a write would grow the file beyond this many bytes.
+    pub max_bytes: Option<u64>,
+    /// Rotate a non-empty file once it is older than this.
+    pub max_age: Option<Duration>,
+    /// Rotated files to keep (`<path>.1` is the newest); older ones are deleted.
+    pub keep: usize,
+}
+
+impl Default for Rotation {
+    fn default() -> Self {
+        Self {
+            max_bytes: None,
+            max_age: None,
+            keep: 5,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct State {
+    file: Option<File>,
+    /// Creation (or first-seen) time of the open file, for age-based rotation.
+    created: Option<SystemTime>,
+    /// Lines written since the last sync.
+    dirty: bool,
+    sync_on_drop: bool,
+    failures: u64,
+    last_error: Option<String>,
+}
+
+impl Drop for State {
+    fn drop(&mut self) {
+        if let (true, true, Some(f)) = (self.sync_on_drop, self.dirty, &self.file) {
+            let _ = f.sync_data();
+        }
+    }
+}
+
+/// File-backed sink writing one JSON object per line. Clones share the open handle.
+#[derive(Debug, Clone)]
+pub struct FileJsonlSink {
+    path: PathBuf,
+    fsync: FsyncPolicy,
+    rotation: Rotation,
+    state: Arc<Mutex<State>>,
+}
+
+impl FileJsonlSink {
+    /// Create a new file-backed sink writing one JSON object per line. The file is opened on the
+    /// first write; parent directories are created as needed.
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        Self {
+            path: path.into(),
+            fsync: FsyncPolicy::default(),
+            rotation: Rotation::default(),
+            state: Arc::new(Mutex::new(State::default())),
+        }
+    }
+
+    /// Set the fsync policy (default [`FsyncPolicy::PerRun`]).
+    #[must_use]
+    pub const fn with_fsync(mut self, fsync: FsyncPolicy) -> Self {
+        self.fsync = fsync;
+        self
+    }
+
+    /// Enable rotation (default: no size or age limit).
+    #[must_use]
+    pub const fn with_rotation(mut self, rotation: Rotation) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    /// Write one fact, merging `subsystem`/`event`/`decision` into it as `emit` does.
+    ///
+    /// # Errors
+    /// Returns the I/O error when the line could not be written (or synced, per policy).
+    pub fn write_fact(
+        &self,
+        subsystem: &str,
+        event: &str,
+        decision: &str,
+        fields: Value,
+    ) -> std::io::Result<()> {
+        let out = envelope(subsystem, event, decision, fields);
+        let sync = match self.fsync {
+            FsyncPolicy::Never => false,
+            FsyncPolicy::PerFact => true,
+            FsyncPolicy::PerRun => ends_run(&out),
+        };
+        self.write_line(&serde_json::to_string(&out)?, sync)
+    }
+
+    /// `fdatasync` the current file.
+    ///
+    /// # Errors
+    /// Returns the I/O error from `fdatasync`.
+    pub fn sync(&self) -> std::io::Result<()> {
+        let mut st = self.lock_state();
+        if let Some(f) = &st.file {
+            f.sync_data()?;
+        }
+        st.dirty = false;
+        Ok(())
+    }
+
+    /// Number of lines the infallible `emit`/`log` paths failed to write.
+    #[must_use]
+    pub fn failures(&self) -> u64 {
+        self.lock_state().failures
+    }
+
+    /// Most recent write error from the infallible `emit`/`log` paths.
+    #[must_use]
+    pub fn last_error(&self) -> Option<String> {
+        self.lock_state().last_error.clone()
+    }
+
+    fn lock_state(&self) -> MutexGuard<'_, State> {
+        self.state
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    fn record_failure(&self, e: &std::io::Error) {
+        log::error!("facts: cannot write {}: {e}", self.path.display());
+        let mut st = self.lock_state();
+        st.failures += 1;
+        st.last_error = Some(e.to_string());
+    }
+
+    fn write_line(&self, line: &str, sync: bool) -> std::io::Result<()> {
+        let mut buf = Vec::with_capacity(line.len() + 1);
+        buf.extend_from_slice(line.as_bytes());
+        buf.push(b'\n');
+        let mut st = self.lock_state();
+        st.sync_on_drop = self.fsync != FsyncPolicy::Never;
+        self.lock_current(&mut st)?;
+        let res = self
+            .rotate_if_needed(&mut st, buf.len() as u64)
+            .and_then(|()| {
+                let Some(f) = &st.file else {
+                    return Err(std::io::Error::other("log file not open"));
+                };
+                // One write per line: with O_APPEND and the flock held, lines stay whole.
+                let mut w: &File = f;
+                w.write_all(&buf)?;
+                if sync {
+                    f.sync_data()?;
+                }
+                Ok(())
+            });
+        if let Some(f) = &st.file {
+            let _ = FileExt::unlock(f);
+        }
+        if res.is_ok() {
+            st.dirty = !sync;
+        }
+        res
+    }
+
+    fn open(&self) -> std::io::Result<File> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+    }
+
+    /// Take the writer lock on the file currently at `path`, reopening when another writer has
+    /// rotated it away since this handle was opened.
+    fn lock_current(&self, st: &mut State) -> std::io::Result<()> {
+        loop {
+            let f = if let Some(f) = st.file.take() {
+                f
+            } else {
+                st.created = None;
+                self.open()?
+            };
+            // Trait-qualified: std's inherent `File` locking methods shadow fs2's names.
+            FileExt::lock_exclusive(&f)?;
+            let current = std::fs::metadata(&self.path).ok();
+            let mine = f.metadata()?;
+            if current.is_some_and(|m| m.dev() == mine.dev() && m.ino() == mine.ino()) {
+                if st.created.is_none() {
+                    st.created = Some(
+                        mine.created()
+                            .or_else(|_| mine.modified())
+                            .unwrap_or_else(|_| SystemTime::now()),
+                    );
+                }
+                st.file = Some(f);
+                return Ok(());
+            }
+            let _ = FileExt::unlock(&f);
+        }
+    }
+
+    /// Rotate the locked active file when `incoming` more bytes would exceed the size limit or the
+    /// file is past its age limit. The new file is opened and locked before the old lock is
+    /// released, so other writers block until it exists.
+    fn rotate_if_needed(&self, st: &mut State, incoming: u64) -> std::io::Result<()> {
+        let Some(f) = &st.file else {
+            return Ok(());
+        };
+        let len = f.metadata()?.len();
+        if len == 0 {
+            return Ok(());
+        }
+        let too_big = self.rotation.max_bytes.is_some_and(|m| len + incoming > m);
+        let too_old = self.rotation.max_age.is_some_and(|age| {
+            st.created
+                .and_then(|c| c.elapsed().ok())
+                .is_some_and(|e| e >= age)
+        });
+        if !(too_big || too_old) {
+            return Ok(());
+        }
+        if st.sync_on_drop && st.dirty {
+            f.sync_data()?;
+        }
+        self.shift_rotated()?;
+        let new = self.open()?;
+        FileExt::lock_exclusive(&new)?;
+        if let Some(old) = st.file.replace(new) {
+            let _ = FileExt::unlock(&old);
+        }
+        st.created = Some(SystemTime::now());
+        st.dirty = false;
+        Ok(())
+    }
+
+    fn rotated(&self, n: usize) -> PathBuf {
+        let mut s = self.path.as_os_str().to_owned();
+        s.push(format!(".{n}"));
+        PathBuf::from(s)
+    }
+
+    /// `<path>.{keep-1}` -> `<path>.{keep}`, ..., `<path>` -> `<path>.1`; drops the oldest.
+    fn shift_rotated(&self) -> std::io::Result<()> {
+        let keep = self.rotation.keep;
+        if keep == 0 {
+            return std::fs::remove_file(&self.path);
+        }
+        let _ = std::fs::remove_file(self.rotated(keep));
+        for n in (1..keep).rev() {
+            let from = self.rotated(n);
+            if from.exists() {
+                std::fs::rename(&from, self.rotated(n + 1))?;
+            }
+        }
+        std::fs::rename(&self.path, self.rotated(1))
+    }
+}
+
+/// Merge subsystem/event/decision into the JSON object if it's an object; otherwise, wrap.
+fn envelope(subsystem: &str, event: &str, decision: &str, fields: Value) -> Value {
+    match fields {
+        Value::Object(mut m) => {
+            m.entry("subsystem".to_string())
+                .or_insert(Value::from(subsystem));
+            m.entry("event".to_string()).or_insert(Value::from(event));
+            m.entry("decision".to_string())
+                .or_insert(Value::from(decision));
+            Value::Object(m)
+        }
+        other @ (Value::Null
+        | Value::Bool(_)
+        | Value::Number(_)
+        | Value::String(_)
+        | Value::Array(_)) => serde_json::json!({
+            "subsystem": subsystem,
+            "event": event,
+            "decision": decision,
+            "fields": other,
+        }),
+    }
+}
+
+/// Whether `fact` is the last one a stage run emits.
+fn ends_run(fact: &Value) -> bool {
+    match fact.get("stage").and_then(Value::as_str) {
+        Some("preflight.summary" | "rollback.summary" | "prune.result") => true,
+        Some("apply.result") => fact.get("action_id").is_none(),
+        _ => false,
+    }
+}
+
+impl FactsEmitter for FileJsonlSink {
+    fn emit(&self, subsystem: &str, event: &str, decision: &str, fields: Value) {
+        if let Err(e) = self.write_fact(subsystem, event, decision, fields) {
+            self.record_failure(&e);
+        }
+    }
+
+    fn try_emit(
+        &self,
+        subsystem: &str,
+        event: &str,
+        decision: &str,
+        fields: Value,
+    ) -> Result<(), EmitError> {
+        Ok(self.write_fact(subsystem, event, decision, fields)?)
+    }
+
+    fn flush(&self) -> Result<(), EmitError> {
+        Ok(self.sync()?)
+    }
+}
+
+impl AuditSink for FileJsonlSink {
+    fn log(&self, level: Level, msg: &str) {
+        let out = serde_json::json!({
+            "subsystem": "switchyard",
+            "event": "audit",
+            "decision": "info",
+            "level": format!("{}", level),
+            "message": msg,
+        });
+        let res = serde_json::to_string(&out)
+            .map_err(std::io::Error::from)
+            .and_then(|line| self.write_line(&line, self.fsync == FsyncPolicy::PerFact));
+        if let Err(e) = res {
+            self.record_failure(&e);
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn lines(path: &std::path::Path) -> Vec<Value> {
+        std::fs::read_to_string(path)
+            .unwrap_or_default()
+            .lines()
+            .map(|l| serde_json::from_str(l).expect("whole JSON line"))
+            .collect()
+    }
+
+    #[test]
+    fn rotates_by_size_and_keeps_bounded_history() {
+        let td = tempfile::tempdir().unwrap();
+        let path = td.path().join("logs/facts.jsonl");
+        let sink = FileJsonlSink::new(&path).with_rotation(Rotation {
+            max_bytes: Some(200),
+            max_age: None,
+            keep: 2,
+        });
+        for i in 0..20 {
+            sink.emit("switchyard", "apply.attempt", "success", json!({ "i": i }));
+        }
+        assert_eq!(sink.failures(), 0);
+        let rotated = sink.rotated(1);
+        assert!(rotated.exists());
+        assert!(sink.rotated(2).exists());
+        assert!(!sink.rotated(3).exists(), "retention bound");
+        for p in [&path, &rotated] {
+            let len = std::fs::metadata(p).unwrap().len();
+            assert!(len <= 200, "{} is {len} bytes", p.display());
+        }
+        // The newest line is in the active file.
+        assert_eq!(lines(&path).last().unwrap()["i"], 19);
+    }
+
+    #[test]
+    fn rotates_by_age() {
+        let td = tempfile::tempdir().unwrap();
+        let path = td.path().join("facts.jsonl");
+        let sink = FileJsonlSink::new(&path)
+            .with_fsync(FsyncPolicy::PerFact)
+            .with_rotation(Rotation {
+                max_bytes: None,
+                max_age: Some(Duration::ZERO),
+                keep: 1,
+            });
+        sink.emit("switchyard", "plan", "success", json!({ "n": 1 }));
+        sink.emit("switchyard", "plan", "success", json!({ "n": 2 }));
+        assert_eq!(lines(&sink.rotated(1))[0]["n"], 1);
+        assert_eq!(lines(&path)[0]["n"], 2);
+    }
+
+    #[test]
+    fn concurrent_writers_never_interleave_lines() {
+        let td = tempfile::tempdir().unwrap();
+        let path = td.path().join("facts.jsonl");
+        let pad = "x".repeat(8192);
+        let handles: Vec<_> = (0..4)
+            .map(|w| {
+                // Separate instances hold separate open file descriptions, like separate processes.
+                let sink = FileJsonlSink::new(&path).with_rotation(Rotation {
+                    max_bytes: Some(64 * 1024),
+                    max_age: None,
+                    keep: 100,
+                });
+                let pad = pad.clone();
+                std::thread::spawn(move || {
+                    for i in 0..50 {
+                        sink.emit(
+                            "switchyard",
+                            "e",
+                            "success",
+                            json!({ "w": w, "i": i, "pad": pad }),
+                        );
+                    }
+                    assert_eq!(sink.failures(), 0);
+                })
+            })
+            .collect();
+        for h in handles {
+            h.join().unwrap();
+        }
+        let mut total = lines(&path).len();
+        for n in 1..=100 {
+            total += lines(&FileJsonlSink::new(&path).rotated(n)).len();
+        }
+        assert_eq!(total, 200);
+    }
+
+    #[test]
+    fn write_errors_are_returned_and_counted() {
+        let td = tempfile::tempdir().unwrap();
+        // A directory where the log file should be.
+        let sink = FileJsonlSink::new(td.path());
+        assert!(sink
+            .write_fact("switchyard", "plan", "success", json!({}))
+            .is_err());
+        // The fallible trait path surfaces the error to the caller instead of counting it.
+        assert!(sink
+            .try_emit("switchyard", "plan", "success", json!({}))
+            .is_err());
+        sink.emit("switchyard", "plan", "success", json!({}));
+        sink.log(Level::Info, "hello");
+        assert_eq!(sink.failures(), 2);
+        assert!(sink.last_error().is_some());
+    }
+
+    #[test]
+    fn run_boundaries() {
+        assert!(ends_run(&json!({ "stage": "apply.result" })));
+        assert!(!ends_run(
+            &json!({ "stage": "apply.result", "action_id": "a" })
+        ));
+        assert!(ends_run(&json!({ "stage": "preflight.summary" })));
+        assert!(!ends_run(&json!({ "stage": "preflight" })));
+    }
+}
@@ -0,0 +1,481 @@
+//! Counters and histograms derived from emitted facts, rendered as `OpenMetrics` or Prometheus
+//! text for a textfile collector (no listener).
+//!
+//! Register a [`Metrics`] with `ApiBuilder::with_metrics`; every fact is observed as it is
+//! emitted (before redaction), and apply records each action's kind and outcome. Keep a clone to
+//! render after each run:
+//!
+//! ```rust,ignore
+//! use switchyard::logging::{Metrics, MetricsFormat};
+//!
+//! let metrics = Metrics::new();
+//! let api = switchyard::Switchyard::builder(facts, audit, policy)
+//!     .with_metrics(metrics.clone())
+//!     .build();
+//! // ... plan / apply
+//! metrics.write_textfile("/var/lib/node_exporter/switchyard.prom", MetricsFormat::Prometheus)?;
+//! ```
+//!
+//! Only Commit facts (`dry_run: false`) feed the metrics, except `switchyard_applies`, which
+//! counts dry runs under `mode="dry_run"`. Values accumulate for the lifetime of the `Metrics`;
+//! clones share them.
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::io::Write as _;
+use std::path::Path;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use serde_json::Value;
+
+/// Histogram bucket upper bounds, in milliseconds.
+const BUCKETS_MS: [u64; 12] = [1, 5, 10, 25, 50, 100, 250, 500, 1000, 2500, 5000, 10000];
+
+/// Counter families: name (without `_total`), help.
+const COUNTERS: [(&str, &str); 7] = [
+    ("switchyard_applies", "Apply runs by mode and outcome."),
+    (
+        "switchyard_actions",
+        "Executed plan actions by kind and outcome.",
+    ),
+    (
+        "switchyard_failures",
+        "Failure facts by stage and error identifier.",
+    ),
+    ("switchyard_rollbacks", "Apply runs that rolled back."),
+    (
+        "switchyard_rollback_steps",
+        "Per-action rollback steps by outcome.",
+    ),
+    (
+        "switchyard_exdev_fallbacks",
+        "Actions completed via the degraded cross-filesystem (EXDEV) fallback.",
+    ),
+    ("switchyard_smoke_failures", "Failed post-apply smoke runs."),
+];
+
+/// Histogram families: name, help.
+const HISTOGRAMS: [(&str, &str); 5] = [
+    (
+        "switchyard_lock_wait_ms",
+        "Time apply waited for its locks, in milliseconds.",
+    ),
+    (
+        "switchyard_fsync_ms",
+        "Per-action swap and fsync time, in milliseconds.",
+    ),
+    (
+        "switchyard_hash_ms",
+        "Per-run time spent hashing, in milliseconds.",
+    ),
+    (
+        "switchyard_backup_ms",
+        "Per-run time spent on backups, in milliseconds.",
+    ),
+    (
+        "switchyard_swap_ms",
+        "Per-run time spent swapping, in milliseconds.",
+    ),
+];
+
+/// Text exposition format.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MetricsFormat {
+    /// `OpenMetrics` 1.0 (`application/openmetrics-text`), terminated by `# EOF`.
+    #[default]
+    OpenMetrics,
+    /// Prometheus text 0.0.4, as read by the node exporter textfile collector.
+    Prometheus,
+}
+
+type Labels = Vec<(&'static str, String)>;
+
+#[derive(Clone, Debug, Default)]
+struct Histogram {
+    /// Non-cumulative counts per bucket; the last slot is `+Inf`.
+    buckets: [u64; BUCKETS_MS.len() + 1],
+    sum: u64,
+    count: u64,
+}
+
+impl Histogram {
+    fn observe(&mut self, v: u64) {
+        let slot = BUCKETS_MS
+            .iter()
+            .position(|&le| v <= le)
+            .unwrap_or(BUCKETS_MS.len());
+        if let Some(b) = self.buckets.get_mut(slot) {
+            *b += 1;
+        }
+        self.sum = self.sum.saturating_add(v);
+        self.count += 1;
+    }
+}
+
+#[derive(Debug, Default)]
+struct Registry {
+    counters: BTreeMap<&'static str, BTreeMap<Labels, u64>>,
+    histograms: BTreeMap<&'static str, Histogram>,
+}
+
+impl Registry {
+    fn inc(&mut self, name: &'static str, labels: Labels) {
+        *self
+            .counters
+            .entry(name)
+            .or_default()
+            .entry(labels)
+            .or_default() += 1;
+    }
+
+    fn observe(&mut self, name: &'static str, v: Option<u64>) {
+        if let Some(v) = v {
+            self.histograms.entry(name).or_default().observe(v);
+        }
+    }
+}
+
+/// Metrics registry fed by facts. Clones share the values.
+#[derive(Clone, Debug, Default)]
+pub struct Metrics {
+    inner: Arc<Mutex<Registry>>,
+}
+
+impl Metrics {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Registry> {
+        self.inner
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    /// Update the metrics from one fact (as emitted, with its envelope).
+    pub fn observe(&self, decision: &str, fact: &Value) {
+        let stage = fact.get("stage").and_then(Value::as_str).unwrap_or("");
+        let dry_run = fact.get("dry_run").and_then(Value::as_bool) == Some(true);
+        let per_action = fact.get("action_id").is_some();
+        let mut reg = self.lock();
+        if stage == "apply.result" && !per_action {
+            let mode = if dry_run { "dry_run" } else { "commit" };
+            reg.inc(
+                "switchyard_applies",
+                vec![
+                    ("mode", mode.to_string()),
+                    ("decision", decision.to_string()),
+                ],
+            );
+        }
+        if dry_run {
+            return;
+        }
+        if decision == "failure" {
+            if let Some(id) = fact.get("error_id").and_then(Value::as_str) {
+                reg.inc(
+                    "switchyard_failures",
+                    vec![("stage", stage.to_string()), ("error_id", id.to_string())],
+                );
+            }
+        }
+        let ms = |key: &str| fact.get(key).and_then(Value::as_u64);
+        match (stage, per_action) {
+            ("apply.attempt", false) => reg.observe("switchyard_lock_wait_ms", ms("lock_wait_ms")),
+            ("apply.result", true) => {
+                reg.observe("switchyard_fsync_ms", ms("fsync_ms"));
+                // Only a swap that went through counts; a failed fallback is a failure.
+                if decision == "success"
+                    && fact.get("degraded_reason").and_then(Value::as_str) == Some("exdev_fallback")
+                {
+                    reg.inc("switchyard_exdev_fallbacks", Vec::new());
+                }
+            }
+            ("apply.result", false) => {
+                if fact.get("rolled_back").and_then(Value::as_bool) == Some(true) {
+                    reg.inc("switchyard_rollbacks", Vec::new());
+                }
+                if let Some(perf) = fact.get("perf") {
+                    let perf_ms = |key: &str| perf.get(key).and_then(Value::as_u64);
+                    reg.observe("switchyard_hash_ms", perf_ms("hash_ms"));
+                    reg.observe("switchyard_backup_ms", perf_ms("backup_ms"));
+                    reg.observe("switchyard_swap_ms", perf_ms("swap_ms"));
+                }
+            }
+            ("rollback", _) => reg.inc(
+                "switchyard_rollback_steps",
+                vec![("decision", decision.to_string())],
+            ),
+            ("smoke.result", _) if decision == "failure" => {
+                reg.inc("switchyard_smoke_failures", Vec::new());
+            }
+            _ => {}
+        }
+    }
+
+    /// Record the outcome of one executed action (`ensure_symlink`, `restore_from_backup`).
+    pub(crate) fn record_action(&self, kind: &str, ok: bool) {
+        let decision = if ok { "success" } else { "failure" };
+        self.lock().inc(
+            "switchyard_actions",
+            vec![
+                ("kind", kind.to_string()),
+                ("decision", decision.to_string()),
+            ],
+        );
+    }
+
+    /// Value of a counter sample, e.g. `counter("switchyard_applies", &[("mode", "commit"),
+    /// ("decision", "success")])`; 0 when never incremented.
+    #[must_use]
+    pub fn counter(&self, name: &str, labels: &[(&str, &str)]) -> u64 {
+        let reg = self.lock();
+        reg.counters
+            .get(name)
+            .and_then(|samples| {
+                samples.iter().find_map(|(l, v)| {
+                    let same = l.len() == labels.len()
+                        && l.iter()
+                            .zip(labels)
+                            .all(|((k, v), (k2, v2))| k == k2 && v == v2);
+                    same.then_some(*v)
+                })
+            })
+            .unwrap_or(0)
+    }
+
+    /// Observation count of a histogram.
+    #[must_use]
+    pub fn histogram_count(&self, name: &str) -> u64 {
+        self.lock().histograms.get(name).map_or(0, |h| h.count)
+    }
+
+    /// Render every family in `format`. Families without samples are listed with their
+    /// metadata; label-free counters and histograms render as zero.
+    #[must_use]
+    pub fn render(&self, format: MetricsFormat) -> String {
+        let reg = self.lock();
+        let mut out = String::new();
+        for (name, help) in COUNTERS {
+            let family = match format {
+                MetricsFormat::OpenMetrics => name.to_string(),
+                MetricsFormat::Prometheus => format!("{name}_total"),
+            };
+            let _ = writeln!(out, "# HELP {family} {help}");
+            let _ = writeln!(out, "# TYPE {family} counter");
+            match reg.counters.get(name) {
+                Some(samples) => {
+                    for (labels, v) in samples {
+                        let _ = writeln!(out, "{name}_total{} {v}", render_labels(labels, None));
+                    }
+                }
+                None if !is_labelled(name) => {
+                    let _ = writeln!(out, "{name}_total 0");
+                }
+                None => {}
+            }
+        }
+        let empty = Histogram::default();
+        for (name, help) in HISTOGRAMS {
+            let h = reg.histograms.get(name).unwrap_or(&empty);
+            let _ = writeln!(out, "# HELP {name} {help}");
+            let _ = writeln!(out, "# TYPE {name} histogram");
+            let mut cumulative = 0u64;
+            for (i, count) in h.buckets.iter().enumerate() {
+                cumulative += count;
+                let le = BUCKETS_MS
+                    .get(i)
+                    .map_or_else(|| "+Inf".to_string(), ToString::to_string);
+                let _ = writeln!(
+                    out,
+                    "{name}_bucket{} {cumulative}",
+                    render_labels(&[], Some(&le))
+                );
+            }
+            let _ = writeln!(out, "{name}_sum {}", h.sum);
+            let _ = writeln!(out, "{name}_count {}", h.count);
+        }
+        if format == MetricsFormat::OpenMetrics {
+            out.push_str("# EOF\n");
+        }
+        out
+    }
+
+    /// Write [`render`](Self::render) output to `path` atomically (temporary file in the same
+    /// directory, then rename), so a collector never reads a partial file.
+    ///
+    /// # Errors
+    /// Returns the I/O error from writing or renaming.
+    pub fn write_textfile<P: AsRef<Path>>(
+        &self,
+        path: P,
+        format: MetricsFormat,
+    ) -> std::io::Result<()> {
+        let path = path.as_ref();
+        let Some(name) = path.file_name() else {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "metrics path has no file name",
+            ));
+        };
+        let mut tmp_name = std::ffi::OsString::from(".");
+        tmp_name.push(name);
+        tmp_name.push(format!(".{}.tmp", std::process::id()));
+        let tmp = path.with_file_name(tmp_name);
+        let body = self.render(format);
+        let res = std::fs::File::create(&tmp)
+            .and_then(|mut f| {
+                f.write_all(body.as_bytes())?;
+                f.sync_all()
+            })
+            .and_then(|()| std::fs::rename(&tmp, path));
+        if res.is_err() {
+            let _ = std::fs::remove_file(&tmp);
+        }
+        res
+    }
+}
+
+fn is_labelled(name: &str) -> bool {
+    matches!(
+        name,
+        "switchyard_applies"
+            | "switchyard_actions"
+            | "switchyard_failures"
+            | "switchyard_rollback_steps"
+    )
+}
+
+fn render_labels(labels: &[(&'static str, String)], le: Option<&str>) -> String {
+    let mut parts: Vec<String> = labels
+        .iter()
+        .map(|(k, v)| format!("{k}=\"{}\"", escape_label(v)))
+        .collect();
+    if let Some(le) = le {
+        parts.push(format!("le=\"{le}\""));
+    }
+    if parts.is_empty() {
+        String::new()
+    } else {
+        format!("{{{}}}", parts.join(","))
+    }
+}
+
+fn escape_label(v: &str) -> String {
+    v.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn facts_feed_counters_and_histograms() {
+        let m = Metrics::new();
+        m.observe(
+            "success",
+            &json!({"stage": "apply.attempt", "dry_run": false, "lock_wait_ms": 7}),
+        );
+        m.observe(
+            "success",
+            &json!({"stage": "apply.result", "dry_run": false, "action_id": "a",
+                    "fsync_ms": 3, "degraded": true, "degraded_reason": "exdev_fallback"}),
+        );
+        m.observe(
+            "failure",
+            &json!({"stage": "apply.result", "dry_run": false, "action_id": "b",
+                    "degraded_reason": "exdev_fallback", "error_id": "E_EXDEV"}),
+        );
+        m.observe(
+            "failure",
+            &json!({"stage": "smoke.result", "dry_run": false, "error_id": "E_SMOKE"}),
+        );
+        m.observe(
+            "success",
+            &json!({"stage": "rollback", "dry_run": false, "action_id": "a"}),
+        );
+        m.observe(
+            "failure",
+            &json!({"stage": "apply.result", "dry_run": false, "rolled_back": true,
+                    "error_id": "E_SMOKE", "perf": {"hash_ms": 2, "backup_ms": 0, "swap_ms": 3}}),
+        );
+        m.observe(
+            "success",
+            &json!({"stage": "apply.result", "dry_run": true, "lock_wait_ms": 99}),
+        );
+        m.record_action("ensure_symlink", true);
+
+        assert_eq!(
+            m.counter(
+                "switchyard_applies",
+                &[("mode", "commit"), ("decision", "failure")]
+            ),
+            1
+        );
+        assert_eq!(
+            m.counter(
+                "switchyard_applies",
+                &[("mode", "dry_run"), ("decision", "success")]
+            ),
+            1
+        );
+        assert_eq!(
+            m.counter(
+                "switchyard_failures",
+                &[("stage", "smoke.result"), ("error_id", "E_SMOKE")]
+            ),
+            1
+        );
+        assert_eq!(m.counter("switchyard_rollbacks", &[]), 1);
+        assert_eq!(
+            m.counter("switchyard_exdev_fallbacks", &[]),
+            1,
+            "failed fallbacks are not counted"
+        );
+        assert_eq!(
+            m.counter(
+                "switchyard_failures",
+                &[("stage", "apply.result"), ("error_id", "E_EXDEV")]
+            ),
+            1
+        );
+        assert_eq!(m.counter("switchyard_smoke_failures", &[]), 1);
+        assert_eq!(m.histogram_count("switchyard_lock_wait_ms"), 1);
+
+        let text = m.render(MetricsFormat::OpenMetrics);
+        assert!(text.contains("# TYPE switchyard_applies counter\n"));
+        assert!(text.contains(
+            "switchyard_actions_total{kind=\"ensure_symlink\",decision=\"success\"} 1\n"
+        ));
+        assert!(text.contains("switchyard_lock_wait_ms_bucket{le=\"5\"} 0\n"));
+        assert!(text.contains("switchyard_lock_wait_ms_bucket{le=\"10\"} 1\n"));
+        assert!(text.contains("switchyard_lock_wait_ms_bucket{le=\"+Inf\"} 1\n"));
+        assert!(text.contains("switchyard_lock_wait_ms_sum 7\n"));
+        assert!(text.contains("switchyard_hash_ms_count 1\n"));
+        assert!(text.ends_with("# EOF\n"));
+
+        let prom = m.render(MetricsFormat::Prometheus);
+        assert!(prom.contains("# TYPE switchyard_applies_total counter\n"));
+        assert!(!prom.contains("# EOF"));
+    }
+
+    #[test]
+    fn textfile_is_replaced_atomically() {
+        let td = tempfile::tempdir().unwrap();
+        let path = td.path().join("switchyard.prom");
+        let m = Metrics::new();
+        m.write_textfile(&path, MetricsFormat::Prometheus).unwrap();
+        m.record_action("restore_from_backup", false);
+        m.write_textfile(&path, MetricsFormat::Prometheus).unwrap();
+        let text = std::fs::read_to_string(&path).unwrap();
+        assert!(text.contains(
+            "switchyard_actions_total{kind=\"restore_from_backup\",decision=\"failure\"} 1"
+        ));
+        assert_eq!(std::fs::read_dir(td.path()).unwrap().count(), 1);
+        assert_eq!(escape_label("a\"b\\c\nd"), "a\\\"b\\\\c\\nd");
+    }
+}
@@ -1,7 +1,22 @@
 pub mod audit;
+pub mod chain;
+pub mod event;
 pub mod facts;
+#[cfg(feature = "file-logging")]
+mod file_sink;
+pub mod metrics;
 pub mod redact;
+#[cfg(feature = "schema-validation")]
+pub mod schema;
+#[cfg(feature = "system-logging")]
+pub mod system;
 
 pub use audit::{Decision, EventBuilder, Stage, StageLogger};
-pub use facts::{AuditSink, FactsEmitter, JsonlSink};
-pub use redact::{redact_event, ts_for_mode, TS_ZERO};
+pub use event::{parse_jsonl, AuditEvent};
+pub use facts::{AuditSink, EmitError, FactsEmitter, JsonlSink};
+pub use metrics::{Metrics, MetricsFormat};
+pub use redact::{redact_event, redact_text, ts_for_mode, FieldAction, RedactionPolicy, TS_ZERO};
+#[cfg(feature = "schema-validation")]
+pub use schema::{validate_fact, SchemaValidatingEmitter, SchemaViolation};
+#[cfg(feature = "system-logging")]
+pub use system::{JournaldSink, SyslogFacility, SyslogSink};
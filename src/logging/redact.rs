@@ -1,5 +1,7 @@
 use crate::types::plan::ApplyMode;
+use regex::Regex;
 use serde_json::Value;
+use sha2::{Digest, Sha256};
 use time::format_description::well_known::Rfc3339;
 use time::OffsetDateTime;
 
@@ -43,6 +45,9 @@ pub fn redact_event(mut v: Value) -> Value {
         obj.remove("before_hash");
         obj.remove("after_hash");
         obj.remove("hash_alg");
+        // Hash-chain links cover the (mode-dependent) raw fact, so they never compare equal
+        obj.remove("hash");
+        obj.remove("prev_hash");
         // Keep dry_run/redacted flags; these are asserted in audit tests
         // Placeholder secret masking: if provenance.helper exists, replace with "***"
         if let Some(p) = obj.get_mut("provenance") {
@@ -52,6 +57,19 @@ pub fn redact_event(mut v: Value) -> Value {
                 }
             }
         }
+        // Captured smoke command output may carry secrets; mask it entirely
+        if let Some(checks) = obj
+            .get_mut("smoke")
+            .and_then(|s| s.get_mut("checks"))
+            .and_then(Value::as_array_mut)
+        {
+            for c in checks.iter_mut().filter_map(Value::as_object_mut) {
+                if c.get("stderr").is_some_and(|v| !v.is_null()) {
+                    c.insert("stderr".into(), Value::String("***".into()));
+                }
+                c.remove("duration_ms");
+            }
+        }
         // Attestations are preserved, but bundle_hash/public_key_id may vary; mask if present
         if let Some(att) = obj.get_mut("attestation") {
             if let Some(aobj) = att.as_object_mut() {
@@ -64,14 +82,399 @@ pub fn redact_event(mut v: Value) -> Value {
                 if aobj.contains_key("signature") {
                     aobj.insert("signature".into(), Value::String("***".into()));
                 }
+                if aobj.contains_key("chain_head") {
+                    aobj.insert("chain_head".into(), Value::String("***".into()));
+                }
             }
         }
     }
     v
 }
 
+/// What a [`RedactionPolicy`] rule does with the fields it selects.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FieldAction {
+    /// Drop the field.
+    Remove,
+    /// Replace the value with `***`.
+    Mask,
+    /// Replace the value with the hex SHA-256 of the policy salt followed by the value (strings
+    /// as-is, other values as JSON), so equal values stay correlatable without being readable.
+    Hash,
+    /// Emit the field as produced, exempt from the built-in dry-run redaction, path rewrites and
+    /// other rules (e.g. keep `before_hash`/`after_hash` in dry runs).
+    Keep,
+}
+
+impl FieldAction {
+    const fn as_str(self) -> &'static str {
+        match self {
+            FieldAction::Remove => "remove",
+            FieldAction::Mask => "mask",
+            FieldAction::Hash => "hash",
+            FieldAction::Keep => "keep",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct FieldRule {
+    path: String,
+    action: FieldAction,
+}
+
+#[derive(Clone, Debug)]
+struct PathRewrite {
+    pattern: Regex,
+    replacement: String,
+}
+
+/// Operator-configured redaction applied to every fact, in `DryRun` and `Commit` alike
+/// (`ApiBuilder::with_redaction_policy`).
+///
+/// Field rules select values by dot-separated path from the fact root; `*` matches any key or
+/// array element (`host.hostname`, `provenance.pkg`, `smoke.checks.*.command`). Path rewrites
+/// run a regex replacement over every string value of the fact, so `^/home/[^/]+` only touches
+/// whole-value paths while an unanchored `/home/[^/]+` also rewrites paths inside messages.
+///
+/// Rewrites run first, then rules in the order added; `keep` rules win over everything. Facts
+/// that a rule or rewrite changed carry `redacted: true` and list the rules in
+/// `redaction.rules` (e.g. `hash:provenance.pkg`). Masked and hashed values become strings.
+#[derive(Clone, Debug, Default)]
+pub struct RedactionPolicy {
+    rules: Vec<FieldRule>,
+    rewrites: Vec<PathRewrite>,
+    salt: Vec<u8>,
+}
+
+impl RedactionPolicy {
+    /// Empty policy: facts are emitted as produced.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a rule applying `action` to the fields selected by `path`.
+    #[must_use]
+    pub fn rule(mut self, path: impl Into<String>, action: FieldAction) -> Self {
+        self.rules.push(FieldRule {
+            path: path.into(),
+            action,
+        });
+        self
+    }
+
+    /// Shorthand for [`FieldAction::Remove`].
+    #[must_use]
+    pub fn remove(self, path: impl Into<String>) -> Self {
+        self.rule(path, FieldAction::Remove)
+    }
+
+    /// Shorthand for [`FieldAction::Mask`].
+    #[must_use]
+    pub fn mask(self, path: impl Into<String>) -> Self {
+        self.rule(path, FieldAction::Mask)
+    }
+
+    /// Shorthand for [`FieldAction::Hash`].
+    #[must_use]
+    pub fn hash(self, path: impl Into<String>) -> Self {
+        self.rule(path, FieldAction::Hash)
+    }
+
+    /// Shorthand for [`FieldAction::Keep`].
+    #[must_use]
+    pub fn keep(self, path: impl Into<String>) -> Self {
+        self.rule(path, FieldAction::Keep)
+    }
+
+    /// Salt prepended to values before hashing; use a per-deployment secret so hashes of
+    /// low-entropy values (package names, hostnames) cannot be reversed by dictionary.
+    #[must_use]
+    pub fn with_salt(mut self, salt: impl Into<Vec<u8>>) -> Self {
+        self.salt = salt.into();
+        self
+    }
+
+    /// Rewrite `pattern` matches in string values with `replacement` (`$1` style groups).
+    ///
+    /// # Errors
+    /// Returns the regex error for an invalid `pattern`.
+    pub fn rewrite_paths(
+        mut self,
+        pattern: &str,
+        replacement: impl Into<String>,
+    ) -> Result<Self, regex::Error> {
+        self.rewrites.push(PathRewrite {
+            pattern: Regex::new(pattern)?,
+            replacement: replacement.into(),
+        });
+        Ok(self)
+    }
+
+    /// Whether the policy has no rules or rewrites.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.rules.is_empty() && self.rewrites.is_empty()
+    }
+
+    /// Apply the policy to a fact (without the built-in dry-run redaction).
+    #[must_use]
+    pub fn apply(&self, fact: Value) -> Value {
+        self.redact(fact, false).0
+    }
+
+    /// Redact `fact`, first with [`redact_event`] when `determinism` is set; returns the fact and
+    /// the labels of the policy rules that changed it.
+    pub(crate) fn redact(&self, fact: Value, determinism: bool) -> (Value, Vec<String>) {
+        let mut kept = Vec::new();
+        for rule in self.rules.iter().filter(|r| r.action == FieldAction::Keep) {
+            collect(&fact, &segments(&rule.path), &mut Vec::new(), &mut kept);
+        }
+        let mut fact = if determinism {
+            redact_event(fact)
+        } else {
+            fact
+        };
+        let mut applied = Vec::new();
+        for rw in &self.rewrites {
+            if rewrite_strings(&mut fact, &rw.pattern, &rw.replacement) {
+                applied.push(format!("rewrite:{}", rw.pattern.as_str()));
+            }
+        }
+        for rule in self.rules.iter().filter(|r| r.action != FieldAction::Keep) {
+            if self.apply_rule(&mut fact, &segments(&rule.path), rule.action) {
+                applied.push(format!("{}:{}", rule.action.as_str(), rule.path));
+            }
+        }
+        for (at, value) in kept {
+            put(&mut fact, &at, value);
+        }
+        (fact, applied)
+    }
+
+    /// Apply `action` at `segs` below `v`; true when anything changed.
+    fn apply_rule(&self, v: &mut Value, segs: &[&str], action: FieldAction) -> bool {
+        let Some((first, rest)) = segs.split_first() else {
+            return false;
+        };
+        if rest.is_empty() {
+            return match v {
+                Value::Object(map) => {
+                    let keys: Vec<String> = if *first == "*" {
+                        map.keys().cloned().collect()
+                    } else {
+                        map.contains_key(*first)
+                            .then(|| (*first).to_string())
+                            .into_iter()
+                            .collect()
+                    };
+                    for k in &keys {
+                        if action == FieldAction::Remove {
+                            map.remove(k);
+                        } else if let Some(slot) = map.get_mut(k) {
+                            *slot = self.replacement(slot, action);
+                        }
+                    }
+                    !keys.is_empty()
+                }
+                Value::Array(items) if *first == "*" && !items.is_empty() => {
+                    if action == FieldAction::Remove {
+                        items.clear();
+                    } else {
+                        for slot in items.iter_mut() {
+                            *slot = self.replacement(slot, action);
+                        }
+                    }
+                    true
+                }
+                _ => false,
+            };
+        }
+        let mut changed = false;
+        match v {
+            Value::Object(map) if *first == "*" => {
+                for child in map.values_mut() {
+                    changed |= self.apply_rule(child, rest, action);
+                }
+            }
+            Value::Object(map) => {
+                if let Some(child) = map.get_mut(*first) {
+                    changed = self.apply_rule(child, rest, action);
+                }
+            }
+            Value::Array(items) if *first == "*" => {
+                for child in items.iter_mut() {
+                    changed |= self.apply_rule(child, rest, action);
+                }
+            }
+            _ => {}
+        }
+        changed
+    }
+
+    fn replacement(&self, v: &Value, action: FieldAction) -> Value {
+        match action {
+            FieldAction::Hash => {
+                let mut h = Sha256::new();
+                h.update(&self.salt);
+                match v {
+                    Value::String(s) => h.update(s.as_bytes()),
+                    other => h.update(other.to_string().as_bytes()),
+                }
+                Value::String(hex::encode(h.finalize()))
+            }
+            _ => Value::String("***".into()),
+        }
+    }
+}
+
+/// One step of a concrete location in a fact.
+#[derive(Clone, Debug)]
+enum Step {
+    Key(String),
+    Index(usize),
+}
+
+fn segments(path: &str) -> Vec<&str> {
+    path.split('.').filter(|s| !s.is_empty()).collect()
+}
+
+/// Record the location and value of every field selected by `segs`.
+fn collect(v: &Value, segs: &[&str], at: &mut Vec<Step>, out: &mut Vec<(Vec<Step>, Value)>) {
+    let Some((first, rest)) = segs.split_first() else {
+        out.push((at.clone(), v.clone()));
+        return;
+    };
+    match v {
+        Value::Object(map) => {
+            for (k, child) in map.iter().filter(|(k, _)| *first == "*" || k == first) {
+                at.push(Step::Key(k.clone()));
+                collect(child, rest, at, out);
+                at.pop();
+            }
+        }
+        Value::Array(items) if *first == "*" => {
+            for (i, child) in items.iter().enumerate() {
+                at.push(Step::Index(i));
+                collect(child, rest, at, out);
+                at.pop();
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Store `value` at `at`, re-inserting object keys that were removed.
+fn put(v: &mut Value, at: &[Step], value: Value) {
+    let Some((last, parents)) = at.split_last() else {
+        *v = value;
+        return;
+    };
+    let mut cur = v;
+    for step in parents {
+        let next = match (step, cur) {
+            (Step::Key(k), Value::Object(map)) => map.get_mut(k),
+            (Step::Index(i), Value::Array(items)) => items.get_mut(*i),
+            _ => None,
+        };
+        let Some(next) = next else {
+            return;
+        };
+        cur = next;
+    }
+    match (last, cur) {
+        (Step::Key(k), Value::Object(map)) => {
+            map.insert(k.clone(), value);
+        }
+        (Step::Index(i), Value::Array(items)) => {
+            if let Some(slot) = items.get_mut(*i) {
+                *slot = value;
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Rewrite `pattern` in every string value below `v`; true when anything changed.
+fn rewrite_strings(v: &mut Value, pattern: &Regex, replacement: &str) -> bool {
+    match v {
+        Value::String(s) => match pattern.replace_all(s, replacement) {
+            std::borrow::Cow::Owned(new) if new != *s => {
+                *s = new;
+                true
+            }
+            _ => false,
+        },
+        Value::Array(items) => items.iter_mut().fold(false, |acc, c| {
+            rewrite_strings(c, pattern, replacement) | acc
+        }),
+        Value::Object(map) => map.values_mut().fold(false, |acc, c| {
+            rewrite_strings(c, pattern, replacement) | acc
+        }),
+        _ => false,
+    }
+}
+
+/// Key fragments whose values are masked by [`redact_text`].
+const SECRET_KEYS: &[&str] = &[
+    "password",
+    "passwd",
+    "secret",
+    "token",
+    "apikey",
+    "api_key",
+    "credential",
+    "authorization",
+];
+
+fn is_secret_key(key: &str) -> bool {
+    let k = key.to_ascii_lowercase();
+    SECRET_KEYS.iter().any(|s| k.contains(s))
+}
+
+/// Mask secret-looking values in free-form captured output (e.g. command stderr).
+///
+/// Values of `key=value` / `key: value` pairs whose key names a secret, and the credential following
+/// a `Bearer`/`Basic` scheme, are replaced with `***`. Whitespace and everything else is preserved.
+#[must_use]
+pub fn redact_text(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut mask_next = false;
+    for piece in s.split_inclusive(char::is_whitespace) {
+        let word = piece.trim_end();
+        let ws = &piece[word.len()..];
+        if word.is_empty() {
+            out.push_str(ws);
+            continue;
+        }
+        let scheme = word.eq_ignore_ascii_case("bearer") || word.eq_ignore_ascii_case("basic");
+        if mask_next && !scheme {
+            out.push_str("***");
+            out.push_str(ws);
+            mask_next = false;
+            continue;
+        }
+        if let Some((key, value)) = word.split_once(['=', ':']) {
+            if is_secret_key(key) {
+                out.push_str(&word[..=key.len()]);
+                if value.is_empty() {
+                    mask_next = true;
+                } else {
+                    out.push_str("***");
+                }
+                out.push_str(ws);
+                continue;
+            }
+        }
+        mask_next = scheme;
+        out.push_str(piece);
+    }
+    out
+}
+
 #[cfg(test)]
-#[allow(clippy::panic)]
+#[allow(clippy::panic, clippy::unwrap_used)]
 mod tests {
     use super::*;
     use serde_json::json;
@@ -116,4 +519,66 @@ mod tests {
             Some("***")
         );
     }
+
+    #[test]
+    fn policy_rules_and_rewrites() {
+        let policy = RedactionPolicy::new()
+            .rewrite_paths("^/home/[^/]+", "/home/***")
+            .unwrap()
+            .remove("host.hostname")
+            .mask("smoke.checks.*.command")
+            .hash("provenance.pkg")
+            .keep("before_hash")
+            .with_salt("s1");
+        let fact = json!({
+            "ts": "2025-01-01T12:00:00Z",
+            "path": "/home/alice/bin/ls",
+            "error_detail": "cannot open /home/alice/bin/ls",
+            "before_hash": "abc",
+            "after_hash": "def",
+            "host": {"hostname": "build-7", "os": "linux"},
+            "provenance": {"pkg": "coreutils", "uid": 0},
+            "smoke": {"checks": [{"command": "ls --token x"}, {"command": null}]},
+        });
+        let (out, rules) = policy.redact(fact.clone(), true);
+        assert_eq!(out["ts"], TS_ZERO);
+        assert_eq!(out["path"], "/home/***/bin/ls");
+        assert_eq!(out["error_detail"], fact["error_detail"], "anchored");
+        assert_eq!(out["before_hash"], "abc", "kept despite dry-run redaction");
+        assert!(out.get("after_hash").is_none());
+        assert_eq!(out["host"], json!({"os": "linux"}));
+        assert_eq!(out["smoke"]["checks"][0]["command"], "***");
+        assert_eq!(out["smoke"]["checks"][1]["command"], "***");
+        let pkg = out["provenance"]["pkg"].as_str().unwrap();
+        assert_eq!(pkg.len(), 64);
+        assert_eq!(out["provenance"]["uid"], 0);
+        assert_eq!(
+            rules,
+            [
+                "rewrite:^/home/[^/]+",
+                "remove:host.hostname",
+                "mask:smoke.checks.*.command",
+                "hash:provenance.pkg"
+            ]
+        );
+
+        // Same salt, same hash; another salt, another hash.
+        assert_eq!(policy.apply(fact.clone())["provenance"]["pkg"], pkg);
+        let other = policy.clone().with_salt("s2").apply(fact.clone());
+        assert_ne!(other["provenance"]["pkg"], pkg);
+        // Nothing selected, nothing reported.
+        let (_, rules) = policy.redact(json!({"path": "/usr/bin/ls"}), false);
+        assert!(rules.is_empty());
+    }
+
+    #[test]
+    fn redact_text_masks_secret_values() {
+        let out = redact_text("failed: password=hunter2 user=bob\nAuthorization: Bearer abc.def\n");
+        assert_eq!(
+            out,
+            "failed: password=*** user=bob\nAuthorization: Bearer ***\n"
+        );
+        assert_eq!(redact_text("API_TOKEN: xyz ok"), "API_TOKEN: *** ok");
+        assert_eq!(redact_text("curl -H Bearer tok"), "curl -H Bearer ***");
+    }
 }
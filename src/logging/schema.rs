@@ -0,0 +1,269 @@
+//! Runtime validation of emitted facts against `SPEC/audit_event.v2.schema.json`
+//! (`--features schema-validation`).
+//!
+//! Wrap a sink in [`SchemaValidatingEmitter`] in staging or CI so a new code path emitting a
+//! fact the schema rejects is caught before downstream parsers see it:
+//!
+//! ```rust,ignore
+//! use switchyard::logging::{SchemaValidatingEmitter, SchemaViolation};
+//!
+//! let facts = SchemaValidatingEmitter::new(FileJsonlSink::new("/var/log/switchyard/facts.jsonl"))
+//!     .with_on_violation(SchemaViolation::Drop);
+//! let api = switchyard::Switchyard::new(facts.clone(), audit, policy);
+//! // ...
+//! assert_eq!(facts.violations(), 0);
+//! ```
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock};
+
+use jsonschema::JSONSchema;
+use log::Level;
+use serde_json::Value;
+
+use super::facts::{AuditSink, EmitError, FactsEmitter};
+
+/// The v2 audit fact schema, as shipped in `SPEC/`.
+pub const AUDIT_EVENT_V2_SCHEMA: &str = include_str!("../../SPEC/audit_event.v2.schema.json");
+
+#[allow(
+    clippy::expect_used,
+    reason = "the embedded schema is a build input; tests compile it"
+)]
+fn compiled() -> &'static JSONSchema {
+    static SCHEMA: OnceLock<JSONSchema> = OnceLock::new();
+    SCHEMA.get_or_init(|| {
+        let schema: Value =
+            serde_json::from_str(AUDIT_EVENT_V2_SCHEMA).expect("embedded schema is valid JSON");
+        JSONSchema::compile(&schema).expect("embedded schema compiles")
+    })
+}
+
+/// Check one fact against the v2 schema.
+///
+/// # Errors
+/// Returns one message per violation, prefixed with the offending JSON pointer.
+pub fn validate_fact(fact: &Value) -> Result<(), Vec<String>> {
+    compiled().validate(fact).map_err(|errors| {
+        errors
+            .map(|e| {
+                let at = e.instance_path.to_string();
+                if at.is_empty() {
+                    e.to_string()
+                } else {
+                    format!("{at}: {e}")
+                }
+            })
+            .collect()
+    })
+}
+
+/// What [`SchemaValidatingEmitter`] does with a fact that fails validation. Every violation is
+/// logged and counted whatever the action.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SchemaViolation {
+    /// Forward the fact anyway.
+    #[default]
+    Warn,
+    /// Do not forward the fact; `try_emit` reports it as not recorded.
+    Drop,
+    /// Panic, failing the test or CI run that emitted it. Only honoured in builds with
+    /// `debug_assertions`; release builds treat it as [`SchemaViolation::Drop`].
+    Panic,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    violations: u64,
+    last_violation: Option<String>,
+}
+
+/// Emitter validating every fact against the v2 schema before forwarding it to `inner`.
+/// Clones share the violation counter.
+#[derive(Debug, Clone)]
+pub struct SchemaValidatingEmitter<E> {
+    inner: E,
+    on_violation: SchemaViolation,
+    state: Arc<Mutex<State>>,
+}
+
+impl<E: FactsEmitter> SchemaValidatingEmitter<E> {
+    /// Wrap `inner`, forwarding violating facts with a warning ([`SchemaViolation::Warn`]).
+    pub fn new(inner: E) -> Self {
+        Self {
+            inner,
+            on_violation: SchemaViolation::default(),
+            state: Arc::new(Mutex::new(State::default())),
+        }
+    }
+
+    /// Set the action taken on a violating fact.
+    #[must_use]
+    pub const fn with_on_violation(mut self, on_violation: SchemaViolation) -> Self {
+        self.on_violation = on_violation;
+        self
+    }
+
+    /// The wrapped emitter.
+    pub const fn inner(&self) -> &E {
+        &self.inner
+    }
+
+    /// Number of facts that failed validation.
+    #[must_use]
+    pub fn violations(&self) -> u64 {
+        self.lock_state().violations
+    }
+
+    /// Violations of the most recent failing fact, joined with `; `.
+    #[must_use]
+    pub fn last_violation(&self) -> Option<String> {
+        self.lock_state().last_violation.clone()
+    }
+
+    fn lock_state(&self) -> MutexGuard<'_, State> {
+        self.state
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    /// Validate `fields`; `Err` carries the message when the fact must not be forwarded.
+    #[allow(
+        clippy::panic,
+        clippy::panic_in_result_fn,
+        reason = "SchemaViolation::Panic is an explicit opt-in for tests; debug builds only"
+    )]
+    fn check(&self, event: &str, fields: &Value) -> Result<(), String> {
+        let Err(errors) = validate_fact(fields) else {
+            return Ok(());
+        };
+        let msg = format!(
+            "{event}: fact violates the v2 schema: {}",
+            errors.join("; ")
+        );
+        {
+            let mut st = self.lock_state();
+            st.violations += 1;
+            st.last_violation = Some(errors.join("; "));
+        }
+        match self.on_violation {
+            SchemaViolation::Warn => {
+                log::warn!("facts: {msg}");
+                Ok(())
+            }
+            SchemaViolation::Panic if cfg!(debug_assertions) => panic!("{msg}: {fields}"),
+            SchemaViolation::Drop | SchemaViolation::Panic => {
+                log::error!("facts: dropped {msg}");
+                Err(msg)
+            }
+        }
+    }
+}
+
+impl<E: FactsEmitter> FactsEmitter for SchemaValidatingEmitter<E> {
+    fn emit(&self, subsystem: &str, event: &str, decision: &str, fields: Value) {
+        if self.check(event, &fields).is_ok() {
+            self.inner.emit(subsystem, event, decision, fields);
+        }
+    }
+
+    fn try_emit(
+        &self,
+        subsystem: &str,
+        event: &str,
+        decision: &str,
+        fields: Value,
+    ) -> Result<(), EmitError> {
+        self.check(event, &fields).map_err(EmitError)?;
+        self.inner.try_emit(subsystem, event, decision, fields)
+    }
+
+    fn flush(&self) -> Result<(), EmitError> {
+        self.inner.flush()
+    }
+}
+
+impl<E: AuditSink> AuditSink for SchemaValidatingEmitter<E> {
+    fn log(&self, level: Level, msg: &str) {
+        self.inner.log(level, msg);
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[derive(Debug, Clone, Default)]
+    struct Capture(Arc<Mutex<Vec<Value>>>);
+
+    impl FactsEmitter for Capture {
+        fn emit(&self, _subsystem: &str, _event: &str, _decision: &str, fields: Value) {
+            self.0.lock().unwrap().push(fields);
+        }
+    }
+
+    const TS: &str = "1970-01-01T00:00:00Z";
+    const PLAN: &str = "00000000-0000-5000-8000-000000000000";
+
+    fn good() -> Value {
+        json!({"schema_version": 2, "ts": TS, "plan_id": PLAN, "stage": "plan",
+               "decision": "success", "path": "/usr/bin/ls"})
+    }
+
+    fn bad() -> Value {
+        // plan facts require `path`; `seq` must be an integer
+        json!({"schema_version": 2, "ts": TS, "plan_id": PLAN, "stage": "plan",
+               "decision": "success", "seq": "3"})
+    }
+
+    #[test]
+    fn warn_forwards_and_counts() {
+        let cap = Capture::default();
+        let facts = SchemaValidatingEmitter::new(cap.clone());
+        facts.emit("switchyard", "plan", "success", good());
+        facts.emit("switchyard", "plan", "success", bad());
+        assert_eq!(cap.0.lock().unwrap().len(), 2);
+        assert_eq!(facts.clone().violations(), 1);
+        let last = facts.last_violation().unwrap();
+        assert!(last.contains("/seq"), "{last}");
+        assert!(last.contains("path"), "{last}");
+    }
+
+    #[test]
+    fn drop_withholds_and_fails_try_emit() {
+        let cap = Capture::default();
+        let facts =
+            SchemaValidatingEmitter::new(cap.clone()).with_on_violation(SchemaViolation::Drop);
+        facts.emit("switchyard", "plan", "success", bad());
+        assert!(facts
+            .try_emit("switchyard", "plan", "success", bad())
+            .is_err());
+        facts
+            .try_emit("switchyard", "plan", "success", good())
+            .unwrap();
+        assert_eq!(*cap.0.lock().unwrap(), vec![good()]);
+        assert_eq!(facts.violations(), 2);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "fact violates the v2 schema")]
+    fn panic_mode_panics() {
+        let facts = SchemaValidatingEmitter::new(Capture::default())
+            .with_on_violation(SchemaViolation::Panic);
+        facts.emit("switchyard", "plan", "success", bad());
+    }
+
+    #[test]
+    #[cfg(not(debug_assertions))]
+    fn panic_mode_drops_in_release() {
+        let cap = Capture::default();
+        let facts =
+            SchemaValidatingEmitter::new(cap.clone()).with_on_violation(SchemaViolation::Panic);
+        assert!(facts
+            .try_emit("switchyard", "plan", "success", bad())
+            .is_err());
+        assert!(cap.0.lock().unwrap().is_empty());
+        assert_eq!(facts.violations(), 1);
+    }
+}
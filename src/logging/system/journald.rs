@@ -0,0 +1,322 @@
+//! systemd journal native protocol.
+//!
+//! One datagram per entry, holding `KEY=value\n` fields; values containing a newline use the
+//! binary form `KEY\n<u64 LE length><value>\n`. Entries too large for a datagram are written to
+//! a sealed memfd and its descriptor is passed instead, as `sd_journal_send` does.
+use std::fs::File;
+use std::io::Write as _;
+use std::os::fd::AsFd as _;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use log::Level;
+use serde_json::Value;
+
+use super::{severity_of_decision, severity_of_level, summary_line, text, Datagram};
+use crate::logging::facts::{AuditSink, EmitError, FactsEmitter};
+
+/// Default journald native socket.
+pub const JOURNALD_SOCKET: &str = "/run/systemd/journal/socket";
+
+/// Fact keys exported as journal fields, in addition to `MESSAGE`, `PRIORITY`,
+/// `SYSLOG_IDENTIFIER`, `STAGE`, `DECISION` and the full fact in `FACT`.
+const FIELDS: [(&str, &str); 8] = [
+    ("plan_id", "PLAN_ID"),
+    ("run_id", "RUN_ID"),
+    ("event_id", "EVENT_ID"),
+    ("seq", "SEQ"),
+    ("action_id", "ACTION_ID"),
+    ("path", "TARGET_PATH"),
+    ("error_id", "ERROR_ID"),
+    ("exit_code", "EXIT_CODE"),
+];
+
+/// Sink writing facts and audit lines to the systemd journal.
+#[derive(Debug, Clone)]
+pub struct JournaldSink {
+    inner: Arc<Datagram>,
+    identifier: String,
+}
+
+impl Default for JournaldSink {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl JournaldSink {
+    /// Sink for the system journal at [`JOURNALD_SOCKET`].
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_socket(JOURNALD_SOCKET)
+    }
+
+    /// Sink sending to the native protocol socket at `path`.
+    pub fn with_socket<P: Into<PathBuf>>(path: P) -> Self {
+        Self {
+            inner: Arc::new(Datagram::new(path.into())),
+            identifier: "switchyard".to_string(),
+        }
+    }
+
+    /// Set `SYSLOG_IDENTIFIER` (default `switchyard`).
+    #[must_use]
+    pub fn with_identifier<S: Into<String>>(mut self, identifier: S) -> Self {
+        self.identifier = identifier.into();
+        self
+    }
+
+    /// Send one fact as a journal entry.
+    ///
+    /// # Errors
+    /// Returns the I/O error when the entry could not be sent.
+    pub fn send_fact(
+        &self,
+        subsystem: &str,
+        event: &str,
+        decision: &str,
+        fields: &Value,
+    ) -> std::io::Result<()> {
+        let mut entry = vec![
+            ("MESSAGE", summary_line(event, decision, fields)),
+            ("PRIORITY", severity_of_decision(decision).to_string()),
+            ("SYSLOG_IDENTIFIER", self.identifier.clone()),
+            ("SUBSYSTEM", subsystem.to_string()),
+            (
+                "STAGE",
+                text(fields, "stage").unwrap_or_else(|| event.to_string()),
+            ),
+            ("DECISION", decision.to_string()),
+        ];
+        for (key, name) in FIELDS {
+            if let Some(v) = text(fields, key) {
+                entry.push((name, v));
+            }
+        }
+        entry.push(("FACT", fields.to_string()));
+        self.send(&encode(&entry))
+    }
+
+    /// Lines the infallible `emit`/`log` paths failed to send.
+    #[must_use]
+    pub fn failures(&self) -> u64 {
+        self.inner.failures()
+    }
+
+    /// Most recent send error from the infallible `emit`/`log` paths.
+    #[must_use]
+    pub fn last_error(&self) -> Option<String> {
+        self.inner.last_error()
+    }
+
+    fn send(&self, buf: &[u8]) -> std::io::Result<()> {
+        match self.inner.send(buf) {
+            Err(e) if matches!(e.raw_os_error(), Some(libc::EMSGSIZE | libc::ENOBUFS)) => {
+                self.send_memfd(buf)
+            }
+            res => res,
+        }
+    }
+
+    /// Pass an oversized entry as a sealed memfd (`SCM_RIGHTS`).
+    fn send_memfd(&self, buf: &[u8]) -> std::io::Result<()> {
+        use rustix::fs::{fcntl_add_seals, memfd_create, MemfdFlags, SealFlags};
+        use rustix::net::{
+            sendmsg_unix, SendAncillaryBuffer, SendAncillaryMessage, SendFlags, SocketAddrUnix,
+        };
+        let mut file = File::from(memfd_create(
+            "switchyard-journal",
+            MemfdFlags::CLOEXEC | MemfdFlags::ALLOW_SEALING,
+        )?);
+        file.write_all(buf)?;
+        fcntl_add_seals(
+            &file,
+            SealFlags::SHRINK | SealFlags::GROW | SealFlags::WRITE | SealFlags::SEAL,
+        )?;
+        self.inner.with_socket(|sock, path| {
+            let addr = SocketAddrUnix::new(path)?;
+            let mut space = [0u8; rustix::cmsg_space!(ScmRights(1))];
+            let mut control = SendAncillaryBuffer::new(&mut space);
+            let fds = [file.as_fd()];
+            control.push(SendAncillaryMessage::ScmRights(&fds));
+            sendmsg_unix(sock, &addr, &[], &mut control, SendFlags::empty())?;
+            Ok(())
+        })
+    }
+}
+
+/// Serialize fields in the native protocol.
+fn encode(fields: &[(&str, String)]) -> Vec<u8> {
+    let mut out = Vec::new();
+    for (key, value) in fields {
+        out.extend_from_slice(key.as_bytes());
+        if value.contains('\n') {
+            out.push(b'\n');
+            out.extend_from_slice(&(value.len() as u64).to_le_bytes());
+        } else {
+            out.push(b'=');
+        }
+        out.extend_from_slice(value.as_bytes());
+        out.push(b'\n');
+    }
+    out
+}
+
+impl FactsEmitter for JournaldSink {
+    fn emit(&self, subsystem: &str, event: &str, decision: &str, fields: Value) {
+        if let Err(e) = self.send_fact(subsystem, event, decision, &fields) {
+            self.inner.record_failure(&e);
+        }
+    }
+
+    fn try_emit(
+        &self,
+        subsystem: &str,
+        event: &str,
+        decision: &str,
+        fields: Value,
+    ) -> Result<(), EmitError> {
+        Ok(self.send_fact(subsystem, event, decision, &fields)?)
+    }
+}
+
+impl AuditSink for JournaldSink {
+    fn log(&self, level: Level, msg: &str) {
+        let entry = [
+            ("MESSAGE", msg.to_string()),
+            ("PRIORITY", severity_of_level(level).to_string()),
+            ("SYSLOG_IDENTIFIER", self.identifier.clone()),
+        ];
+        if let Err(e) = self.send(&encode(&entry)) {
+            self.inner.record_failure(&e);
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::os::unix::net::UnixDatagram;
+
+    /// Parse a native-protocol entry back into (key, value) pairs.
+    fn decode(mut buf: &[u8]) -> Vec<(String, String)> {
+        let mut out = Vec::new();
+        while !buf.is_empty() {
+            let nl = buf.iter().position(|&b| b == b'\n').unwrap();
+            let line = &buf[..nl];
+            if let Some(eq) = line.iter().position(|&b| b == b'=') {
+                out.push((
+                    String::from_utf8(line[..eq].to_vec()).unwrap(),
+                    String::from_utf8(line[eq + 1..].to_vec()).unwrap(),
+                ));
+                buf = &buf[nl + 1..];
+            } else {
+                let len = u64::from_le_bytes(buf[nl + 1..nl + 9].try_into().unwrap());
+                let len = usize::try_from(len).unwrap();
+                let start = nl + 9;
+                out.push((
+                    String::from_utf8(line.to_vec()).unwrap(),
+                    String::from_utf8(buf[start..start + len].to_vec()).unwrap(),
+                ));
+                buf = &buf[start + len + 1..];
+            }
+        }
+        out
+    }
+
+    #[test]
+    fn facts_become_structured_journal_fields() {
+        let td = tempfile::tempdir().unwrap();
+        let sock_path = td.path().join("journal.sock");
+        let server = UnixDatagram::bind(&sock_path).unwrap();
+        let sink = JournaldSink::with_socket(&sock_path);
+        let fact = json!({
+            "stage": "apply.result",
+            "plan_id": "p1",
+            "run_id": "r1",
+            "seq": 3,
+            "path": "/usr/bin/ls",
+            "error_id": "E_ATOMIC_SWAP",
+        });
+        sink.emit("switchyard", "apply.result", "failure", fact.clone());
+        let mut buf = vec![0u8; 65536];
+        let n = server.recv(&mut buf).unwrap();
+        let entry = decode(&buf[..n]);
+        let get = |k: &str| {
+            entry
+                .iter()
+                .find(|(key, _)| key == k)
+                .map(|(_, v)| v.as_str())
+        };
+        assert_eq!(
+            get("MESSAGE"),
+            Some("apply.result failure /usr/bin/ls (E_ATOMIC_SWAP)")
+        );
+        assert_eq!(get("PRIORITY"), Some("3"));
+        assert_eq!(get("SYSLOG_IDENTIFIER"), Some("switchyard"));
+        assert_eq!(get("STAGE"), Some("apply.result"));
+        assert_eq!(get("DECISION"), Some("failure"));
+        assert_eq!(get("PLAN_ID"), Some("p1"));
+        assert_eq!(get("RUN_ID"), Some("r1"));
+        assert_eq!(get("SEQ"), Some("3"));
+        assert_eq!(get("ERROR_ID"), Some("E_ATOMIC_SWAP"));
+        let full: Value = serde_json::from_str(get("FACT").unwrap()).unwrap();
+        assert_eq!(full, fact);
+
+        // Multi-line values use the length-prefixed binary form.
+        sink.log(Level::Warn, "smoke failed:\nstderr");
+        let n = server.recv(&mut buf).unwrap();
+        let entry = decode(&buf[..n]);
+        assert!(entry.contains(&("PRIORITY".into(), "4".into())));
+        assert!(entry.contains(&("MESSAGE".into(), "smoke failed:\nstderr".into())));
+    }
+
+    #[test]
+    fn missing_socket_is_an_error() {
+        let td = tempfile::tempdir().unwrap();
+        let sink = JournaldSink::with_socket(td.path().join("absent.sock"));
+        assert!(sink
+            .try_emit("switchyard", "plan", "success", json!({}))
+            .is_err());
+        sink.emit("switchyard", "plan", "success", json!({}));
+        assert_eq!(sink.failures(), 1);
+        assert!(sink.last_error().is_some());
+    }
+
+    #[test]
+    fn oversized_entries_are_passed_as_memfd() {
+        use rustix::net::{recvmsg, RecvAncillaryBuffer, RecvAncillaryMessage, RecvFlags};
+        use std::io::{Read as _, Seek as _};
+        let td = tempfile::tempdir().unwrap();
+        let sock_path = td.path().join("journal.sock");
+        let server = UnixDatagram::bind(&sock_path).unwrap();
+        let sink = JournaldSink::with_socket(&sock_path);
+        let big = "x".repeat(4 << 20);
+        sink.try_emit(
+            "switchyard",
+            "preflight.summary",
+            "success",
+            json!({ "blob": big }),
+        )
+        .unwrap();
+        let mut space = [0u8; rustix::cmsg_space!(ScmRights(1))];
+        let mut control = RecvAncillaryBuffer::new(&mut space);
+        recvmsg(&server, &mut [], &mut control, RecvFlags::empty()).unwrap();
+        let fd = control
+            .drain()
+            .find_map(|m| match m {
+                RecvAncillaryMessage::ScmRights(mut fds) => fds.next(),
+                _ => None,
+            })
+            .expect("memfd passed");
+        // The descriptor shares the sender's file offset, which sits at the end.
+        let mut file = File::from(fd);
+        file.rewind().unwrap();
+        let mut payload = Vec::new();
+        file.read_to_end(&mut payload).unwrap();
+        let entry = decode(&payload);
+        assert!(entry.iter().any(|(k, v)| k == "FACT" && v.contains(&big)));
+    }
+}
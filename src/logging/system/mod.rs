@@ -0,0 +1,127 @@
+//! System log sinks (`--features system-logging`).
+//!
+//! - [`JournaldSink`] speaks the systemd journal's native protocol, with facts split into
+//!   structured fields (`PLAN_ID`, `RUN_ID`, `STAGE`, `DECISION`, `ERROR_ID`, ...).
+//! - [`SyslogSink`] sends RFC 5424 messages with the key fields as structured data.
+//!
+//! Both send datagrams over a unix socket whose path is configurable (`with_socket`), so tests
+//! and containers can point them at a stand-in. Clones share one socket. Like
+//! `FileJsonlSink`, `try_emit` returns send errors while `emit`/`log` log and count them.
+use std::os::unix::net::UnixDatagram;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+
+use log::Level;
+use serde_json::Value;
+
+mod journald;
+mod syslog;
+
+pub use journald::{JournaldSink, JOURNALD_SOCKET};
+pub use syslog::{SyslogFacility, SyslogSink, SYSLOG_SOCKET};
+
+#[derive(Debug, Default)]
+struct State {
+    socket: Option<UnixDatagram>,
+    failures: u64,
+    last_error: Option<String>,
+}
+
+/// Unbound datagram socket sending to a fixed path, created on first use.
+#[derive(Debug)]
+struct Datagram {
+    path: PathBuf,
+    state: Mutex<State>,
+}
+
+impl Datagram {
+    fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            state: Mutex::new(State::default()),
+        }
+    }
+
+    fn lock_state(&self) -> MutexGuard<'_, State> {
+        self.state
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    /// Run `f` with the socket and destination path.
+    fn with_socket<R>(
+        &self,
+        f: impl FnOnce(&UnixDatagram, &Path) -> std::io::Result<R>,
+    ) -> std::io::Result<R> {
+        let mut st = self.lock_state();
+        if st.socket.is_none() {
+            st.socket = Some(UnixDatagram::unbound()?);
+        }
+        let Some(sock) = &st.socket else {
+            return Err(std::io::Error::other("socket not open"));
+        };
+        f(sock, &self.path)
+    }
+
+    fn send(&self, buf: &[u8]) -> std::io::Result<()> {
+        self.with_socket(|sock, path| sock.send_to(buf, path).map(drop))
+    }
+
+    fn record_failure(&self, e: &std::io::Error) {
+        log::error!("facts: cannot send to {}: {e}", self.path.display());
+        let mut st = self.lock_state();
+        st.failures += 1;
+        st.last_error = Some(e.to_string());
+    }
+
+    fn failures(&self) -> u64 {
+        self.lock_state().failures
+    }
+
+    fn last_error(&self) -> Option<String> {
+        self.lock_state().last_error.clone()
+    }
+}
+
+/// Syslog severity of a fact: failures are errors, warnings warnings, the rest informational.
+fn severity_of_decision(decision: &str) -> u8 {
+    match decision {
+        "failure" => 3,
+        "warn" => 4,
+        _ => 6,
+    }
+}
+
+const fn severity_of_level(level: Level) -> u8 {
+    match level {
+        Level::Error => 3,
+        Level::Warn => 4,
+        Level::Info => 6,
+        Level::Debug | Level::Trace => 7,
+    }
+}
+
+/// Scalar field of a fact rendered as text (`None` for absent, null or structured values).
+fn text(fields: &Value, key: &str) -> Option<String> {
+    match fields.get(key)? {
+        Value::String(s) => Some(s.clone()),
+        v @ (Value::Number(_) | Value::Bool(_)) => Some(v.to_string()),
+        Value::Null | Value::Array(_) | Value::Object(_) => None,
+    }
+}
+
+/// One-line human summary of a fact, e.g. `apply.result failure /usr/bin/ls (E_ATOMIC_SWAP)`.
+fn summary_line(event: &str, decision: &str, fields: &Value) -> String {
+    let stage = text(fields, "stage").unwrap_or_else(|| event.to_string());
+    let mut line = format!("{stage} {decision}");
+    if let Some(path) = text(fields, "path") {
+        line.push(' ');
+        line.push_str(&path);
+    }
+    if let Some(id) = text(fields, "error_id") {
+        line.push_str(" (");
+        line.push_str(&id);
+        line.push(')');
+    }
+    line
+}
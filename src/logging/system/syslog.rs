@@ -0,0 +1,298 @@
+//! RFC 5424 syslog over a unix datagram socket.
+//!
+//! `<PRI>1 TIMESTAMP HOSTNAME APP-NAME PROCID MSGID [switchyard@32473 ...] MSG`: the fact's stage
+//! is the MSGID, its key fields are structured data and the MSG is the fact as JSON. `32473` is
+//! the documentation enterprise number of RFC 5612; receivers should match on the SD-ID name.
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use log::Level;
+use serde_json::Value;
+use time::OffsetDateTime;
+
+use super::{severity_of_decision, severity_of_level, text, Datagram};
+use crate::logging::facts::{AuditSink, EmitError, FactsEmitter};
+
+/// Default local syslog socket.
+pub const SYSLOG_SOCKET: &str = "/dev/log";
+
+/// Structured-data ID carrying the fact's key fields.
+const SD_ID: &str = "switchyard@32473";
+
+/// Fact keys exported as SD-PARAMs (besides `stage` and `decision`).
+const SD_PARAMS: [&str; 5] = ["plan_id", "run_id", "action_id", "error_id", "exit_code"];
+
+/// Syslog facility of the messages.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SyslogFacility {
+    #[default]
+    User,
+    Daemon,
+    Auth,
+    Local0,
+    Local1,
+    Local2,
+    Local3,
+    Local4,
+    Local5,
+    Local6,
+    Local7,
+}
+
+impl SyslogFacility {
+    /// Numeric facility code.
+    #[must_use]
+    pub const fn code(self) -> u8 {
+        match self {
+            SyslogFacility::User => 1,
+            SyslogFacility::Daemon => 3,
+            SyslogFacility::Auth => 4,
+            SyslogFacility::Local0 => 16,
+            SyslogFacility::Local1 => 17,
+            SyslogFacility::Local2 => 18,
+            SyslogFacility::Local3 => 19,
+            SyslogFacility::Local4 => 20,
+            SyslogFacility::Local5 => 21,
+            SyslogFacility::Local6 => 22,
+            SyslogFacility::Local7 => 23,
+        }
+    }
+}
+
+/// Sink writing facts and audit lines as RFC 5424 syslog messages.
+#[derive(Debug, Clone)]
+pub struct SyslogSink {
+    inner: Arc<Datagram>,
+    facility: SyslogFacility,
+    app_name: String,
+    hostname: String,
+}
+
+impl Default for SyslogSink {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SyslogSink {
+    /// Sink for the local syslog daemon at [`SYSLOG_SOCKET`].
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_socket(SYSLOG_SOCKET)
+    }
+
+    /// Sink sending to the unix datagram socket at `path`.
+    pub fn with_socket<P: Into<PathBuf>>(path: P) -> Self {
+        Self {
+            inner: Arc::new(Datagram::new(path.into())),
+            facility: SyslogFacility::default(),
+            app_name: "switchyard".to_string(),
+            hostname: crate::adapters::lock::hostname().unwrap_or_else(|| "-".to_string()),
+        }
+    }
+
+    /// Set the facility (default [`SyslogFacility::User`]).
+    #[must_use]
+    pub const fn with_facility(mut self, facility: SyslogFacility) -> Self {
+        self.facility = facility;
+        self
+    }
+
+    /// Set APP-NAME (default `switchyard`).
+    #[must_use]
+    pub fn with_app_name<S: Into<String>>(mut self, app_name: S) -> Self {
+        self.app_name = app_name.into();
+        self
+    }
+
+    /// Send one fact as a syslog message.
+    ///
+    /// # Errors
+    /// Returns the I/O error when the message could not be sent.
+    pub fn send_fact(&self, event: &str, decision: &str, fields: &Value) -> std::io::Result<()> {
+        let stage = text(fields, "stage").unwrap_or_else(|| event.to_string());
+        let mut sd = format!(
+            "[{SD_ID} stage=\"{}\" decision=\"{}\"",
+            sd_escape(&stage),
+            sd_escape(decision)
+        );
+        for key in SD_PARAMS {
+            if let Some(v) = text(fields, key) {
+                sd.push(' ');
+                sd.push_str(key);
+                sd.push_str("=\"");
+                sd.push_str(&sd_escape(&v));
+                sd.push('"');
+            }
+        }
+        sd.push(']');
+        let msg = self.format(
+            severity_of_decision(decision),
+            &stage,
+            &sd,
+            &fields.to_string(),
+        );
+        self.inner.send(msg.as_bytes())
+    }
+
+    /// Lines the infallible `emit`/`log` paths failed to send.
+    #[must_use]
+    pub fn failures(&self) -> u64 {
+        self.inner.failures()
+    }
+
+    /// Most recent send error from the infallible `emit`/`log` paths.
+    #[must_use]
+    pub fn last_error(&self) -> Option<String> {
+        self.inner.last_error()
+    }
+
+    fn format(&self, severity: u8, msgid: &str, sd: &str, msg: &str) -> String {
+        let pri = u16::from(self.facility.code()) * 8 + u16::from(severity);
+        format!(
+            "<{pri}>1 {} {} {} {} {} {sd} {msg}",
+            timestamp(OffsetDateTime::now_utc()),
+            header_field(&self.hostname, 255),
+            header_field(&self.app_name, 48),
+            std::process::id(),
+            header_field(msgid, 32),
+        )
+    }
+}
+
+/// RFC 5424 TIMESTAMP: RFC 3339 in UTC with at most microsecond precision.
+fn timestamp(t: OffsetDateTime) -> String {
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:06}Z",
+        t.year(),
+        u8::from(t.month()),
+        t.day(),
+        t.hour(),
+        t.minute(),
+        t.second(),
+        t.microsecond()
+    )
+}
+
+/// Header fields are 1..=`max` printable US-ASCII characters, `-` when empty.
+fn header_field(s: &str, max: usize) -> String {
+    let out: String = s.chars().filter(char::is_ascii_graphic).take(max).collect();
+    if out.is_empty() {
+        "-".to_string()
+    } else {
+        out
+    }
+}
+
+/// Escape `"`, `\` and `]` in an SD-PARAM value.
+fn sd_escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        if matches!(c, '"' | '\\' | ']') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+impl FactsEmitter for SyslogSink {
+    fn emit(&self, _subsystem: &str, event: &str, decision: &str, fields: Value) {
+        if let Err(e) = self.send_fact(event, decision, &fields) {
+            self.inner.record_failure(&e);
+        }
+    }
+
+    fn try_emit(
+        &self,
+        _subsystem: &str,
+        event: &str,
+        decision: &str,
+        fields: Value,
+    ) -> Result<(), EmitError> {
+        Ok(self.send_fact(event, decision, &fields)?)
+    }
+}
+
+impl AuditSink for SyslogSink {
+    fn log(&self, level: Level, msg: &str) {
+        let line = self.format(severity_of_level(level), "audit", "-", msg);
+        if let Err(e) = self.inner.send(line.as_bytes()) {
+            self.inner.record_failure(&e);
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::os::unix::net::UnixDatagram;
+
+    #[test]
+    fn facts_are_rfc5424_messages_with_structured_data() {
+        let td = tempfile::tempdir().unwrap();
+        let sock_path = td.path().join("log.sock");
+        let server = UnixDatagram::bind(&sock_path).unwrap();
+        let sink = SyslogSink::with_socket(&sock_path).with_facility(SyslogFacility::Local3);
+        let fact = json!({
+            "stage": "apply.result",
+            "plan_id": "p1",
+            "run_id": "r1",
+            "error_id": "E_SMOKE",
+            "exit_code": 80,
+            "path": "/usr/bin/\"odd]",
+        });
+        sink.emit("switchyard", "apply.result", "failure", fact.clone());
+        let mut buf = vec![0u8; 65536];
+        let n = server.recv(&mut buf).unwrap();
+        let line = std::str::from_utf8(&buf[..n]).unwrap();
+        // local3 (19) * 8 + err (3)
+        assert!(line.starts_with("<155>1 "), "{line}");
+        let parts: Vec<&str> = line.splitn(8, ' ').collect();
+        assert!(
+            parts[1].ends_with('Z') && parts[1].len() == 27,
+            "{}",
+            parts[1]
+        );
+        assert_eq!(parts[3], "switchyard");
+        assert_eq!(parts[4], std::process::id().to_string());
+        assert_eq!(parts[5], "apply.result");
+        let (sd, msg) = parts[6..]
+            .join(" ")
+            .split_once("] ")
+            .map(|(a, b)| (a.to_string(), b.to_string()))
+            .unwrap();
+        assert_eq!(
+            sd,
+            "[switchyard@32473 stage=\"apply.result\" decision=\"failure\" plan_id=\"p1\" \
+             run_id=\"r1\" error_id=\"E_SMOKE\" exit_code=\"80\""
+        );
+        assert_eq!(serde_json::from_str::<Value>(&msg).unwrap(), fact);
+
+        sink.log(Level::Info, "apply: finished");
+        let n = server.recv(&mut buf).unwrap();
+        let line = std::str::from_utf8(&buf[..n]).unwrap();
+        assert!(line.starts_with("<158>1 "), "{line}");
+        assert!(line.ends_with(" audit - apply: finished"), "{line}");
+    }
+
+    #[test]
+    fn sd_values_are_escaped() {
+        assert_eq!(sd_escape(r#"a"b\c]d"#), r#"a\"b\\c\]d"#);
+        assert_eq!(header_field("", 32), "-");
+        assert_eq!(header_field("has space", 32), "hasspace");
+    }
+
+    #[test]
+    fn missing_socket_is_an_error() {
+        let td = tempfile::tempdir().unwrap();
+        let sink = SyslogSink::with_socket(td.path().join("absent.sock"));
+        assert!(sink
+            .try_emit("switchyard", "plan", "success", json!({}))
+            .is_err());
+        sink.log(Level::Info, "x");
+        assert_eq!(sink.failures(), 1);
+    }
+}
@@ -0,0 +1,107 @@
+//! Pluggable policy checks.
+//!
+//! Built-in gating lives in `policy::gating`. Integrators with site-specific rules
+//! (e.g., "never touch binaries listed in /etc/critical-bins") implement [`PolicyCheck`]
+//! and register it via `ApiBuilder::with_policy_check(...)`.
+//!
+//! Findings are merged into preflight rows (`notes`, `checks`) and enforced at apply-time by
+//! the same gating path as built-in checks:
+//! - `Stop` findings block the plan (`E_POLICY`) unless `override_preflight=true`.
+//! - `Warn` findings are surfaced in `PreflightReport.warnings` and do not block.
+//! - `Note` findings are informational only.
+use serde_json::{json, Value};
+
+use crate::policy::Policy;
+use crate::types::{Action, Plan};
+
+/// Severity of a custom check finding.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CheckSeverity {
+    Stop,
+    Warn,
+    Note,
+}
+
+impl CheckSeverity {
+    /// Stable lowercase label used in facts and preflight rows.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            CheckSeverity::Stop => "stop",
+            CheckSeverity::Warn => "warn",
+            CheckSeverity::Note => "note",
+        }
+    }
+}
+
+/// A single finding produced by a [`PolicyCheck`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CheckFinding {
+    pub severity: CheckSeverity,
+    /// Stable, machine-readable reason code (e.g., `critical_bin`).
+    pub code: String,
+    /// Human-readable reason.
+    pub reason: String,
+}
+
+impl CheckFinding {
+    #[must_use]
+    pub fn stop(code: impl Into<String>, reason: impl Into<String>) -> Self {
+        Self {
+            severity: CheckSeverity::Stop,
+            code: code.into(),
+            reason: reason.into(),
+        }
+    }
+
+    #[must_use]
+    pub fn warn(code: impl Into<String>, reason: impl Into<String>) -> Self {
+        Self {
+            severity: CheckSeverity::Warn,
+            code: code.into(),
+            reason: reason.into(),
+        }
+    }
+
+    #[must_use]
+    pub fn note(code: impl Into<String>, reason: impl Into<String>) -> Self {
+        Self {
+            severity: CheckSeverity::Note,
+            code: code.into(),
+            reason: reason.into(),
+        }
+    }
+}
+
+/// Custom preflight check evaluated alongside the built-in gates.
+///
+/// Both hooks default to "no findings" so implementors only override what they need.
+pub trait PolicyCheck: Send + Sync {
+    /// Stable name of the check, recorded with each finding.
+    fn name(&self) -> &str;
+
+    /// Evaluate a single action.
+    fn check_action(&self, _policy: &Policy, _act: &Action) -> Vec<CheckFinding> {
+        Vec::new()
+    }
+
+    /// Evaluate the plan as a whole (e.g., cross-action invariants).
+    fn check_plan(&self, _policy: &Policy, _plan: &Plan) -> Vec<CheckFinding> {
+        Vec::new()
+    }
+}
+
+/// Render a finding into the human message used in `stops`/`warnings`/`notes`.
+pub(crate) fn finding_message(check: &str, f: &CheckFinding) -> String {
+    format!("policy check {check}: {} ({})", f.reason, f.code)
+}
+
+/// Render a finding into the structured record carried in preflight rows and summary.
+pub(crate) fn finding_record(check: &str, f: &CheckFinding) -> Value {
+    json!({
+        "check": check,
+        "severity": f.severity.as_str(),
+        "code": f.code,
+        "reason": f.reason,
+    })
+}
@@ -3,8 +3,9 @@ use crate::constants::RESCUE_MIN_COUNT as DEFAULT_RESCUE_MIN_COUNT;
 use std::path::PathBuf;
 
 use super::types::{
-    ApplyFlow, Backup, Durability, ExdevPolicy, Governance, LockingPolicy, PreservationPolicy,
-    Rescue, RiskLevel, Risks, Scope, SmokePolicy, SourceTrustPolicy,
+    ApplyFlow, Audit, AuditDurability, Backup, Durability, ExdevPolicy, FsClassPolicy, Governance,
+    LockScope, LockingPolicy, PreservationPolicy, Rescue, RescueProfile, RiskLevel, Risks, Scope,
+    SmokePolicy, SourceTrustPolicy,
 };
 
 /// Policy governs preflight gates, apply behavior, and production hardening for Switchyard.
@@ -14,11 +15,14 @@ use super::types::{
 pub struct Policy {
     pub scope: Scope,
     pub rescue: Rescue,
+    /// Rescue profiles tried in order; empty means `[RescueProfile::Path]`.
+    pub rescue_profiles: Vec<RescueProfile>,
     pub risks: Risks,
     pub durability: Durability,
     pub apply: ApplyFlow,
     pub governance: Governance,
     pub backup: Backup,
+    pub audit: Audit,
     // Retention knobs remain top-level for prune API
     pub retention_count_limit: Option<usize>,
     pub retention_age_limit: Option<std::time::Duration>,
@@ -36,16 +40,22 @@ impl Default for Policy {
                 exec_check: false,
                 min_count: DEFAULT_RESCUE_MIN_COUNT,
             },
+            rescue_profiles: Vec::new(),
             risks: Risks {
                 suid_sgid: RiskLevel::Stop,
                 hardlinks: RiskLevel::Stop,
+                busy_binaries: RiskLevel::Warn,
+                elf_compat: RiskLevel::Warn,
+                elf_target: None,
                 source_trust: SourceTrustPolicy::WarnOnUntrusted,
                 ownership_strict: false,
+                fs_classes: FsClassPolicy::default(),
             },
             durability: Durability {
                 backup_durability: true,
                 sidecar_integrity: true,
                 preservation: PreservationPolicy::Off,
+                space_margin_percent: 10,
             },
             apply: ApplyFlow {
                 exdev: ExdevPolicy::Fail,
@@ -56,12 +66,16 @@ impl Default for Policy {
             },
             governance: Governance {
                 locking: LockingPolicy::Optional,
+                lock_scope: LockScope::Global,
+                preflight_shared_lock: false,
+                audit_durability: AuditDurability::BestEffort,
                 smoke: SmokePolicy::Off,
                 allow_unlocked_commit: false,
             },
             backup: Backup {
                 tag: DEFAULT_BACKUP_TAG.to_string(),
             },
+            audit: Audit::default(),
             retention_count_limit: None,
             retention_age_limit: None,
             allow_unreliable_immutable_check: false,
@@ -70,13 +84,43 @@ impl Default for Policy {
     }
 }
 
-#[derive(Clone, Copy, Debug)]
+/// Which metadata dimensions are captured into the backup sidecar and re-applied on restore.
+///
+/// - `Basic`: mode only (historical behavior).
+/// - `Extended`: mode, owner/group, and access/modification timestamps.
+/// - `Full`: everything in `Extended` plus all extended attributes, which covers
+///   `security.capability`, `security.selinux`, and POSIX ACLs (`system.posix_acl_*`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum PreservationTier {
     Basic,
     Extended,
     Full,
 }
 
+impl PreservationTier {
+    /// Stable lowercase label used in sidecars and preflight rows.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            PreservationTier::Basic => "basic",
+            PreservationTier::Extended => "extended",
+            PreservationTier::Full => "full",
+        }
+    }
+
+    /// Whether owner/group and timestamps are preserved.
+    #[must_use]
+    pub const fn includes_owner_and_timestamps(self) -> bool {
+        matches!(self, PreservationTier::Extended | PreservationTier::Full)
+    }
+
+    /// Whether extended attributes (including ACLs and capabilities) are preserved.
+    #[must_use]
+    pub const fn includes_xattrs(self) -> bool {
+        matches!(self, PreservationTier::Full)
+    }
+}
+
 impl Policy {
     /// Construct a Policy configured with recommended **production defaults**.
     ///
@@ -138,6 +182,7 @@ impl Policy {
     /// - `require_preservation = true` (STOP if basic preservation not supported)
     /// - `override_preflight = false` (fail-closed)
     /// - `force_untrusted_source = false`
+    /// - `elf_compat = Stop` (source arch/interpreter/library mismatches block)
     /// - `force_restore_best_effort = false` (missing backup → error)
     /// - `backup_tag = "coreutils"`
     ///
@@ -166,6 +211,7 @@ impl Policy {
         p.durability.preservation = PreservationPolicy::RequireBasic;
         p.apply.override_preflight = false;
         p.risks.source_trust = SourceTrustPolicy::RequireTrusted;
+        p.risks.elf_compat = RiskLevel::Stop;
         p.apply.best_effort_restore = false;
         p.backup.tag = "coreutils".to_string();
 
@@ -195,6 +241,7 @@ impl Policy {
         self.durability.preservation = PreservationPolicy::RequireBasic;
         self.apply.override_preflight = false;
         self.risks.source_trust = SourceTrustPolicy::RequireTrusted;
+        self.risks.elf_compat = RiskLevel::Stop;
         self.apply.best_effort_restore = false;
         self.backup.tag = "coreutils".to_string();
         self.apply.extra_mount_checks = vec![
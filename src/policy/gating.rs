@@ -1,6 +1,10 @@
-use crate::api::DebugOwnershipOracle;
-use crate::policy::types::{RiskLevel, SourceTrustPolicy};
+use crate::api::{DebugOwnershipOracle, DebugPolicyCheck};
+use crate::fs::fstype::{fs_type_at, fs_type_of, FsClass, FsType};
+use crate::fs::mount::{MountTable, TargetMount};
+use crate::policy::checks::{finding_message, finding_record, CheckSeverity};
+use crate::policy::types::{ElfTarget, ExdevPolicy, FsClassAction, RiskLevel, SourceTrustPolicy};
 use crate::policy::Policy;
+use crate::preflight::busy::{BusyIndex, BusyProcess};
 use crate::types::plan::Action;
 use crate::types::Plan;
 
@@ -10,6 +14,21 @@ pub(crate) struct Evaluation {
     pub policy_ok: bool,
     pub stops: Vec<String>,
     pub notes: Vec<String>,
+    /// Non-blocking findings from custom checks, surfaced as preflight warnings.
+    pub warnings: Vec<String>,
+    /// Structured custom check findings for preflight rows.
+    pub checks: Vec<serde_json::Value>,
+}
+
+impl Evaluation {
+    /// Fold another evaluation's findings into this one.
+    pub(crate) fn absorb(&mut self, other: Evaluation) {
+        self.stops.extend(other.stops);
+        self.notes.extend(other.notes);
+        self.warnings.extend(other.warnings);
+        self.checks.extend(other.checks);
+        self.policy_ok = self.stops.is_empty();
+    }
 }
 
 /// Evaluate policy gating for a single action.
@@ -20,10 +39,12 @@ pub(crate) struct Evaluation {
 pub(crate) fn evaluate_action(
     policy: &Policy,
     owner: Option<&dyn DebugOwnershipOracle>,
+    checks: &[Box<dyn DebugPolicyCheck>],
     act: &Action,
 ) -> Evaluation {
     let mut stops: Vec<String> = Vec::new();
     let mut notes: Vec<String> = Vec::new();
+    let mut warnings: Vec<String> = Vec::new();
 
     match act {
         Action::EnsureSymlink { source, target } => {
@@ -46,14 +67,7 @@ pub(crate) fn evaluate_action(
             } else {
                 notes.push("mount ok: target rw+exec".to_string());
             }
-            if let Err(e) = crate::preflight::checks::check_immutable(&target.as_path()) {
-                stops.push(format!(
-                    "immutable target: {} (target={})",
-                    e,
-                    target.as_path().display()
-                ));
-                notes.push("immutable target".to_string());
-            }
+            inode_flag_gates(policy, &target.as_path(), &mut stops, &mut notes);
             if let Ok(hard) = crate::preflight::checks::check_hardlink_hazard(&target.as_path()) {
                 if hard {
                     match policy.risks.hardlinks {
@@ -109,6 +123,24 @@ pub(crate) fn evaluate_action(
                     }
                 }
             }
+            let expect = policy.risks.elf_target.or_else(ElfTarget::host);
+            for p in
+                crate::preflight::elf::check_elf_compat(&source.as_path(), source.root(), expect)
+            {
+                match policy.risks.elf_compat {
+                    RiskLevel::Stop => {
+                        stops.push(format!("elf incompatible: {p}"));
+                        notes.push("elf incompatible".to_string());
+                    }
+                    RiskLevel::Warn => {
+                        warnings.push(format!("elf incompatible: {p}"));
+                        notes.push("elf incompatible".to_string());
+                    }
+                    RiskLevel::Allow => {
+                        notes.push(format!("elf incompatible ignored by policy: {p}"));
+                    }
+                }
+            }
             if policy.risks.ownership_strict {
                 if let Some(oracle) = owner {
                     if let Err(e) = oracle.owner_of(target) {
@@ -167,14 +199,7 @@ pub(crate) fn evaluate_action(
             } else {
                 notes.push("mount ok: target rw+exec".to_string());
             }
-            if let Err(e) = crate::preflight::checks::check_immutable(&target.as_path()) {
-                stops.push(format!(
-                    "immutable target: {} (target={})",
-                    e,
-                    target.as_path().display()
-                ));
-                notes.push("immutable target".to_string());
-            }
+            inode_flag_gates(policy, &target.as_path(), &mut stops, &mut notes);
             if let Ok(risk) = crate::preflight::checks::check_suid_sgid_risk(&target.as_path()) {
                 if risk {
                     match policy.risks.suid_sgid {
@@ -218,10 +243,208 @@ pub(crate) fn evaluate_action(
         }
     }
 
-    Evaluation {
+    let mut eval = Evaluation {
         policy_ok: stops.is_empty(),
         stops,
         notes,
+        warnings,
+        ..Evaluation::default()
+    };
+    for check in checks {
+        merge_findings(&mut eval, check.name(), check.check_action(policy, act));
+    }
+    eval.policy_ok = eval.stops.is_empty();
+    eval
+}
+
+/// Immutable/append-only STOPs and nodump notes, honoring `allow_unreliable_immutable_check`.
+fn inode_flag_gates(
+    policy: &Policy,
+    path: &std::path::Path,
+    stops: &mut Vec<String>,
+    notes: &mut Vec<String>,
+) {
+    let res =
+        crate::preflight::checks::check_inode_flags(path, policy.allow_unreliable_immutable_check);
+    for (kind, e) in res.stops {
+        stops.push(format!(
+            "{}: {} (target={})",
+            kind.label(),
+            e,
+            path.display()
+        ));
+        notes.push(kind.label().to_string());
+    }
+    notes.extend(res.notes);
+}
+
+/// Busy-binary gating for one action against a process index built once per plan.
+///
+/// Returns the evaluation (per `risks.busy_binaries`) and the matching processes for rows.
+pub(crate) fn evaluate_busy(
+    policy: &Policy,
+    index: &BusyIndex,
+    act: &Action,
+) -> (Evaluation, Vec<BusyProcess>) {
+    let target = match act {
+        Action::EnsureSymlink { target, .. } | Action::RestoreFromBackup { target } => target,
+    };
+    let procs = index.lookup(&target.as_path());
+    let mut eval = Evaluation::default();
+    if !procs.is_empty() {
+        let who = procs
+            .iter()
+            .map(|p| format!("{} ({})", p.pid, p.comm))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let msg = format!(
+            "busy binary: {} in use by {}",
+            target.as_path().display(),
+            who
+        );
+        match policy.risks.busy_binaries {
+            RiskLevel::Stop => {
+                eval.stops.push(msg);
+                eval.notes.push("busy binary".to_string());
+            }
+            RiskLevel::Warn => {
+                eval.warnings.push(msg);
+                eval.notes.push("busy binary".to_string());
+            }
+            RiskLevel::Allow => {
+                eval.notes.push("busy binary ignored by policy".to_string());
+            }
+        }
+    }
+    eval.policy_ok = eval.stops.is_empty();
+    (eval, procs)
+}
+
+/// Filesystem-class gating for one action: the target's filesystem and its backup directory's.
+///
+/// Returns the evaluation (per `risks.fs_classes`) and the target's filesystem type for rows.
+pub(crate) fn evaluate_fs_type(policy: &Policy, act: &Action) -> (Evaluation, Option<FsType>) {
+    let mut eval = Evaluation::default();
+    let target = match act {
+        Action::EnsureSymlink { target, .. } | Action::RestoreFromBackup { target } => {
+            target.as_path()
+        }
+    };
+    let at_target = fs_type_at(&target);
+    let at_backup = target.parent().and_then(fs_type_of);
+    let mut seen: Vec<FsClass> = Vec::new();
+    for ty in [at_target, at_backup].into_iter().flatten() {
+        let Some(caveat) = ty.class.caveat() else {
+            continue;
+        };
+        if seen.contains(&ty.class) {
+            continue;
+        }
+        seen.push(ty.class);
+        let classes = &policy.risks.fs_classes;
+        let action = match ty.class {
+            FsClass::Network => classes.network,
+            FsClass::Fuse => classes.fuse,
+            FsClass::Overlay => classes.overlay,
+            FsClass::Tmpfs => classes.tmpfs,
+            FsClass::Local => FsClassAction::Allow,
+        };
+        let msg = format!("filesystem {} at {}: {caveat}", ty.name, target.display());
+        match action {
+            FsClassAction::Stop => {
+                eval.stops.push(msg);
+                eval.notes.push(format!("fs class {}", ty.class.as_str()));
+            }
+            FsClassAction::RequireDegraded
+                if !matches!(policy.apply.exdev, ExdevPolicy::DegradedFallback) =>
+            {
+                eval.stops
+                    .push(format!("{msg} (requires apply.exdev=DegradedFallback)"));
+                eval.notes.push(format!(
+                    "fs class {} requires degraded mode",
+                    ty.class.as_str()
+                ));
+            }
+            FsClassAction::Warn | FsClassAction::RequireDegraded => {
+                eval.warnings.push(msg);
+                eval.notes.push(format!("fs class {}", ty.class.as_str()));
+            }
+            FsClassAction::Allow => {
+                eval.notes
+                    .push(format!("fs class {} ignored by policy", ty.class.as_str()));
+            }
+        }
+    }
+    eval.policy_ok = eval.stops.is_empty();
+    (eval, at_target)
+}
+
+/// Mount analysis for one action against a mount table read once per plan.
+///
+/// Never blocks; predicts problems that would otherwise only surface during apply:
+/// - the target is its own mount (e.g. a bind-mounted file), so renaming over it fails with
+///   EXDEV/EBUSY;
+/// - a setuid/setgid source lives on a `nosuid` mount, so the bits would be ignored.
+///
+/// Returns the evaluation and the target's mount record for rows.
+pub(crate) fn evaluate_mount(
+    table: &MountTable,
+    act: &Action,
+) -> (Evaluation, Option<serde_json::Value>) {
+    let mut eval = Evaluation::default();
+    let target = match act {
+        Action::EnsureSymlink { target, .. } | Action::RestoreFromBackup { target } => target,
+    };
+    let tm = TargetMount::analyze(table, &target.as_path());
+    if tm.as_ref().and_then(|m| m.backup_same_dev) == Some(false) {
+        eval.warnings.push(format!(
+            "target is a separate mount; rename will fail with EXDEV: {}",
+            target.as_path().display()
+        ));
+    }
+    if let Action::EnsureSymlink { source, .. } = act {
+        use std::os::unix::fs::PermissionsExt;
+        let src = source.as_path();
+        let setid = std::fs::metadata(&src).is_ok_and(|m| m.permissions().mode() & 0o6000 != 0);
+        if setid && table.find(&src).is_some_and(|m| m.flags.no_suid) {
+            eval.warnings.push(format!(
+                "source on nosuid mount; setuid/setgid bits will be ignored: {}",
+                src.display()
+            ));
+        }
+    }
+    eval.policy_ok = true;
+    (eval, tm.as_ref().map(TargetMount::to_json))
+}
+
+/// Evaluate plan-level custom checks.
+pub(crate) fn evaluate_plan(
+    policy: &Policy,
+    checks: &[Box<dyn DebugPolicyCheck>],
+    plan: &Plan,
+) -> Evaluation {
+    let mut eval = Evaluation::default();
+    for check in checks {
+        merge_findings(&mut eval, check.name(), check.check_plan(policy, plan));
+    }
+    eval.policy_ok = eval.stops.is_empty();
+    eval
+}
+
+fn merge_findings(
+    eval: &mut Evaluation,
+    name: &str,
+    findings: Vec<crate::policy::checks::CheckFinding>,
+) {
+    for f in findings {
+        let msg = finding_message(name, &f);
+        match f.severity {
+            CheckSeverity::Stop => eval.stops.push(msg.clone()),
+            CheckSeverity::Warn => eval.warnings.push(msg.clone()),
+            CheckSeverity::Note => {}
+        }
+        eval.notes.push(msg);
+        eval.checks.push(finding_record(name, &f));
     }
 }
 
@@ -230,24 +453,38 @@ pub(crate) fn evaluate_action(
 pub(crate) fn gating_errors(
     policy: &Policy,
     owner: Option<&dyn DebugOwnershipOracle>,
+    checks: &[Box<dyn DebugPolicyCheck>],
     plan: &Plan,
 ) -> Vec<String> {
     let mut errs: Vec<String> = Vec::new();
 
     // Global rescue verification: if required by policy, STOP when unavailable.
     if policy.rescue.require
-        && !crate::policy::rescue::verify_rescue_tools_with_exec_min(
-            policy.rescue.exec_check,
-            policy.rescue.min_count,
+        && crate::policy::rescue::verify_rescue_for_plan(
+            &policy.rescue,
+            &policy.rescue_profiles,
+            plan,
+            None,
         )
+        .status
+        .is_err()
     {
         errs.push("rescue profile unavailable".to_string());
     }
 
+    errs.extend(evaluate_plan(policy, checks, plan).stops);
+
+    // Only pay for the /proc scan when busy binaries can actually block.
+    let busy = matches!(policy.risks.busy_binaries, RiskLevel::Stop).then(BusyIndex::scan);
     for act in &plan.actions {
-        let eval = evaluate_action(policy, owner, act);
+        let eval = evaluate_action(policy, owner, checks, act);
         errs.extend(eval.stops);
+        if let Some(index) = &busy {
+            errs.extend(evaluate_busy(policy, index, act).0.stops);
+        }
+        errs.extend(evaluate_fs_type(policy, act).0.stops);
     }
+    errs.extend(crate::preflight::space::SpaceBudget::estimate(policy, plan).shortfalls());
 
     errs
 }
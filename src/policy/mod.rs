@@ -7,6 +7,7 @@
 //! [`Switchyard`](crate::Switchyard) instance.
 //!
 //! Submodules:
+//! - `checks`: pluggable `PolicyCheck` trait for site-specific gates
 //! - `config`: policy struct and presets
 //! - `gating`: apply-stage gating parity with preflight
 //! - `rescue`: rescue toolset verification helpers
@@ -14,9 +15,11 @@
 //! The crate may expose compatibility re-exports at the top-level temporarily;
 //! prefer importing from `switchyard::policy`.
 
+pub mod checks;
 pub mod config;
 pub mod gating;
 pub mod rescue;
 pub mod types;
 
+pub use checks::{CheckFinding, CheckSeverity, PolicyCheck};
 pub use config::Policy;
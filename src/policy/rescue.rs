@@ -5,13 +5,21 @@
 //! - `BusyBox` present on PATH (preferred single-binary profile)
 //! - GNU core tools subset present on PATH (configurable minimum count)
 //!
+//! `verify_rescue_for_plan` is plan-aware: candidates that resolve to a plan target or source
+//! are excluded (and reported), since the change being protected would break them. It also
+//! honors `Policy::rescue_profiles` (static `BusyBox`/`toybox` with applet verification, or a
+//! named list of paths).
+//!
 //! Test override knobs:
 //! - `SWITCHYARD_FORCE_RESCUE_OK=1|0` forces the result for testing.
 //!
 use crate::constants::{RESCUE_MIN_COUNT, RESCUE_MUST_HAVE};
-use crate::types::{RescueError, RescueStatus};
+use crate::policy::types::{Rescue, RescueProfile};
+use crate::types::{Action, ExcludedRescueTool, Plan, RescueCheck, RescueError, RescueStatus};
+use std::collections::HashSet;
 use std::env;
 use std::ffi::OsString;
+use std::path::{Path, PathBuf};
 
 /// Verify that at least one rescue toolset is available on PATH (`BusyBox` or GNU core utilities).
 /// Wrapper that does not enforce executability checks.
@@ -67,43 +75,169 @@ pub fn verify_rescue(exec_check: bool) -> Result<RescueStatus, RescueError> {
 }
 
 fn verify_rescue_min(exec_check: bool, min_count: usize) -> Result<RescueStatus, RescueError> {
-    // Test override knobs: only honor when explicitly allowed in tests or when the
-    // SWITCHYARD_TEST_ALLOW_ENV_OVERRIDES=1 flag is set. This prevents accidental
-    // production behavior changes due to process-global env during integration tests.
+    if let Some(forced) = env_override(min_count) {
+        return forced;
+    }
+    verify_path_profile(
+        exec_check,
+        min_count,
+        &PlanPaths::default(),
+        &mut Vec::new(),
+    )
+}
+
+/// Test override knobs: only honor when explicitly allowed in tests or when the
+/// `SWITCHYARD_TEST_ALLOW_ENV_OVERRIDES=1` flag is set. This prevents accidental
+/// production behavior changes due to process-global env during integration tests.
+fn env_override(min_count: usize) -> Option<Result<RescueStatus, RescueError>> {
     let allow_env_overrides = cfg!(test)
         || env::var_os("SWITCHYARD_TEST_ALLOW_ENV_OVERRIDES") == Some(OsString::from("1"));
-    if allow_env_overrides {
-        if let Ok(v) = env::var("SWITCHYARD_FORCE_RESCUE_OK") {
-            let v = v.trim();
-            if v == "1" {
-                return Ok(RescueStatus::GNU {
-                    found: min_count,
-                    min: min_count,
-                });
+    if !allow_env_overrides {
+        return None;
+    }
+    match env::var("SWITCHYARD_FORCE_RESCUE_OK").ok()?.trim() {
+        "1" => Some(Ok(RescueStatus::GNU {
+            found: min_count,
+            min: min_count,
+        })),
+        "0" => Some(Err(RescueError::Unavailable)),
+        _ => None,
+    }
+}
+
+/// Verify rescue tooling for `plan` under the `rescue` policy and `profiles`
+/// (`Policy::rescue_profiles`).
+///
+/// Profiles are tried in order (`[RescueProfile::Path]` when none are configured) and the
+/// first available one wins. Candidates resolving to a plan target or source are excluded and
+/// listed in the result. `force_ok` short-circuits like [`verify_rescue_min_with_override`].
+#[must_use]
+pub fn verify_rescue_for_plan(
+    rescue: &Rescue,
+    profiles: &[RescueProfile],
+    plan: &Plan,
+    force_ok: Option<bool>,
+) -> RescueCheck {
+    let forced = match force_ok {
+        Some(true) => Some(Ok(RescueStatus::GNU {
+            found: rescue.min_count,
+            min: rescue.min_count,
+        })),
+        Some(false) => Some(Err(RescueError::Unavailable)),
+        None => env_override(rescue.min_count),
+    };
+    if let Some(status) = forced {
+        return RescueCheck {
+            status,
+            excluded: Vec::new(),
+        };
+    }
+    let touched = PlanPaths::of(plan);
+    let mut excluded = Vec::new();
+    let default_profiles = [RescueProfile::Path];
+    let profiles = if profiles.is_empty() {
+        &default_profiles[..]
+    } else {
+        profiles
+    };
+    let mut status = Err(RescueError::Unavailable);
+    for profile in profiles {
+        let r = match profile {
+            RescueProfile::Path => {
+                verify_path_profile(rescue.exec_check, rescue.min_count, &touched, &mut excluded)
+            }
+            RescueProfile::BusyBox(p) => {
+                verify_multicall("busybox", p, &["--list"], rescue, &touched, &mut excluded)
+                    .map(|path| RescueStatus::BusyBox { path })
+            }
+            RescueProfile::Toybox(p) => {
+                verify_multicall("toybox", p, &[], rescue, &touched, &mut excluded)
+                    .map(|path| RescueStatus::Toybox { path })
+            }
+            RescueProfile::Paths { name, paths } => {
+                verify_named(name, paths, rescue.exec_check, &touched, &mut excluded)
+            }
+        };
+        if r.is_ok() {
+            status = r;
+            break;
+        }
+    }
+    // Several profiles can exclude the same tool; report each once, in a stable order.
+    excluded.sort();
+    excluded.dedup();
+    RescueCheck { status, excluded }
+}
+
+/// Paths a plan replaces or links to, both as given and canonicalized.
+#[derive(Default)]
+struct PlanPaths(HashSet<PathBuf>);
+
+impl PlanPaths {
+    fn of(plan: &Plan) -> Self {
+        let mut set = HashSet::new();
+        let mut add = |p: PathBuf| {
+            if let Ok(c) = p.canonicalize() {
+                set.insert(c);
             }
-            if v == "0" {
-                return Err(RescueError::Unavailable);
+            set.insert(p);
+        };
+        for act in &plan.actions {
+            match act {
+                Action::EnsureSymlink { source, target } => {
+                    add(source.as_path());
+                    add(target.as_path());
+                }
+                Action::RestoreFromBackup { target } => add(target.as_path()),
             }
         }
+        PlanPaths(set)
     }
+
+    /// Whether `candidate` is, or resolves to, something the plan touches. Records it if so.
+    fn excludes(&self, tool: &str, candidate: &Path, out: &mut Vec<ExcludedRescueTool>) -> bool {
+        let hit = self.0.contains(candidate)
+            || candidate.canonicalize().is_ok_and(|c| self.0.contains(&c));
+        if hit {
+            out.push(ExcludedRescueTool {
+                tool: tool.to_string(),
+                path: candidate.to_path_buf(),
+            });
+        }
+        hit
+    }
+}
+
+fn verify_path_profile(
+    exec_check: bool,
+    min_count: usize,
+    touched: &PlanPaths,
+    excluded: &mut Vec<ExcludedRescueTool>,
+) -> Result<RescueStatus, RescueError> {
     // If exec checks are disabled and minimum required count is zero, treat as OK.
     if !exec_check && min_count == 0 {
         return Ok(RescueStatus::GNU { found: 0, min: 0 });
     }
-    // Prefer BusyBox (single binary) as a compact rescue profile
-    if let Some(p) = which_on_path("busybox") {
-        if !exec_check || is_executable(&p) {
-            return Ok(RescueStatus::BusyBox { path: p });
-        }
+    // Prefer BusyBox (single binary) as a compact rescue profile. Later PATH entries are
+    // considered when an earlier copy is the one the plan replaces.
+    let usable = |bin: &str, p: &String, excluded: &mut Vec<ExcludedRescueTool>| {
+        (!exec_check || is_executable(p)) && !touched.excludes(bin, Path::new(p), excluded)
+    };
+    if let Some(p) = which_all_on_path("busybox")
+        .into_iter()
+        .find(|p| usable("busybox", p, excluded))
+    {
+        return Ok(RescueStatus::BusyBox { path: p });
     }
     // Fallback: require a tiny subset of GNU core tools to be present
     let must_have = RESCUE_MUST_HAVE;
     let mut found = 0usize;
     for bin in must_have {
-        if let Some(p) = which_on_path(bin) {
-            if !exec_check || is_executable(&p) {
-                found += 1;
-            }
+        if which_all_on_path(bin)
+            .into_iter()
+            .any(|p| usable(bin, &p, excluded))
+        {
+            found += 1;
         }
     }
     if found >= min_count {
@@ -116,15 +250,77 @@ fn verify_rescue_min(exec_check: bool, min_count: usize) -> Result<RescueStatus,
     }
 }
 
-fn which_on_path(bin: &str) -> Option<String> {
-    let path = env::var_os("PATH")?;
-    for dir in env::split_paths(&path) {
-        let cand = dir.join(bin);
-        if cand.exists() {
-            return Some(cand.display().to_string());
-        }
+/// Verify a multi-call binary (`busybox --list`, `toybox`) provides enough rescue applets.
+fn verify_multicall(
+    tool: &str,
+    path: &Path,
+    args: &[&str],
+    rescue: &Rescue,
+    touched: &PlanPaths,
+    excluded: &mut Vec<ExcludedRescueTool>,
+) -> Result<String, RescueError> {
+    let shown = path.display().to_string();
+    if !path.is_file()
+        || (rescue.exec_check && !is_executable(&shown))
+        || touched.excludes(tool, path, excluded)
+    {
+        return Err(RescueError::Unavailable);
+    }
+    let out = std::process::Command::new(path)
+        .args(args)
+        .stdin(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .output()
+        .map_err(|_| RescueError::Unavailable)?;
+    let listing = String::from_utf8_lossy(&out.stdout);
+    let applets: HashSet<&str> = listing.split_whitespace().collect();
+    let found = RESCUE_MUST_HAVE
+        .iter()
+        .filter(|t| applets.contains(**t))
+        .count();
+    if found >= rescue.min_count {
+        Ok(shown)
+    } else {
+        Err(RescueError::Unavailable)
+    }
+}
+
+fn verify_named(
+    name: &str,
+    paths: &[PathBuf],
+    exec_check: bool,
+    touched: &PlanPaths,
+    excluded: &mut Vec<ExcludedRescueTool>,
+) -> Result<RescueStatus, RescueError> {
+    let mut ok = !paths.is_empty();
+    for p in paths {
+        let tool = p.file_name().map_or_else(
+            || p.display().to_string(),
+            |f| f.to_string_lossy().into_owned(),
+        );
+        let usable = p.is_file() && (!exec_check || is_executable(&p.display().to_string()));
+        // Evaluate exclusion for every path so all of them are reported.
+        let touched_by_plan = touched.excludes(&tool, p, excluded);
+        ok &= usable && !touched_by_plan;
     }
-    None
+    if ok {
+        Ok(RescueStatus::Named {
+            name: name.to_string(),
+            found: paths.len(),
+        })
+    } else {
+        Err(RescueError::Unavailable)
+    }
+}
+
+/// Every existing `bin` on PATH, in PATH order.
+fn which_all_on_path(bin: &str) -> Vec<String> {
+    let path = env::var_os("PATH").unwrap_or_default();
+    env::split_paths(&path)
+        .map(|dir| dir.join(bin))
+        .filter(|cand| cand.exists())
+        .map(|cand| cand.display().to_string())
+        .collect()
 }
 
 #[cfg(unix)]
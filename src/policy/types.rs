@@ -8,6 +8,80 @@ pub enum RiskLevel {
     Allow,
 }
 
+/// ELF identity expected of symlink sources: machine (`e_machine`), class, and byte order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ElfTarget {
+    pub machine: u16,
+    pub class64: bool,
+    pub little_endian: bool,
+}
+
+impl ElfTarget {
+    /// Target for a Rust/uname-style architecture name (e.g., `x86_64`, `aarch64`).
+    #[must_use]
+    pub fn from_arch(name: &str) -> Option<Self> {
+        let (machine, class64, little_endian) = match name {
+            "x86_64" | "amd64" => (62, true, true),
+            "x86" | "i386" | "i686" => (3, false, true),
+            "aarch64" | "arm64" => (183, true, true),
+            "arm" | "armv7" | "armhf" => (40, false, true),
+            "riscv64" => (243, true, true),
+            "powerpc64le" | "ppc64le" => (21, true, true),
+            "powerpc64" | "ppc64" => (21, true, false),
+            "s390x" => (22, true, false),
+            "loongarch64" => (258, true, true),
+            _ => return None,
+        };
+        Some(Self {
+            machine,
+            class64,
+            little_endian,
+        })
+    }
+
+    /// Target matching the running host, when the architecture is known.
+    #[must_use]
+    pub fn host() -> Option<Self> {
+        Self::from_arch(std::env::consts::ARCH).map(|t| Self {
+            little_endian: cfg!(target_endian = "little"),
+            ..t
+        })
+    }
+}
+
+/// Handling for one filesystem class (see `fs::fstype::FsClass`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FsClassAction {
+    Allow,
+    Warn,
+    Stop,
+    /// Proceed only when `apply.exdev` is `DegradedFallback`; STOP otherwise.
+    RequireDegraded,
+}
+
+/// Per-class handling of filesystems whose rename/fsync guarantees differ from local disks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FsClassPolicy {
+    /// NFS, SMB/CIFS, Ceph, 9p, AFS.
+    pub network: FsClassAction,
+    pub fuse: FsClassAction,
+    /// overlayfs: replacing a lower-layer file triggers copy-up.
+    pub overlay: FsClassAction,
+    /// tmpfs/ramfs: nothing survives a reboot, backups included.
+    pub tmpfs: FsClassAction,
+}
+
+impl Default for FsClassPolicy {
+    fn default() -> Self {
+        Self {
+            network: FsClassAction::Warn,
+            fuse: FsClassAction::Warn,
+            overlay: FsClassAction::Warn,
+            tmpfs: FsClassAction::Allow,
+        }
+    }
+}
+
 /// Cross‑filesystem behavior policy for atomic rename failures (EXDEV).
 #[derive(Clone, Copy, Debug, Default)]
 pub enum ExdevPolicy {
@@ -23,6 +97,46 @@ pub enum LockingPolicy {
     Optional,
 }
 
+/// What apply locks in Commit mode.
+///
+/// Path scopes lock each distinct key in canonical (sorted) order, so concurrent applies over
+/// unrelated trees do not serialize and overlapping ones cannot deadlock. Lock managers without
+/// path-lock support fall back to the process lock.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LockScope {
+    /// One host-wide process lock.
+    #[default]
+    Global,
+    /// The canonical parent directory of every target.
+    ParentDirs,
+    /// Every target path (canonical parent joined with the file name).
+    Targets,
+}
+
+/// Whether apply may mutate when audit facts cannot be recorded.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AuditDurability {
+    /// Emission failures never stop apply; they are listed in `ApplyReport::audit_failures`.
+    #[default]
+    BestEffort,
+    /// In Commit mode each action's `apply.attempt` must be durably recorded
+    /// (`FactsEmitter::try_emit` then `flush`) before the action mutates anything. A failure is a
+    /// hard stop with `E_AUDIT`; failures after mutation are still only reported.
+    Required,
+}
+
+impl LockScope {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            LockScope::Global => "global",
+            LockScope::ParentDirs => "parent_dirs",
+            LockScope::Targets => "targets",
+        }
+    }
+}
+
 /// Preservation requirement policy for metadata dimensions.
 #[derive(Clone, Copy, Debug)]
 pub enum PreservationPolicy {
@@ -60,13 +174,35 @@ pub struct Rescue {
     pub min_count: usize,
 }
 
+/// Where rescue tooling comes from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RescueProfile {
+    /// `busybox` on PATH, else at least `min_count` GNU core tools on PATH.
+    Path,
+    /// A specific (typically static) `BusyBox`; its applet list must cover `min_count` rescue tools.
+    BusyBox(PathBuf),
+    /// A specific `toybox`; its command list must cover `min_count` rescue tools.
+    Toybox(PathBuf),
+    /// A named list of tool paths, all of which must be present.
+    Paths { name: String, paths: Vec<PathBuf> },
+}
+
 /// Risk controls toggles.
 #[derive(Debug, Copy, Clone)]
 pub struct Risks {
     pub suid_sgid: RiskLevel,
     pub hardlinks: RiskLevel,
+    /// Targets currently executed or mapped by running processes (see `preflight::busy`).
+    pub busy_binaries: RiskLevel,
+    /// ELF sources that do not match the target arch or whose interpreter/libraries are
+    /// missing under the root (see `preflight::elf`).
+    pub elf_compat: RiskLevel,
+    /// Expected ELF machine/class/endianness for sources; `None` means the host.
+    pub elf_target: Option<ElfTarget>,
     pub source_trust: SourceTrustPolicy,
     pub ownership_strict: bool,
+    /// Handling of targets and backup directories on filesystems with weaker guarantees.
+    pub fs_classes: FsClassPolicy,
 }
 
 impl Default for Risks {
@@ -74,8 +210,12 @@ impl Default for Risks {
         Self {
             suid_sgid: RiskLevel::Stop,
             hardlinks: RiskLevel::Stop,
+            busy_binaries: RiskLevel::Warn,
+            elf_compat: RiskLevel::Warn,
+            elf_target: None,
             source_trust: SourceTrustPolicy::RequireTrusted,
             ownership_strict: false,
+            fs_classes: FsClassPolicy::default(),
         }
     }
 }
@@ -86,6 +226,9 @@ pub struct Durability {
     pub backup_durability: bool,
     pub sidecar_integrity: bool,
     pub preservation: PreservationPolicy,
+    /// Safety margin, in percent of the estimated need, that each filesystem must have free
+    /// beyond the bytes and inodes the plan's backups will consume (default 10).
+    pub space_margin_percent: u32,
 }
 
 impl Default for Durability {
@@ -94,6 +237,7 @@ impl Default for Durability {
             backup_durability: true,
             sidecar_integrity: true,
             preservation: PreservationPolicy::Off,
+            space_margin_percent: 10,
         }
     }
 }
@@ -112,6 +256,11 @@ pub struct ApplyFlow {
 #[derive(Debug, Copy, Clone)]
 pub struct Governance {
     pub locking: LockingPolicy,
+    pub lock_scope: LockScope,
+    /// Preflight takes a shared lock over the keys apply locks exclusively, so it never reports
+    /// on a half-applied plan. Requires a lock manager; ignored without one.
+    pub preflight_shared_lock: bool,
+    pub audit_durability: AuditDurability,
     pub smoke: SmokePolicy,
     pub allow_unlocked_commit: bool,
 }
@@ -120,6 +269,9 @@ impl Default for Governance {
     fn default() -> Self {
         Self {
             locking: LockingPolicy::Optional,
+            lock_scope: LockScope::Global,
+            preflight_shared_lock: false,
+            audit_durability: AuditDurability::BestEffort,
             smoke: SmokePolicy::Off,
             allow_unlocked_commit: true,
         }
@@ -131,3 +283,12 @@ impl Default for Governance {
 pub struct Backup {
     pub tag: String,
 }
+
+/// Audit trail integrity.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Audit {
+    /// Chain each run's facts: every fact carries `prev_hash` and `hash` (SHA-256 over its
+    /// canonical JSON), and the apply attestation covers the chain head. Verify logs with
+    /// `logging::chain::verify_chain`.
+    pub hash_chain: bool,
+}
@@ -70,40 +70,119 @@ pub fn check_suid_sgid_risk(path: &Path) -> std::io::Result<bool> {
     Ok(false)
 }
 
-/// Best-effort check for the immutable attribute via `lsattr -d`.
-/// Returns `Err(String)` only when the target itself is immutable.
-/// If `lsattr` is missing or fails, this returns `Ok(())` (best-effort).
-///
-/// # Errors
-///
-/// Returns an error string if the target is immutable.
-pub fn check_immutable(path: &Path) -> Result<(), String> {
-    // Heuristic via lsattr -d; best-effort and non-fatal when unavailable
-    let Ok(output) = std::process::Command::new("lsattr")
-        .arg("-d")
-        .arg(path) // avoid lossy UTF-8 conversion
-        .output()
-    else {
-        return Ok(());
-    };
+/// Why the inode flag probe blocks a target.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InodeFlagStopKind {
+    /// `chattr +i`.
+    Immutable,
+    /// `chattr +a`.
+    AppendOnly,
+    /// The filesystem lacks `FS_IOC_GETFLAGS` and `allow_unreliable` is not set.
+    Unreliable,
+    /// The flags could not be read for another reason (e.g. `EACCES`).
+    Unreadable,
+}
 
-    if !output.status.success() {
-        return Ok(()); // non-zero exit from lsattr -> treat as inconclusive
+impl InodeFlagStopKind {
+    /// Prefix for the preflight STOP and the row note.
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            InodeFlagStopKind::Immutable => "immutable target",
+            InodeFlagStopKind::AppendOnly => "append-only target",
+            InodeFlagStopKind::Unreliable => "inode flags unreliable",
+            InodeFlagStopKind::Unreadable => "inode flags unreadable",
+        }
     }
+}
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    for line in stdout.lines() {
-        if let Some(attrs) = line.split_whitespace().next() {
-            if attrs.contains('i') {
-                return Err(format!(
-                    "Target '{}' is immutable (chattr +i). Run: chattr -i -- {}",
-                    path.display(),
-                    path.display()
+/// Outcome of the inode flag probe used by preflight gating.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct InodeFlagCheck {
+    /// Blocking findings with their kind (immutable, append-only, an unreliable probe when not
+    /// allowed, or unreadable flags).
+    pub stops: Vec<(InodeFlagStopKind, String)>,
+    /// Informational findings (nodump, or an unreliable probe allowed by policy).
+    pub notes: Vec<String>,
+}
+
+/// Check immutable/append-only/nodump inode flags via `FS_IOC_GETFLAGS`.
+///
+/// - Immutable (`+i`) and append-only (`+a`) targets cannot be replaced and yield STOPs.
+/// - Nodump (`+d`) is reported as a note only.
+/// - When the filesystem lacks `FS_IOC_GETFLAGS` (`ENOTTY`/`EOPNOTSUPP`/`EINVAL`) the probe is
+///   unreliable: STOP unless `allow_unreliable` is set, in which case a note is recorded and the
+///   check passes.
+/// - A target that vanished since it was listed has no flags to check. Any other error (e.g.
+///   `EACCES` opening the target) is a STOP with its own message regardless of
+///   `allow_unreliable`.
+#[must_use]
+pub fn check_inode_flags(path: &Path, allow_unreliable: bool) -> InodeFlagCheck {
+    let mut out = InodeFlagCheck::default();
+    match crate::fs::meta::inode_flags(path) {
+        Ok(None) => {}
+        Ok(Some(f)) => {
+            if f.immutable {
+                out.stops.push((
+                    InodeFlagStopKind::Immutable,
+                    format!(
+                        "Target '{}' is immutable (chattr +i). Run: chattr -i -- {}",
+                        path.display(),
+                        path.display()
+                    ),
                 ));
             }
+            if f.append_only {
+                out.stops.push((
+                    InodeFlagStopKind::AppendOnly,
+                    format!(
+                        "Target '{}' is append-only (chattr +a). Run: chattr -a -- {}",
+                        path.display(),
+                        path.display()
+                    ),
+                ));
+            }
+            if f.nodump {
+                out.notes.push("nodump flag set on target".to_string());
+            }
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::Unsupported => {
+            if allow_unreliable {
+                out.notes.push(format!(
+                    "immutable check unreliable (allowed by policy): {e}"
+                ));
+            } else {
+                out.stops.push((
+                    InodeFlagStopKind::Unreliable,
+                    format!("immutable check unreliable for '{}': {e}", path.display()),
+                ));
+            }
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => {
+            out.stops.push((
+                InodeFlagStopKind::Unreadable,
+                format!("cannot read inode flags of '{}': {e}", path.display()),
+            ));
         }
     }
-    Ok(())
+    out
+}
+
+/// Best-effort check for the immutable or append-only attribute.
+/// Returns `Err(String)` only when the target itself is immutable or append-only.
+/// If the flags cannot be read, this returns `Ok(())` (best-effort); use
+/// [`check_inode_flags`] for policy-aware handling of unreliable probes.
+///
+/// # Errors
+///
+/// Returns an error string if the target is immutable or append-only.
+pub fn check_immutable(path: &Path) -> Result<(), String> {
+    let res = check_inode_flags(path, true);
+    match res.stops.into_iter().next() {
+        Some((_, e)) => Err(e),
+        None => Ok(()),
+    }
 }
 
 /// Source trust checks. Returns Err(String) if untrusted and `force` is false. When `force` is true,
@@ -0,0 +1,470 @@
+//! ELF compatibility validation for symlink sources.
+//!
+//! A minimal, allocation-light ELF reader: it parses the identification bytes, program headers,
+//! `PT_INTERP`, and the `PT_DYNAMIC` entries needed to list `DT_NEEDED`, `DT_RUNPATH` and
+//! `DT_RPATH`. No section headers are read, so stripped binaries are fine.
+//!
+//! [`check_elf_compat`] then confirms that a source:
+//! - matches the expected machine/class/byte order (host or `Policy.risks.elf_target`);
+//! - has its program interpreter present under the `SafePath` root;
+//! - has each `DT_NEEDED` library resolvable under the root in `DT_RUNPATH`/`DT_RPATH` or the
+//!   standard library directories (including multiarch `*-linux-*` subdirectories).
+//!
+//! Paths are resolved as if chrooted at the root: absolute symlinks inside it (e.g.
+//! `lib64/ld-linux-x86-64.so.2 -> /lib/x86_64-linux-gnu/...`) point into the root, not the host.
+//!
+//! Non-ELF sources (e.g., scripts) are not checked.
+use std::fs::File;
+use std::os::unix::fs::FileExt as _;
+use std::path::{Path, PathBuf};
+
+use crate::fs::paths::resolve_in_root;
+use crate::policy::types::ElfTarget;
+
+const PT_LOAD: u32 = 1;
+const PT_DYNAMIC: u32 = 2;
+const PT_INTERP: u32 = 3;
+const DT_NULL: u64 = 0;
+const DT_NEEDED: u64 = 1;
+const DT_STRTAB: u64 = 5;
+const DT_RPATH: u64 = 15;
+const DT_RUNPATH: u64 = 29;
+/// Upper bound on program headers and dynamic entries read, to bound work on hostile input.
+const MAX_ENTRIES: u64 = 4096;
+
+/// Facts read from an ELF file.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ElfInfo {
+    pub class64: bool,
+    pub little_endian: bool,
+    pub machine: u16,
+    /// Program interpreter from `PT_INTERP` (dynamic executables only).
+    pub interp: Option<String>,
+    /// `DT_NEEDED` sonames.
+    pub needed: Vec<String>,
+    /// `DT_RUNPATH` (or, when absent, `DT_RPATH`) entries.
+    pub runpath: Vec<String>,
+}
+
+struct Reader {
+    f: File,
+    class64: bool,
+    le: bool,
+}
+
+impl Reader {
+    fn bytes(&self, off: u64, len: usize) -> std::io::Result<Vec<u8>> {
+        let mut buf = vec![0u8; len];
+        self.f.read_exact_at(&mut buf, off)?;
+        Ok(buf)
+    }
+
+    fn u16_at(&self, b: &[u8], i: usize) -> Option<u16> {
+        let x = array_at(b, i)?;
+        Some(if self.le {
+            u16::from_le_bytes(x)
+        } else {
+            u16::from_be_bytes(x)
+        })
+    }
+
+    fn u32_at(&self, b: &[u8], i: usize) -> Option<u32> {
+        let x = array_at(b, i)?;
+        Some(if self.le {
+            u32::from_le_bytes(x)
+        } else {
+            u32::from_be_bytes(x)
+        })
+    }
+
+    fn u64_at(&self, b: &[u8], i: usize) -> Option<u64> {
+        let x = array_at(b, i)?;
+        Some(if self.le {
+            u64::from_le_bytes(x)
+        } else {
+            u64::from_be_bytes(x)
+        })
+    }
+
+    /// Read a class-sized word (u32 for ELF32, u64 for ELF64).
+    fn word_at(&self, b: &[u8], i: usize) -> Option<u64> {
+        if self.class64 {
+            self.u64_at(b, i)
+        } else {
+            self.u32_at(b, i).map(u64::from)
+        }
+    }
+
+    fn cstr_at(&self, off: u64) -> std::io::Result<String> {
+        let mut out = Vec::new();
+        let mut pos = off;
+        loop {
+            let mut chunk = [0u8; 64];
+            let n = self.f.read_at(&mut chunk, pos)?;
+            if n == 0 {
+                break;
+            }
+            let got = chunk.get(..n).unwrap_or_default();
+            if let Some(z) = got.iter().position(|&c| c == 0) {
+                out.extend(got.iter().take(z));
+                break;
+            }
+            out.extend_from_slice(got);
+            pos = pos.saturating_add(n as u64);
+            if out.len() > 4096 {
+                break;
+            }
+        }
+        Ok(String::from_utf8_lossy(&out).into_owned())
+    }
+}
+
+/// `N` bytes of `b` at `i`; `None` when `b` is too short.
+fn array_at<const N: usize>(b: &[u8], i: usize) -> Option<[u8; N]> {
+    b.get(i..i.checked_add(N)?)?.try_into().ok()
+}
+
+fn truncated() -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, "truncated ELF")
+}
+
+struct Phdr {
+    kind: u32,
+    offset: u64,
+    vaddr: u64,
+    filesz: u64,
+}
+
+/// Parse the ELF at `path`. Returns `Ok(None)` when the file is not ELF.
+///
+/// # Errors
+///
+/// Returns an IO error if the file cannot be read or is truncated.
+pub fn parse_elf(path: &Path) -> std::io::Result<Option<ElfInfo>> {
+    let f = File::open(path)?;
+    let mut ident = [0u8; 16];
+    if f.read_exact_at(&mut ident, 0).is_err() || ident[..4] != *b"\x7fELF" {
+        return Ok(None);
+    }
+    let class64 = match ident[4] {
+        1 => false,
+        2 => true,
+        _ => return Ok(None),
+    };
+    let le = match ident[5] {
+        1 => true,
+        2 => false,
+        _ => return Ok(None),
+    };
+    let r = Reader { f, class64, le };
+    let ehdr = r.bytes(0, if class64 { 64 } else { 52 })?;
+    let header = || {
+        let machine = r.u16_at(&ehdr, 18)?;
+        Some(if class64 {
+            (
+                machine,
+                r.u64_at(&ehdr, 32)?,
+                r.u16_at(&ehdr, 54)?,
+                r.u16_at(&ehdr, 56)?,
+            )
+        } else {
+            (
+                machine,
+                u64::from(r.u32_at(&ehdr, 28)?),
+                r.u16_at(&ehdr, 42)?,
+                r.u16_at(&ehdr, 44)?,
+            )
+        })
+    };
+    let (machine, phoff, phentsize, phnum) = header().ok_or_else(truncated)?;
+    let mut info = ElfInfo {
+        class64,
+        little_endian: le,
+        machine,
+        ..ElfInfo::default()
+    };
+
+    let phdrs = read_phdrs(&r, phoff, phentsize, phnum)?;
+    if let Some(p) = phdrs.iter().find(|p| p.kind == PT_INTERP) {
+        info.interp = Some(r.cstr_at(p.offset)?);
+    }
+    read_dynamic(&r, &phdrs, &mut info)?;
+    Ok(Some(info))
+}
+
+fn read_phdrs(r: &Reader, phoff: u64, phentsize: u16, phnum: u16) -> std::io::Result<Vec<Phdr>> {
+    let min_phent: u16 = if r.class64 { 56 } else { 32 };
+    let mut phdrs = Vec::new();
+    if phentsize < min_phent {
+        return Ok(phdrs);
+    }
+    for i in 0..u64::from(phnum) {
+        let off = phoff.saturating_add(i.saturating_mul(u64::from(phentsize)));
+        let b = r.bytes(off, usize::from(min_phent))?;
+        phdrs.push(if r.class64 {
+            Phdr {
+                kind: r.u32_at(&b, 0).ok_or_else(truncated)?,
+                offset: r.u64_at(&b, 8).ok_or_else(truncated)?,
+                vaddr: r.u64_at(&b, 16).ok_or_else(truncated)?,
+                filesz: r.u64_at(&b, 32).ok_or_else(truncated)?,
+            }
+        } else {
+            Phdr {
+                kind: r.u32_at(&b, 0).ok_or_else(truncated)?,
+                offset: u64::from(r.u32_at(&b, 4).ok_or_else(truncated)?),
+                vaddr: u64::from(r.u32_at(&b, 8).ok_or_else(truncated)?),
+                filesz: u64::from(r.u32_at(&b, 16).ok_or_else(truncated)?),
+            }
+        });
+    }
+    Ok(phdrs)
+}
+
+/// Fill `needed` and `runpath` from `PT_DYNAMIC`, when present.
+fn read_dynamic(r: &Reader, phdrs: &[Phdr], info: &mut ElfInfo) -> std::io::Result<()> {
+    let Some(dynamic) = phdrs.iter().find(|p| p.kind == PT_DYNAMIC) else {
+        return Ok(());
+    };
+    let (entsize, half) = if r.class64 { (16u64, 8) } else { (8u64, 4) };
+    let mut strtab_vaddr = None;
+    let mut needed_offs = Vec::new();
+    let mut runpath_off = None;
+    let mut rpath_off = None;
+    for i in 0..(dynamic.filesz / entsize).min(MAX_ENTRIES) {
+        let off = dynamic.offset.saturating_add(i.saturating_mul(entsize));
+        let b = r.bytes(off, half * 2)?;
+        let val = r.word_at(&b, half).ok_or_else(truncated)?;
+        match r.word_at(&b, 0).ok_or_else(truncated)? {
+            DT_NULL => break,
+            DT_NEEDED => needed_offs.push(val),
+            DT_STRTAB => strtab_vaddr = Some(val),
+            DT_RUNPATH => runpath_off = Some(val),
+            DT_RPATH => rpath_off = Some(val),
+            _ => {}
+        }
+    }
+    // DT_STRTAB is a virtual address; map it to a file offset through PT_LOAD segments.
+    let Some(strtab) = strtab_vaddr.and_then(|va| {
+        phdrs
+            .iter()
+            .find(|p| p.kind == PT_LOAD && va >= p.vaddr && va < p.vaddr.saturating_add(p.filesz))
+            .and_then(|p| va.checked_sub(p.vaddr)?.checked_add(p.offset))
+    }) else {
+        return Ok(());
+    };
+    for off in needed_offs {
+        info.needed.push(r.cstr_at(strtab.saturating_add(off))?);
+    }
+    if let Some(off) = runpath_off.or(rpath_off) {
+        info.runpath = r
+            .cstr_at(strtab.saturating_add(off))?
+            .split(':')
+            .filter(|s| !s.is_empty())
+            .map(ToString::to_string)
+            .collect();
+    }
+    Ok(())
+}
+
+/// Standard library directories searched under the root, plus multiarch subdirectories, as
+/// root-relative paths.
+fn library_dirs(root: &Path) -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    for base in [
+        "lib",
+        "lib64",
+        "lib32",
+        "usr/lib",
+        "usr/lib64",
+        "usr/lib32",
+        "usr/local/lib",
+    ] {
+        let d = PathBuf::from(base);
+        if let Some(rd) = resolve_in_root(root, &d).and_then(|p| std::fs::read_dir(p).ok()) {
+            for ent in rd.flatten() {
+                let name = d.join(ent.file_name());
+                if ent
+                    .file_name()
+                    .to_str()
+                    .is_some_and(|n| n.contains("-linux-"))
+                    && resolve_in_root(root, &name).is_some_and(|p| p.is_dir())
+                {
+                    dirs.push(name);
+                }
+            }
+        }
+        dirs.push(d);
+    }
+    dirs
+}
+
+/// Expand `$ORIGIN`/`${ORIGIN}` in a `DT_RUNPATH` entry to `origin` (the in-root directory of
+/// the binary).
+fn expand_origin(entry: &str, origin: &str) -> String {
+    entry
+        .replace("${ORIGIN}", origin)
+        .replace("$ORIGIN", origin)
+}
+
+/// Validate `source` against `expect` and the library layout under `root`.
+/// Returns human-readable problems; empty when compatible or when `source` is not ELF.
+#[must_use]
+pub fn check_elf_compat(source: &Path, root: &Path, expect: Option<ElfTarget>) -> Vec<String> {
+    let info = match parse_elf(source) {
+        Ok(Some(i)) => i,
+        Ok(None) => return Vec::new(),
+        Err(e) => return vec![format!("unreadable ELF {}: {e}", source.display())],
+    };
+    let mut problems = Vec::new();
+    if let Some(t) = expect {
+        if info.machine != t.machine
+            || info.class64 != t.class64
+            || info.little_endian != t.little_endian
+        {
+            problems.push(format!(
+                "{} is ELF machine={} {}-bit {} (expected machine={} {}-bit {})",
+                source.display(),
+                info.machine,
+                if info.class64 { 64 } else { 32 },
+                if info.little_endian { "LE" } else { "BE" },
+                t.machine,
+                if t.class64 { 64 } else { 32 },
+                if t.little_endian { "LE" } else { "BE" },
+            ));
+        }
+    }
+    // Paths are resolved as a chroot at `root` would see them, so absolute links inside a
+    // staged root are never judged against the host.
+    if let Some(interp) = &info.interp {
+        if resolve_in_root(root, Path::new(interp)).is_none() {
+            problems.push(format!(
+                "{} interpreter {} missing under {}",
+                source.display(),
+                interp,
+                root.display()
+            ));
+        }
+    }
+    if !info.needed.is_empty() {
+        let origin = source
+            .parent()
+            .and_then(|p| p.strip_prefix(root).ok())
+            .map_or_else(|| "/".to_string(), |p| format!("/{}", p.display()));
+        let mut dirs: Vec<PathBuf> = info
+            .runpath
+            .iter()
+            .map(|rp| PathBuf::from(expand_origin(rp, &origin)))
+            .collect();
+        dirs.extend(library_dirs(root));
+        for lib in &info.needed {
+            if !dirs
+                .iter()
+                .any(|d| resolve_in_root(root, &d.join(lib)).is_some())
+            {
+                problems.push(format!(
+                    "{} needs {} which does not resolve under {}",
+                    source.display(),
+                    lib,
+                    root.display()
+                ));
+            }
+        }
+    }
+    problems
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used, clippy::panic)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn non_elf_is_skipped() {
+        let td = tempfile::tempdir().unwrap();
+        let p = td.path().join("script");
+        std::fs::write(&p, b"#!/bin/sh\necho hi\n").unwrap();
+        assert!(parse_elf(&p).unwrap().is_none());
+        assert!(check_elf_compat(&p, td.path(), ElfTarget::host()).is_empty());
+    }
+
+    #[test]
+    fn own_test_binary_parses_for_host() {
+        let exe = std::env::current_exe().unwrap();
+        let info = parse_elf(&exe).unwrap().expect("test binary is ELF");
+        if let Some(host) = ElfTarget::host() {
+            assert_eq!(info.machine, host.machine);
+            assert_eq!(info.class64, host.class64);
+        }
+
+        // Staged root with the interpreter behind an absolute in-root link and the libraries
+        // in a standard directory; nothing is looked up on the host.
+        let td = tempfile::tempdir().unwrap();
+        let root = td.path();
+        let touch = |rel: &str| {
+            let p = root.join(rel.trim_start_matches('/'));
+            std::fs::create_dir_all(p.parent().unwrap()).unwrap();
+            std::fs::write(p, b"").unwrap();
+        };
+        let interp = info.interp.clone().unwrap_or_default();
+        if !interp.is_empty() {
+            touch("/opt/ld/real-ld.so");
+            let link = root.join(interp.trim_start_matches('/'));
+            std::fs::create_dir_all(link.parent().unwrap()).unwrap();
+            std::os::unix::fs::symlink("/opt/ld/real-ld.so", &link).unwrap();
+        }
+        for lib in &info.needed {
+            touch(&format!("usr/lib/{lib}"));
+        }
+        let source = root.join("bin/app");
+        std::fs::create_dir_all(root.join("bin")).unwrap();
+        std::fs::copy(&exe, &source).unwrap();
+        assert_eq!(
+            check_elf_compat(&source, root, ElfTarget::host()),
+            Vec::<String>::new()
+        );
+
+        if !interp.is_empty() {
+            // The same absolute target exists on the host but not in the root.
+            let link = root.join(interp.trim_start_matches('/'));
+            std::fs::remove_file(&link).unwrap();
+            std::os::unix::fs::symlink(&interp, &link).unwrap();
+            let problems = check_elf_compat(&source, root, ElfTarget::host());
+            assert!(
+                problems.iter().any(|p| p.contains("interpreter")),
+                "{problems:?}"
+            );
+        }
+        if let Some(lib) = info.needed.first() {
+            std::fs::remove_file(root.join("usr/lib").join(lib)).unwrap();
+            let problems = check_elf_compat(&source, root, ElfTarget::host());
+            assert!(
+                problems.iter().any(|p| p.contains("does not resolve")),
+                "{problems:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn origin_expands_in_both_spellings() {
+        assert_eq!(
+            expand_origin("$ORIGIN/../lib", "/opt/app/bin"),
+            "/opt/app/bin/../lib"
+        );
+        assert_eq!(expand_origin("${ORIGIN}/lib", "/opt/app"), "/opt/app/lib");
+        assert_eq!(expand_origin("/usr/lib/app", "/opt"), "/usr/lib/app");
+    }
+
+    #[test]
+    fn truncated_elf_is_an_error_not_a_panic() {
+        let td = tempfile::tempdir().unwrap();
+        let full = std::fs::read(std::env::current_exe().unwrap()).unwrap();
+        let p = td.path().join("cut");
+        for len in [16, 20, 60, 64, 100, 400] {
+            std::fs::write(&p, &full[..len]).unwrap();
+            assert!(parse_elf(&p).is_err(), "{len} bytes");
+            assert!(check_elf_compat(&p, td.path(), None)[0].contains("unreadable ELF"));
+        }
+        assert_eq!(array_at::<4>(&[1, 2, 3], 0), None);
+        assert_eq!(array_at::<2>(&[1, 2, 3], usize::MAX), None);
+    }
+}
@@ -4,9 +4,15 @@
 //! higher-level API. It also exposes a small helper to render a `PreflightReport`
 //! into a SPEC-aligned YAML sequence for fixtures and artifacts.
 
+pub mod busy;
 pub mod checks;
+pub mod elf;
+pub mod space;
 pub mod yaml;
 
 // Re-export common helpers for convenience
-pub use checks::{check_immutable, check_source_trust, ensure_mount_rw_exec};
+pub use checks::{
+    check_immutable, check_inode_flags, check_source_trust, ensure_mount_rw_exec, InodeFlagCheck,
+    InodeFlagStopKind,
+};
 pub use yaml::to_yaml;
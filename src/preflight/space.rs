@@ -0,0 +1,248 @@
+//! Free-space and inode budget for backups.
+//!
+//! Every mutating action snapshots its target into a sibling `.bak` payload plus a JSON sidecar
+//! before swapping. On a nearly full filesystem the copy can fail halfway through a plan, and the
+//! rollback that follows then runs on a disk with no room left. [`SpaceBudget::estimate`] adds up
+//! what the snapshots will need per filesystem (keyed by the device of the target's parent) and
+//! compares it against `statvfs` availability with a percentage safety margin.
+//!
+//! Estimates per snapshot:
+//! - Bytes: the target's size rounded up to the fragment size, plus one fragment for the sidecar.
+//!   Symlinks and absent targets only cost the sidecar fragment.
+//! - Inodes: two (payload and sidecar).
+//!
+//! Restores snapshot the current target only when `apply.capture_restore_snapshot` is set; the
+//! restore itself renames the backup into place and needs no extra space.
+use std::collections::BTreeMap;
+use std::os::unix::fs::MetadataExt as _;
+use std::path::{Path, PathBuf};
+
+use serde_json::{json, Value};
+
+use crate::policy::Policy;
+use crate::types::plan::Action;
+use crate::types::Plan;
+
+/// Payload and sidecar per snapshot.
+const INODES_PER_SNAPSHOT: u64 = 2;
+
+/// Prefix of STOP messages produced for a filesystem that cannot hold the backups.
+pub const INSUFFICIENT_SPACE: &str = "insufficient space for backups";
+
+/// Estimated backup footprint on one filesystem versus what it has available.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FsBudget {
+    /// First target directory seen on this filesystem (for operator context).
+    pub path: PathBuf,
+    pub dev: u64,
+    pub need_bytes: u64,
+    pub need_inodes: u64,
+    /// Need including the safety margin.
+    pub required_bytes: u64,
+    pub required_inodes: u64,
+    /// Space available to unprivileged users (`f_bavail * f_frsize`).
+    pub avail_bytes: u64,
+    /// Inodes available to unprivileged users; `None` when the filesystem does not report
+    /// inode counts (e.g. btrfs), in which case inodes are not checked.
+    pub avail_inodes: Option<u64>,
+}
+
+impl FsBudget {
+    #[must_use]
+    pub const fn bytes_ok(&self) -> bool {
+        self.required_bytes <= self.avail_bytes
+    }
+
+    #[must_use]
+    pub fn inodes_ok(&self) -> bool {
+        self.avail_inodes.is_none_or(|a| self.required_inodes <= a)
+    }
+
+    #[must_use]
+    pub fn ok(&self) -> bool {
+        self.bytes_ok() && self.inodes_ok()
+    }
+
+    #[must_use]
+    pub fn to_json(&self) -> Value {
+        json!({
+            "path": self.path.display().to_string(),
+            "need_bytes": self.need_bytes,
+            "need_inodes": self.need_inodes,
+            "required_bytes": self.required_bytes,
+            "required_inodes": self.required_inodes,
+            "avail_bytes": self.avail_bytes,
+            "avail_inodes": self.avail_inodes,
+            "ok": self.ok(),
+        })
+    }
+}
+
+/// Per-filesystem backup budget for a plan.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SpaceBudget {
+    pub margin_percent: u32,
+    pub filesystems: Vec<FsBudget>,
+}
+
+#[derive(Default)]
+struct Acc {
+    path: PathBuf,
+    frsize: u64,
+    bytes: u64,
+    inodes: u64,
+    avail_bytes: u64,
+    avail_inodes: Option<u64>,
+}
+
+impl SpaceBudget {
+    /// Estimate the backup footprint of `plan` under `policy`.
+    ///
+    /// Targets whose parent directory cannot be inspected are skipped; other gates report them.
+    #[must_use]
+    pub fn estimate(policy: &Policy, plan: &Plan) -> Self {
+        let margin = policy.durability.space_margin_percent;
+        let mut by_dev: BTreeMap<u64, Acc> = BTreeMap::new();
+        for act in &plan.actions {
+            let target = match act {
+                Action::EnsureSymlink { target, .. } => target.as_path(),
+                Action::RestoreFromBackup { target } => {
+                    if !policy.apply.capture_restore_snapshot {
+                        continue;
+                    }
+                    target.as_path()
+                }
+            };
+            let parent = target.parent().unwrap_or_else(|| Path::new("/"));
+            let Ok(pmd) = std::fs::metadata(parent) else {
+                continue;
+            };
+            let acc = match by_dev.entry(pmd.dev()) {
+                std::collections::btree_map::Entry::Occupied(e) => e.into_mut(),
+                std::collections::btree_map::Entry::Vacant(e) => {
+                    let Ok(st) = rustix::fs::statvfs(parent) else {
+                        continue;
+                    };
+                    let frsize = st.f_frsize.max(1);
+                    e.insert(Acc {
+                        path: parent.to_path_buf(),
+                        frsize,
+                        avail_bytes: st.f_bavail.saturating_mul(frsize),
+                        avail_inodes: (st.f_files > 0).then_some(st.f_favail),
+                        ..Acc::default()
+                    })
+                }
+            };
+            let payload = std::fs::symlink_metadata(&target)
+                .ok()
+                .filter(std::fs::Metadata::is_file)
+                .map_or(0, |m| m.len());
+            acc.bytes = acc
+                .bytes
+                .saturating_add(round_up(payload, acc.frsize))
+                .saturating_add(acc.frsize);
+            acc.inodes = acc.inodes.saturating_add(INODES_PER_SNAPSHOT);
+        }
+        let filesystems = by_dev
+            .into_iter()
+            .map(|(dev, a)| FsBudget {
+                path: a.path,
+                dev,
+                need_bytes: a.bytes,
+                need_inodes: a.inodes,
+                required_bytes: with_margin(a.bytes, margin),
+                required_inodes: with_margin(a.inodes, margin),
+                avail_bytes: a.avail_bytes,
+                avail_inodes: a.avail_inodes,
+            })
+            .collect();
+        SpaceBudget {
+            margin_percent: margin,
+            filesystems,
+        }
+    }
+
+    /// One STOP message per filesystem that cannot hold its share of the backups.
+    #[must_use]
+    pub fn shortfalls(&self) -> Vec<String> {
+        self.filesystems
+            .iter()
+            .filter(|f| !f.ok())
+            .map(|f| {
+                let mut what = Vec::new();
+                if !f.bytes_ok() {
+                    what.push(format!(
+                        "{} bytes required (incl. {}% margin), {} available",
+                        f.required_bytes, self.margin_percent, f.avail_bytes
+                    ));
+                }
+                if !f.inodes_ok() {
+                    what.push(format!(
+                        "{} inodes required, {} available",
+                        f.required_inodes,
+                        f.avail_inodes.unwrap_or(0)
+                    ));
+                }
+                format!(
+                    "{INSUFFICIENT_SPACE} on {}: {}",
+                    f.path.display(),
+                    what.join("; ")
+                )
+            })
+            .collect()
+    }
+
+    /// Estimate object for the preflight summary.
+    #[must_use]
+    pub fn to_json(&self) -> Value {
+        json!({
+            "margin_percent": self.margin_percent,
+            "filesystems": self.filesystems.iter().map(FsBudget::to_json).collect::<Vec<_>>(),
+        })
+    }
+}
+
+const fn round_up(n: u64, unit: u64) -> u64 {
+    n.div_ceil(unit).saturating_mul(unit)
+}
+
+fn with_margin(n: u64, percent: u32) -> u64 {
+    let extra = u128::from(n) * u128::from(percent) / 100;
+    u64::try_from(u128::from(n) + extra).unwrap_or(u64::MAX)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used, clippy::panic)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn margin_and_rounding() {
+        assert_eq!(round_up(0, 4096), 0);
+        assert_eq!(round_up(1, 4096), 4096);
+        assert_eq!(round_up(8192, 4096), 8192);
+        assert_eq!(with_margin(1000, 10), 1100);
+        assert_eq!(with_margin(u64::MAX, 50), u64::MAX);
+    }
+
+    #[test]
+    fn shortfall_names_bytes_and_inodes() {
+        let b = SpaceBudget {
+            margin_percent: 10,
+            filesystems: vec![FsBudget {
+                path: PathBuf::from("/usr/bin"),
+                dev: 1,
+                need_bytes: 100,
+                need_inodes: 2,
+                required_bytes: 110,
+                required_inodes: 2,
+                avail_bytes: 50,
+                avail_inodes: Some(1),
+            }],
+        };
+        let s = b.shortfalls();
+        assert_eq!(s.len(), 1);
+        assert!(s[0].starts_with(INSUFFICIENT_SPACE));
+        assert!(s[0].contains("110 bytes required") && s[0].contains("2 inodes required"));
+    }
+}
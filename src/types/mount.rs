@@ -1,13 +1,68 @@
 //! Data-only mount types used across the crate.
 
+use std::path::PathBuf;
+
 /// Typed representation of mount flags.
 /// Centralized under `crate::types` for cross-layer reuse.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(
+    clippy::struct_excessive_bools,
+    reason = "Mirrors independent kernel mount flags"
+)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub struct MountFlags {
     /// Whether the mount is read-only
     pub read_only: bool,
     /// Whether the mount has execution disabled
     pub no_exec: bool,
+    /// Whether setuid/setgid bits are ignored on the mount
+    pub no_suid: bool,
+    /// Whether device files are inaccessible on the mount
+    pub no_dev: bool,
+}
+
+/// Overlay filesystem layers from the `lowerdir=`, `upperdir=` and `workdir=` super options.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct OverlayLayers {
+    /// Writable layer; `None` for read-only overlays
+    pub upper: Option<PathBuf>,
+    /// Read-only layers, topmost first
+    pub lower: Vec<PathBuf>,
+    /// Overlay work directory
+    pub work: Option<PathBuf>,
+}
+
+/// One mount as described by a `/proc/<pid>/mountinfo` line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MountEntry {
+    /// Unique mount id
+    pub mount_id: u32,
+    /// Mount id of the parent mount
+    pub parent_id: u32,
+    /// Device major number (`st_dev`) of the mounted filesystem
+    pub major: u32,
+    /// Device minor number (`st_dev`) of the mounted filesystem
+    pub minor: u32,
+    /// Directory within the filesystem that forms the root of this mount (`/` unless bind-mounted)
+    pub root: PathBuf,
+    /// Mount point relative to the process root
+    pub mount_point: PathBuf,
+    /// Filesystem type (e.g. `ext4`, `overlay`, `nfs4`)
+    pub fs_type: String,
+    /// Mount source (device, remote share, or a pseudo name)
+    pub source: String,
+    /// Per-mount flags
+    pub flags: MountFlags,
+    /// Overlay layers when `fs_type` is `overlay`
+    pub overlay: Option<OverlayLayers>,
+}
+
+impl MountEntry {
+    /// Whether this mount exposes a subtree of its filesystem, i.e. a bind mount of a directory
+    /// or file. Bind mounts of a filesystem root are indistinguishable from regular mounts.
+    #[must_use]
+    pub fn is_bind(&self) -> bool {
+        self.root.as_os_str() != "/"
+    }
 }
 
 /// Error types for mount operations.
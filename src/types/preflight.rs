@@ -21,12 +21,30 @@ pub struct PreflightRow {
     /// Additional notes about the path
     #[serde(skip_serializing_if = "Option::is_none")]
     pub notes: Option<Vec<String>>,
+    /// Structured findings from custom policy checks
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub checks: Option<Vec<serde_json::Value>>,
     /// Preservation information for the path
     #[serde(skip_serializing_if = "Option::is_none")]
     pub preservation: Option<serde_json::Value>,
     /// Whether preservation is supported for this path
     #[serde(skip_serializing_if = "Option::is_none")]
     pub preservation_supported: Option<bool>,
+    /// Configured preservation tier (`basic`/`extended`/`full`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preservation_tier: Option<String>,
+    /// Dimensions that will actually be preserved (supported and selected by the tier)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preservation_effective: Option<serde_json::Value>,
+    /// Running processes executing or mapping the target (`pid`, `comm`, `via`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub busy: Option<Vec<serde_json::Value>>,
+    /// Mount of the target (`fs_type`, `mount_id`, flags, `bind`, `overlay`, `backup_same_dev`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mount: Option<serde_json::Value>,
+    /// Filesystem type of the target from `statfs` (e.g. `ext4`, `nfs`, `overlay`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fs_type: Option<String>,
     /// Whether the path is ready for restore
     #[serde(skip_serializing_if = "Option::is_none")]
     pub restore_ready: Option<bool>,
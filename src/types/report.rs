@@ -1,4 +1,5 @@
 use super::plan::Action;
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 // Typed representation of a preflight report.
@@ -33,6 +34,64 @@ pub struct ApplyReport {
     pub rolled_back: bool,
     /// List of errors encountered during rollback
     pub rollback_errors: Vec<String>,
+    /// Processes still executing or mapping a target's pre-apply inode (restart candidates)
+    pub stale_processes: Vec<StaleProcess>,
+    /// Per-check smoke results (empty when no smoke runner ran)
+    pub smoke_checks: Vec<SmokeCheck>,
+    /// Facts the emitter failed to record (`event: error`), e.g. after a mutation had already
+    /// happened; see `governance.audit_durability`
+    pub audit_failures: Vec<String>,
+}
+
+/// A process still running a replaced binary after apply.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StaleProcess {
+    /// Target path whose previous inode the process still references
+    pub path: String,
+    /// Process id
+    pub pid: u32,
+    /// Short command name
+    pub comm: String,
+    /// How the inode is referenced: `exe` or `maps`
+    pub via: String,
+}
+
+/// Result of one post-apply smoke check.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SmokeCheck {
+    /// Check name (e.g. `link` or a SPEC §11 tool such as `sort`)
+    pub name: String,
+    /// Target path the check exercised
+    pub target: String,
+    /// Command line executed, when the check runs a command
+    pub command: Option<String>,
+    /// Exit status of the command; `None` when it did not exit normally (signal, timeout)
+    pub exit_status: Option<i32>,
+    /// Redacted, length-bounded excerpt of the command's stderr
+    pub stderr: Option<String>,
+    /// Wall-clock duration of the check in milliseconds
+    pub duration_ms: u64,
+    /// Whether the check passed
+    pub ok: bool,
+    /// Why the check failed
+    pub error: Option<String>,
+}
+
+impl SmokeCheck {
+    /// Fact representation used in `smoke.result` events.
+    #[must_use]
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "name": self.name,
+            "target": self.target,
+            "command": self.command,
+            "exit_status": self.exit_status,
+            "stderr": self.stderr,
+            "duration_ms": self.duration_ms,
+            "ok": self.ok,
+            "error": self.error,
+        })
+    }
 }
 
 /// Typed representation of a prune result.
@@ -8,6 +8,28 @@ pub enum RescueStatus {
     BusyBox { path: String },
     /// GNU rescue status with found and minimum counts
     GNU { found: usize, min: usize },
+    /// `toybox` rescue status with path information
+    Toybox { path: String },
+    /// A named list of rescue tool paths, all present
+    Named { name: String, found: usize },
+}
+
+/// A rescue tool candidate ignored because the plan replaces it (as a target) or uses it
+/// as a source.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ExcludedRescueTool {
+    /// Tool or applet provider name (e.g. `cp`, `busybox`)
+    pub tool: String,
+    /// Path that resolved to a plan target or source
+    pub path: std::path::PathBuf,
+}
+
+/// Outcome of plan-aware rescue verification.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RescueCheck {
+    pub status: Result<RescueStatus, RescueError>,
+    /// Candidates excluded because the plan touches them
+    pub excluded: Vec<ExcludedRescueTool>,
 }
 
 /// Error types for rescue operations.
@@ -113,6 +113,16 @@ impl SafePath {
         self.root.join(&self.rel)
     }
 
+    /// Returns a reference to the root this path is confined to.
+    ///
+    /// # Returns
+    ///
+    /// * `&Path` - Reference to the root path
+    #[must_use]
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
     /// Returns a reference to the relative path component.
     ///
     /// # Returns
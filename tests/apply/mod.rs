@@ -1,5 +1,6 @@
 // Module declarations for all test files in the apply directory
 mod attestation_apply_success;
+mod attestation_ed25519_verify;
 mod attestation_error_tolerated;
 mod best_effort_restore_ok;
 mod commit_happy;
@@ -20,6 +21,9 @@ mod perf_aggregation;
 mod public_api;
 mod restore_without_snapshot;
 mod sidecar_integrity_disabled;
+mod smoke_command_runner;
+mod smoke_failure_diagnostics;
 mod smoke_ok;
 mod smoke_required;
 mod smoke_rollback;
+mod smoke_spec_runner;
@@ -0,0 +1,138 @@
+//! SPEC §11 command smoke runner: suite tools among plan targets are executed and validated.
+
+use std::os::unix::fs::PermissionsExt as _;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use switchyard::adapters::{CommandSmokeRunner, SmokeCommand, SmokeTestRunner};
+use switchyard::logging::JsonlSink;
+use switchyard::policy::Policy;
+use switchyard::types::plan::{LinkRequest, PlanInput};
+use switchyard::types::safepath::SafePath;
+use switchyard::types::Plan;
+
+fn host_tool(name: &str) -> Option<PathBuf> {
+    ["/usr/bin", "/bin"]
+        .into_iter()
+        .map(|d| Path::new(d).join(name))
+        .find(|p| p.exists())
+}
+
+/// Link `root/usr/bin/<name>` to `root/opt/new/<name>` (already populated) and plan it.
+fn linked_plan(root: &Path, names: &[&str]) -> Plan {
+    std::fs::create_dir_all(root.join("usr/bin")).unwrap();
+    let link = names
+        .iter()
+        .map(|n| {
+            let src = root.join("opt/new").join(n);
+            let tgt = root.join("usr/bin").join(n);
+            std::os::unix::fs::symlink(&src, &tgt).unwrap();
+            LinkRequest {
+                source: SafePath::from_rooted(root, &src).unwrap(),
+                target: SafePath::from_rooted(root, &tgt).unwrap(),
+            }
+        })
+        .collect();
+    let api = switchyard::Switchyard::new(JsonlSink, JsonlSink, Policy::default());
+    api.plan(PlanInput {
+        link,
+        restore: vec![],
+    })
+}
+
+fn script(root: &Path, name: &str, body: &str) {
+    let p = root.join("opt/new").join(name);
+    std::fs::create_dir_all(p.parent().unwrap()).unwrap();
+    std::fs::write(&p, format!("#!/bin/sh\n{body}\n")).unwrap();
+    std::fs::set_permissions(&p, std::fs::Permissions::from_mode(0o755)).unwrap();
+}
+
+#[test]
+fn host_coreutils_pass_the_suite() {
+    let names: Vec<&str> = SmokeCommand::ALL.iter().map(|c| c.name()).collect();
+    let Some(tools) = names
+        .iter()
+        .map(|n| host_tool(n))
+        .collect::<Option<Vec<_>>>()
+    else {
+        eprintln!("skipping: host lacks one of the SPEC §11 tools");
+        return;
+    };
+    let td = tempfile::tempdir().unwrap();
+    let root = td.path();
+    std::fs::create_dir_all(root.join("opt/new")).unwrap();
+    for (n, host) in names.iter().zip(&tools) {
+        std::os::unix::fs::symlink(host, root.join("opt/new").join(n)).unwrap();
+    }
+    let plan = linked_plan(root, &names);
+    assert_eq!(CommandSmokeRunner::selected(&plan).len(), names.len());
+    let runner = CommandSmokeRunner::new().with_scratch_dir(root);
+    assert!(runner.run(&plan).is_ok());
+}
+
+#[test]
+fn wrong_output_fails_and_unrelated_targets_are_not_executed() {
+    let td = tempfile::tempdir().unwrap();
+    let root = td.path();
+    // `sort` that does not version-sort; `app` would fail if it were ever executed.
+    script(root, "sort", "cat \"$2\"");
+    script(root, "app", "exit 1");
+    let runner = CommandSmokeRunner::new().with_scratch_dir(root);
+
+    let plan = linked_plan(root, &["app"]);
+    assert!(CommandSmokeRunner::selected(&plan).is_empty());
+    assert!(runner.run(&plan).is_ok());
+
+    let td2 = tempfile::tempdir().unwrap();
+    let root2 = td2.path();
+    script(root2, "sort", "cat \"$2\"");
+    let plan = linked_plan(root2, &["sort"]);
+    assert!(runner.run(&plan).is_err());
+}
+
+#[test]
+fn hung_command_is_killed_at_timeout() {
+    let td = tempfile::tempdir().unwrap();
+    let root = td.path();
+    script(root, "date", "sleep 30");
+    let plan = linked_plan(root, &["date"]);
+    let runner = CommandSmokeRunner::new()
+        .with_scratch_dir(root)
+        .with_timeout(Duration::from_millis(200));
+    let started = std::time::Instant::now();
+    assert!(runner.run(&plan).is_err());
+    assert!(started.elapsed() < Duration::from_secs(10));
+    // Scratch directories are cleaned up.
+    assert!(!std::fs::read_dir(root).unwrap().any(|e| e
+        .unwrap()
+        .file_name()
+        .to_string_lossy()
+        .starts_with(".switchyard-smoke")));
+}
+
+#[test]
+fn scratch_dir_is_private_and_unpredictable() {
+    let td = tempfile::tempdir().unwrap();
+    let root = td.path();
+    let seen = root.join("seen");
+    script(
+        root,
+        "date",
+        &format!("stat -c %a . > {0}; pwd >> {0}", seen.display()),
+    );
+    // A directory at the old predictable name must neither be reused nor removed.
+    let planted = root.join(format!(".switchyard-smoke.date.{}.0", std::process::id()));
+    std::fs::create_dir(&planted).unwrap();
+    let plan = linked_plan(root, &["date"]);
+    let runner = CommandSmokeRunner::new().with_scratch_dir(root);
+    let _ = runner.run(&plan);
+
+    let seen = std::fs::read_to_string(&seen).unwrap();
+    let mut lines = seen.lines();
+    assert_eq!(lines.next(), Some("700"));
+    let cwd = Path::new(lines.next().unwrap());
+    assert_eq!(cwd.parent(), Some(root));
+    assert_ne!(cwd, planted);
+    assert!(!cwd.exists(), "scratch dir is removed");
+    assert!(planted.is_dir());
+}
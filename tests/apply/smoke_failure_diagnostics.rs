@@ -0,0 +1,106 @@
+//! Smoke failures carry per-check results into `ApplyReport` and a redacted `smoke.result` fact.
+
+use std::os::unix::fs::PermissionsExt as _;
+use std::sync::{Arc, Mutex};
+
+use serde_json::Value;
+use switchyard::adapters::CommandSmokeRunner;
+use switchyard::logging::{redact_event, FactsEmitter, JsonlSink};
+use switchyard::policy::Policy;
+use switchyard::types::plan::{LinkRequest, PlanInput};
+use switchyard::types::safepath::SafePath;
+use switchyard::types::ApplyMode;
+
+type Captured = Arc<Mutex<Vec<(String, String, String, Value)>>>;
+
+#[derive(Default, Clone, Debug)]
+struct TestEmitter {
+    events: Captured,
+}
+
+impl FactsEmitter for TestEmitter {
+    fn emit(&self, subsystem: &str, event: &str, decision: &str, fields: Value) {
+        self.events.lock().unwrap().push((
+            subsystem.to_string(),
+            event.to_string(),
+            decision.to_string(),
+            fields,
+        ));
+    }
+}
+
+#[test]
+fn failing_command_is_reported_per_check_with_redacted_stderr() {
+    let facts = TestEmitter::default();
+    let mut policy = Policy::default();
+    policy.risks.source_trust = switchyard::policy::types::SourceTrustPolicy::AllowUntrusted;
+    policy.governance.allow_unlocked_commit = true;
+
+    let td = tempfile::tempdir().unwrap();
+    let root = td.path();
+    let src = root.join("opt/new/sort");
+    let tgt = root.join("usr/bin/sort");
+    std::fs::create_dir_all(src.parent().unwrap()).unwrap();
+    std::fs::create_dir_all(tgt.parent().unwrap()).unwrap();
+    std::fs::write(
+        &src,
+        "#!/bin/sh\necho 'sort: cannot reach license server password=hunter2' >&2\nexit 3\n",
+    )
+    .unwrap();
+    std::fs::set_permissions(&src, std::fs::Permissions::from_mode(0o755)).unwrap();
+    std::fs::write(&tgt, b"old").unwrap();
+
+    let api = switchyard::Switchyard::new(facts.clone(), JsonlSink, policy)
+        .with_smoke_runner(Box::new(CommandSmokeRunner::new().with_scratch_dir(root)))
+        .with_ownership_oracle(Box::new(switchyard::adapters::FsOwnershipOracle));
+    let plan = api.plan(PlanInput {
+        link: vec![LinkRequest {
+            source: SafePath::from_rooted(root, &src).unwrap(),
+            target: SafePath::from_rooted(root, &tgt).unwrap(),
+        }],
+        restore: vec![],
+    });
+
+    let report = api.apply(&plan, ApplyMode::Commit).unwrap();
+    assert!(report.rolled_back);
+    assert_eq!(report.smoke_checks.len(), 2, "{:?}", report.smoke_checks);
+    let link = &report.smoke_checks[0];
+    assert!(link.name == "link" && link.ok);
+    let sort = &report.smoke_checks[1];
+    assert_eq!(sort.name, "sort");
+    assert!(!sort.ok);
+    assert_eq!(sort.exit_status, Some(3));
+    assert!(sort
+        .command
+        .as_deref()
+        .unwrap()
+        .ends_with("sort -V versions"));
+    let stderr = sort.stderr.as_deref().unwrap();
+    assert!(stderr.contains("password=***") && !stderr.contains("hunter2"));
+
+    let events = facts.events.lock().unwrap();
+    let smoke: Vec<&Value> = events
+        .iter()
+        .filter(|(_, ev, _, _)| ev == "smoke.result")
+        .map(|(_, _, _, f)| f)
+        .collect();
+    assert_eq!(smoke.len(), 1);
+    let fact = smoke[0];
+    assert_eq!(fact["decision"], "failure");
+    assert_eq!(fact["error_id"], "E_SMOKE");
+    assert_eq!(fact["smoke"]["failed"], 1);
+    assert_eq!(fact["smoke"]["checks"][1]["exit_status"], 3);
+    assert!(!fact.to_string().contains("hunter2"));
+    // Full redaction masks captured output entirely.
+    let redacted = redact_event(fact.clone());
+    assert_eq!(redacted["smoke"]["checks"][1]["stderr"], "***");
+    assert!(redacted["smoke"]["checks"][0]["stderr"].is_null());
+
+    let schema: Value =
+        serde_json::from_str(include_str!("../../SPEC/audit_event.v2.schema.json")).unwrap();
+    let compiled = jsonschema::JSONSchema::compile(&schema).expect("valid schema");
+    assert!(
+        compiled.is_valid(fact),
+        "smoke.result must match the v2 schema"
+    );
+}
@@ -30,7 +30,7 @@ impl SmokeTestRunner for FailingSmoke {
         &self,
         _plan: &switchyard::types::plan::Plan,
     ) -> std::result::Result<(), switchyard::adapters::SmokeFailure> {
-        Err(switchyard::adapters::SmokeFailure)
+        Err(switchyard::adapters::SmokeFailure::default())
     }
 }
 
@@ -0,0 +1,136 @@
+//! Declarative smoke spec: checks keyed by target path/glob are selected for plan targets.
+
+use std::os::unix::fs::PermissionsExt as _;
+use std::path::Path;
+
+use switchyard::adapters::{SmokeSpec, SmokeTestRunner, SpecSmokeRunner};
+use switchyard::logging::JsonlSink;
+use switchyard::policy::Policy;
+use switchyard::types::plan::{LinkRequest, PlanInput};
+use switchyard::types::safepath::SafePath;
+use switchyard::types::Plan;
+
+/// Install `root/opt/new/<name>` as a shell script and link `root/usr/bin/<name>` to it.
+fn linked_plan(root: &Path, tools: &[(&str, &str)]) -> Plan {
+    std::fs::create_dir_all(root.join("opt/new")).unwrap();
+    std::fs::create_dir_all(root.join("usr/bin")).unwrap();
+    let link = tools
+        .iter()
+        .map(|(name, body)| {
+            let src = root.join("opt/new").join(name);
+            let tgt = root.join("usr/bin").join(name);
+            std::fs::write(&src, format!("#!/bin/sh\n{body}\n")).unwrap();
+            std::fs::set_permissions(&src, std::fs::Permissions::from_mode(0o755)).unwrap();
+            std::os::unix::fs::symlink(&src, &tgt).unwrap();
+            LinkRequest {
+                source: SafePath::from_rooted(root, &src).unwrap(),
+                target: SafePath::from_rooted(root, &tgt).unwrap(),
+            }
+        })
+        .collect();
+    let api = switchyard::Switchyard::new(JsonlSink, JsonlSink, Policy::default());
+    api.plan(PlanInput {
+        link,
+        restore: vec![],
+    })
+}
+
+const YAML: &str = r#"
+checks:
+  - target: /usr/bin/ls
+    args: ["--version"]
+    stdout_regex: "uutils"
+  - target: "/usr/bin/su*"
+    name: sudo-version
+    args: ["-V"]
+  - target: "/usr/bin/*"
+    name: is-symlink
+    command: /bin/sh
+    args: ["-c", "test -L '{target}'"]
+  - target: "/sbin/*"
+    name: never-selected
+    expect_exit: 99
+"#;
+
+#[test]
+fn yaml_checks_are_selected_by_path_and_glob() {
+    let td = tempfile::tempdir().unwrap();
+    let root = td.path();
+    let plan = linked_plan(
+        root,
+        &[
+            ("ls", "echo 'ls (uutils coreutils) 0.0.30'"),
+            ("sudo", "echo 'Sudo version 1.9'"),
+        ],
+    );
+    let runner = SpecSmokeRunner::new(SmokeSpec::from_yaml_str(YAML).unwrap()).unwrap();
+    let checks = runner.run_checks(&plan).unwrap();
+    let names: Vec<&str> = checks.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(
+        names,
+        [
+            "link",
+            "link",
+            "/usr/bin/ls",
+            "is-symlink",
+            "sudo-version",
+            "is-symlink"
+        ]
+    );
+    assert!(checks.iter().all(|c| c.ok));
+}
+
+#[test]
+fn exit_code_and_stdout_mismatches_fail() {
+    let td = tempfile::tempdir().unwrap();
+    let root = td.path();
+    let plan = linked_plan(
+        root,
+        &[
+            ("ls", "echo 'ls (GNU coreutils) 9.4'"),
+            ("sudo", "echo 'sudo: missing config' >&2; exit 1"),
+        ],
+    );
+    let runner = SpecSmokeRunner::new(SmokeSpec::from_yaml_str(YAML).unwrap()).unwrap();
+    let failure = runner.run(&plan).unwrap_err();
+    let failed: Vec<_> = failure.failed().collect();
+    assert_eq!(failed.len(), 2, "{failed:?}");
+    assert!(failed[0]
+        .error
+        .as_deref()
+        .unwrap()
+        .contains("does not match `uutils`"));
+    assert_eq!(failed[1].name, "sudo-version");
+    assert_eq!(failed[1].exit_status, Some(1));
+    assert_eq!(failed[1].stderr.as_deref(), Some("sudo: missing config"));
+}
+
+#[test]
+fn toml_spec_loads_by_extension_and_honours_timeout() {
+    let td = tempfile::tempdir().unwrap();
+    let root = td.path();
+    let plan = linked_plan(root, &[("date", "sleep 30")]);
+    let spec = root.join("smoke.toml");
+    std::fs::write(
+        &spec,
+        "[[checks]]\ntarget = \"/usr/bin/date\"\nargs = [\"+%s\"]\ntimeout_ms = 200\n",
+    )
+    .unwrap();
+    let runner = SpecSmokeRunner::load(&spec).unwrap();
+    let started = std::time::Instant::now();
+    let failure = runner.run(&plan).unwrap_err();
+    assert!(started.elapsed() < std::time::Duration::from_secs(10));
+    let check = failure.failed().next().unwrap();
+    assert!(check.error.as_deref().unwrap().starts_with("timed out"));
+    assert_eq!(check.exit_status, None);
+}
+
+#[test]
+fn invalid_specs_are_rejected() {
+    assert!(SmokeSpec::from_yaml_str("checks:\n  - target: /x\n    bogus: 1\n").is_err());
+    let bad_regex =
+        SmokeSpec::from_yaml_str("checks:\n  - target: /x\n    stdout_regex: \"(\"\n").unwrap();
+    assert!(SpecSmokeRunner::new(bad_regex).is_err());
+    let bad_glob = SmokeSpec::from_toml_str("[[checks]]\ntarget = \"/usr/[bin\"\n").unwrap();
+    assert!(SpecSmokeRunner::new(bad_glob).is_err());
+}
@@ -0,0 +1,138 @@
+//! `governance.audit_durability`: apply refuses to mutate when the `apply.attempt` fact cannot be
+//! recorded, and reports facts lost after a mutation.
+
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use serde_json::Value;
+use switchyard::logging::{EmitError, FactsEmitter, JsonlSink};
+use switchyard::policy::types::AuditDurability;
+use switchyard::policy::Policy;
+use switchyard::types::plan::{LinkRequest, PlanInput};
+use switchyard::types::safepath::SafePath;
+use switchyard::types::{ApplyMode, ApplyReport};
+
+type Captured = Arc<Mutex<Vec<(String, String, String, Value)>>>;
+
+/// Records facts, but rejects the stages listed in `failing` on the fallible path.
+#[derive(Default, Clone, Debug)]
+struct FlakyEmitter {
+    events: Captured,
+    failing: Arc<Mutex<Vec<&'static str>>>,
+}
+
+impl FactsEmitter for FlakyEmitter {
+    fn emit(&self, subsystem: &str, event: &str, decision: &str, fields: Value) {
+        self.events.lock().unwrap().push((
+            subsystem.to_string(),
+            event.to_string(),
+            decision.to_string(),
+            fields,
+        ));
+    }
+
+    fn try_emit(
+        &self,
+        subsystem: &str,
+        event: &str,
+        decision: &str,
+        fields: Value,
+    ) -> Result<(), EmitError> {
+        let stage = fields["stage"].as_str().unwrap_or_default().to_string();
+        if self.failing.lock().unwrap().contains(&stage.as_str()) {
+            return Err(EmitError("collector unreachable".to_string()));
+        }
+        self.emit(subsystem, event, decision, fields);
+        Ok(())
+    }
+}
+
+fn apply_one(
+    durability: AuditDurability,
+    failing: &[&'static str],
+) -> (ApplyReport, Captured, bool) {
+    let facts = FlakyEmitter::default();
+    facts.failing.lock().unwrap().extend_from_slice(failing);
+    let mut policy = Policy::default();
+    policy.governance.allow_unlocked_commit = true;
+    policy.governance.audit_durability = durability;
+    policy.risks.source_trust = switchyard::policy::types::SourceTrustPolicy::AllowUntrusted;
+    let api = switchyard::Switchyard::new(facts.clone(), JsonlSink, policy);
+
+    let td = tempfile::tempdir().unwrap();
+    let root = td.path();
+    std::fs::create_dir_all(root.join("bin")).unwrap();
+    std::fs::create_dir_all(root.join("usr/bin")).unwrap();
+    std::fs::write(root.join("bin/new"), b"new").unwrap();
+    std::fs::write(root.join("usr/bin/app"), b"old").unwrap();
+    let plan = api.plan(PlanInput {
+        link: vec![LinkRequest {
+            source: SafePath::from_rooted(root, &root.join("bin/new")).unwrap(),
+            target: SafePath::from_rooted(root, &root.join("usr/bin/app")).unwrap(),
+        }],
+        restore: vec![],
+    });
+    let report = api.apply(&plan, ApplyMode::Commit).unwrap();
+    let mutated = is_symlink(&root.join("usr/bin/app"));
+    (report, facts.events, mutated)
+}
+
+fn is_symlink(p: &Path) -> bool {
+    std::fs::symlink_metadata(p).is_ok_and(|m| m.file_type().is_symlink())
+}
+
+#[test]
+fn required_durability_refuses_to_mutate_without_attempt_fact() {
+    let (report, events, mutated) = apply_one(AuditDurability::Required, &["apply.attempt"]);
+    assert!(!mutated, "target must be left untouched");
+    assert!(report.executed.is_empty());
+    assert!(
+        report
+            .errors
+            .iter()
+            .any(|e| e.contains("audit not durable")),
+        "{:?}",
+        report.errors
+    );
+    let events = events.lock().unwrap();
+    let summary = events
+        .iter()
+        .rev()
+        .find(|(_, _, _, f)| f["stage"] == "apply.result" && f.get("action_id").is_none())
+        .expect("apply summary");
+    assert_eq!(summary.3["error_id"], "E_AUDIT");
+    assert_eq!(summary.3["exit_code"], 100);
+    assert!(events
+        .iter()
+        .any(|(_, _, d, f)| f["stage"] == "apply.result"
+            && f.get("action_id").is_some()
+            && f["error_id"] == "E_AUDIT"
+            && d == "failure"));
+}
+
+#[test]
+fn best_effort_mutates_and_reports_lost_facts() {
+    let (report, _, mutated) = apply_one(AuditDurability::BestEffort, &["apply.attempt"]);
+    assert!(mutated);
+    assert!(report.errors.is_empty(), "{:?}", report.errors);
+    assert!(!report.audit_failures.is_empty());
+    assert!(report
+        .audit_failures
+        .iter()
+        .all(|f| f.starts_with("apply.attempt: collector unreachable")));
+}
+
+#[test]
+fn required_durability_reports_failures_after_mutation() {
+    let (report, _, mutated) = apply_one(AuditDurability::Required, &["apply.result"]);
+    assert!(mutated, "pre-mutation facts were recorded");
+    assert!(report.errors.is_empty(), "{:?}", report.errors);
+    assert!(
+        report
+            .audit_failures
+            .iter()
+            .any(|f| f.starts_with("apply.result:")),
+        "{:?}",
+        report.audit_failures
+    );
+}
@@ -0,0 +1,218 @@
+//! Hash-chained facts: every fact of a run links to its predecessor, the apply attestation
+//! covers the chain head, and the verifier reports tampering.
+
+use std::io::Write as _;
+use std::sync::{Arc, Mutex};
+
+use serde_json::{json, Value};
+use switchyard::adapters::{AttestationError, Attestor, Signature};
+use switchyard::logging::chain::{verify_chain, verify_jsonl, ChainIssueKind};
+use switchyard::logging::{redact_event, FactsEmitter, JsonlSink};
+use switchyard::policy::Policy;
+use switchyard::types::plan::{LinkRequest, PlanInput};
+use switchyard::types::safepath::SafePath;
+use switchyard::types::ApplyMode;
+
+type Captured = Arc<Mutex<Vec<(String, String, String, Value)>>>;
+
+#[derive(Default, Clone, Debug)]
+struct TestEmitter {
+    events: Captured,
+}
+
+impl FactsEmitter for TestEmitter {
+    fn emit(&self, subsystem: &str, event: &str, decision: &str, fields: Value) {
+        self.events.lock().unwrap().push((
+            subsystem.to_string(),
+            event.to_string(),
+            decision.to_string(),
+            fields,
+        ));
+    }
+}
+
+/// Records the last signed bundle.
+#[derive(Clone, Debug, Default)]
+struct RecordingAttestor {
+    bundle: Arc<Mutex<Vec<u8>>>,
+}
+
+impl Attestor for RecordingAttestor {
+    fn sign(&self, bundle: &[u8]) -> Result<Signature, AttestationError> {
+        *self.bundle.lock().unwrap() = bundle.to_vec();
+        Ok(Signature(vec![0xAA, 0xBB, 0xCC]))
+    }
+    fn key_id(&self) -> String {
+        "test-key".to_string()
+    }
+}
+
+/// Plan and commit one link with chaining on; returns the apply-stage facts (JSONL shape) and
+/// the signed bundle.
+fn chained_apply() -> (Vec<Value>, Value) {
+    let facts = TestEmitter::default();
+    let attestor = RecordingAttestor::default();
+    let mut policy = Policy::default();
+    policy.governance.allow_unlocked_commit = true;
+    policy.risks.source_trust = switchyard::policy::types::SourceTrustPolicy::AllowUntrusted;
+    policy.audit.hash_chain = true;
+    let api = switchyard::Switchyard::new(facts.clone(), JsonlSink, policy)
+        .with_attestor(Box::new(attestor.clone()));
+
+    let td = tempfile::tempdir().unwrap();
+    let root = td.path();
+    std::fs::create_dir_all(root.join("bin")).unwrap();
+    std::fs::create_dir_all(root.join("usr/bin")).unwrap();
+    std::fs::write(root.join("bin/new"), b"new").unwrap();
+    std::fs::write(root.join("usr/bin/app"), b"old").unwrap();
+    let plan = api.plan(PlanInput {
+        link: vec![LinkRequest {
+            source: SafePath::from_rooted(root, &root.join("bin/new")).unwrap(),
+            target: SafePath::from_rooted(root, &root.join("usr/bin/app")).unwrap(),
+        }],
+        restore: vec![],
+    });
+    let report = api.apply(&plan, ApplyMode::Commit).unwrap();
+    assert!(report.errors.is_empty(), "{:?}", report.errors);
+
+    let lines = facts
+        .events
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|(subsystem, event, _, f)| {
+            // Sink envelope keys are outside the hashed content.
+            let mut f = f.clone();
+            f["subsystem"] = json!(subsystem);
+            f["event"] = json!(event);
+            f
+        })
+        .collect();
+    let bundle = serde_json::from_slice(&attestor.bundle.lock().unwrap()).unwrap();
+    (lines, bundle)
+}
+
+#[test]
+fn chained_run_verifies_and_attestation_covers_head() {
+    let (facts, bundle) = chained_apply();
+    let report = verify_chain(facts.clone());
+    assert!(report.is_intact(), "{:?}", report.issues);
+    assert_eq!(report.facts, facts.len());
+    assert_eq!(report.runs, 2, "plan and apply are separate runs");
+
+    let result = facts
+        .iter()
+        .rfind(|f| f["stage"] == "apply.result")
+        .unwrap();
+    assert_eq!(result["attestation"]["chain_head"], result["prev_hash"]);
+    assert_eq!(bundle["chain_head"], result["prev_hash"]);
+    assert_eq!(
+        report.heads[result["run_id"].as_str().unwrap()],
+        result["hash"]
+    );
+
+    let schema: Value =
+        serde_json::from_str(include_str!("../../SPEC/audit_event.v2.schema.json")).unwrap();
+    let compiled = jsonschema::JSONSchema::compile(&schema).expect("valid schema");
+    for f in &facts {
+        assert!(compiled.is_valid(f), "fact must match the v2 schema: {f}");
+    }
+}
+
+#[test]
+fn verifier_reports_tampering_in_jsonl() {
+    let (facts, _) = chained_apply();
+    let td = tempfile::tempdir().unwrap();
+    let write = |name: &str, facts: &[Value]| {
+        let path = td.path().join(name);
+        let mut f = std::fs::File::create(&path).unwrap();
+        for v in facts {
+            writeln!(f, "{v}").unwrap();
+        }
+        writeln!(f, r#"{{"event":"audit","message":"not a chained fact"}}"#).unwrap();
+        path
+    };
+    assert!(verify_jsonl(&write("ok.jsonl", &facts))
+        .unwrap()
+        .is_intact());
+
+    let attempt = facts
+        .iter()
+        .position(|f| f["stage"] == "apply.attempt")
+        .unwrap();
+    let mut dropped = facts.clone();
+    dropped.remove(attempt);
+    let kinds: Vec<_> = verify_jsonl(&write("dropped.jsonl", &dropped))
+        .unwrap()
+        .issues
+        .iter()
+        .map(|i| i.kind)
+        .collect();
+    assert_eq!(kinds, vec![ChainIssueKind::Gap]);
+
+    let mut edited = facts;
+    edited[attempt]["lock_backend"] = json!("file");
+    let issues = verify_jsonl(&write("edited.jsonl", &edited))
+        .unwrap()
+        .issues;
+    assert_eq!(issues[0].kind, ChainIssueKind::Modified);
+    assert_eq!(issues[0].index, Some(attempt));
+}
+
+#[test]
+fn chained_dry_run_and_commit_match_after_redaction() {
+    let facts = TestEmitter::default();
+    let mut policy = Policy::default();
+    policy.governance.allow_unlocked_commit = true;
+    policy.risks.source_trust = switchyard::policy::types::SourceTrustPolicy::AllowUntrusted;
+    policy.audit.hash_chain = true;
+    let api = switchyard::Switchyard::new(facts.clone(), JsonlSink, policy);
+
+    let td = tempfile::tempdir().unwrap();
+    let root = td.path();
+    std::fs::create_dir_all(root.join("bin")).unwrap();
+    std::fs::create_dir_all(root.join("usr/bin")).unwrap();
+    std::fs::write(root.join("bin/new"), b"new").unwrap();
+    std::fs::write(root.join("usr/bin/app"), b"old").unwrap();
+    let plan = api.plan(PlanInput {
+        link: vec![LinkRequest {
+            source: SafePath::from_rooted(root, &root.join("bin/new")).unwrap(),
+            target: SafePath::from_rooted(root, &root.join("usr/bin/app")).unwrap(),
+        }],
+        restore: vec![],
+    });
+
+    let per_action = |mode| {
+        facts.events.lock().unwrap().clear();
+        api.apply(&plan, mode).unwrap();
+        facts
+            .events
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(_, _, _, f)| f.clone())
+            .filter(|f| f["stage"] == "apply.result" && f.get("action_id").is_some())
+            .map(|f| {
+                assert!(f.get("hash").is_some() && f.get("prev_hash").is_some());
+                let mut f = redact_event(f);
+                let obj = f.as_object_mut().unwrap();
+                // Run identity and mode markers differ by design.
+                for k in [
+                    "run_id",
+                    "event_id",
+                    "seq",
+                    "dry_run",
+                    "redacted",
+                    "redaction",
+                ] {
+                    obj.remove(k);
+                }
+                f
+            })
+            .collect::<Vec<_>>()
+    };
+    let dry = per_action(ApplyMode::DryRun);
+    let commit = per_action(ApplyMode::Commit);
+    assert!(!dry.is_empty());
+    assert_eq!(dry, commit);
+}
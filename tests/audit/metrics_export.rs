@@ -0,0 +1,93 @@
+//! Metrics fed from a real run: a committed apply whose smoke check fails and rolls back.
+
+use switchyard::adapters::FileLockManager;
+use switchyard::logging::{JsonlSink, Metrics, MetricsFormat};
+use switchyard::policy::Policy;
+use switchyard::types::plan::{LinkRequest, PlanInput};
+use switchyard::types::safepath::SafePath;
+use switchyard::types::ApplyMode;
+
+#[derive(Debug)]
+struct FailingSmokeRunner;
+
+impl switchyard::adapters::SmokeTestRunner for FailingSmokeRunner {
+    fn run(
+        &self,
+        _plan: &switchyard::types::plan::Plan,
+    ) -> Result<(), switchyard::adapters::SmokeFailure> {
+        Err(switchyard::adapters::SmokeFailure::default())
+    }
+}
+
+#[test]
+fn apply_and_rollback_are_counted() {
+    let td = tempfile::tempdir().unwrap();
+    let root = td.path();
+    let metrics = Metrics::new();
+    let mut policy = Policy::default();
+    policy.governance.smoke = switchyard::policy::types::SmokePolicy::Require {
+        auto_rollback: true,
+    };
+    policy.risks.source_trust = switchyard::policy::types::SourceTrustPolicy::AllowUntrusted;
+    let api = switchyard::Switchyard::builder(JsonlSink, JsonlSink, policy)
+        .with_lock_manager(Box::new(FileLockManager::new(root.join("switchyard.lock"))))
+        .with_smoke_runner(Box::new(FailingSmokeRunner))
+        .with_metrics(metrics.clone())
+        .build();
+
+    std::fs::create_dir_all(root.join("bin")).unwrap();
+    std::fs::create_dir_all(root.join("usr/bin")).unwrap();
+    std::fs::write(root.join("bin/new"), b"new").unwrap();
+    std::fs::write(root.join("usr/bin/app"), b"old").unwrap();
+    let plan = api.plan(PlanInput {
+        link: vec![LinkRequest {
+            source: SafePath::from_rooted(root, &root.join("bin/new")).unwrap(),
+            target: SafePath::from_rooted(root, &root.join("usr/bin/app")).unwrap(),
+        }],
+        restore: vec![],
+    });
+    let _ = api.apply(&plan, ApplyMode::DryRun).unwrap();
+    let report = api.apply(&plan, ApplyMode::Commit).unwrap();
+    assert!(report.rolled_back);
+
+    let applies = |mode, decision| {
+        metrics.counter(
+            "switchyard_applies",
+            &[("mode", mode), ("decision", decision)],
+        )
+    };
+    assert_eq!(applies("dry_run", "success"), 1);
+    assert_eq!(applies("commit", "failure"), 1);
+    assert_eq!(
+        metrics.counter(
+            "switchyard_actions",
+            &[("kind", "ensure_symlink"), ("decision", "success")]
+        ),
+        1,
+        "dry runs do not count actions"
+    );
+    assert_eq!(metrics.counter("switchyard_rollbacks", &[]), 1);
+    assert_eq!(metrics.counter("switchyard_smoke_failures", &[]), 1);
+    assert_eq!(
+        metrics.counter(
+            "switchyard_failures",
+            &[("stage", "smoke.result"), ("error_id", "E_SMOKE")]
+        ),
+        1
+    );
+    assert_eq!(
+        metrics.counter("switchyard_rollback_steps", &[("decision", "success")]),
+        1
+    );
+    assert_eq!(metrics.histogram_count("switchyard_lock_wait_ms"), 1);
+    assert_eq!(metrics.histogram_count("switchyard_fsync_ms"), 1);
+    assert_eq!(metrics.histogram_count("switchyard_swap_ms"), 1);
+
+    let path = td.path().join("switchyard.prom");
+    metrics
+        .write_textfile(&path, MetricsFormat::Prometheus)
+        .unwrap();
+    let text = std::fs::read_to_string(&path).unwrap();
+    assert!(text.contains("switchyard_rollbacks_total 1\n"), "{text}");
+    assert!(text.contains("switchyard_fsync_ms_bucket{le=\"+Inf\"} 1\n"));
+}
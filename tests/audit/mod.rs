@@ -1,6 +1,12 @@
 // Module declarations for all test files in the audit directory
 mod audit_schema;
+mod durable_audit;
 mod envelope_v2_1;
+mod hash_chain;
+mod metrics_export;
 mod preflight_summary_error_id;
 mod provenance_presence;
+mod redaction_policy;
+mod runtime_schema_validation;
 mod summary_error_ids_ownership;
+mod typed_events;
@@ -0,0 +1,122 @@
+//! Operator `RedactionPolicy`: applied to every fact in both `DryRun` and `Commit`, with `keep`
+//! rules overriding the built-in dry-run redaction.
+
+use std::sync::{Arc, Mutex};
+
+use serde_json::Value;
+use switchyard::adapters::OwnershipOracle;
+use switchyard::logging::{FactsEmitter, JsonlSink, RedactionPolicy};
+use switchyard::policy::Policy;
+use switchyard::types::errors::Result;
+use switchyard::types::ownership::OwnershipInfo;
+use switchyard::types::plan::{LinkRequest, PlanInput};
+use switchyard::types::safepath::SafePath;
+use switchyard::types::ApplyMode;
+
+type Captured = Arc<Mutex<Vec<(String, String, String, Value)>>>;
+
+#[derive(Default, Clone, Debug)]
+struct TestEmitter {
+    events: Captured,
+}
+
+impl FactsEmitter for TestEmitter {
+    fn emit(&self, subsystem: &str, event: &str, decision: &str, fields: Value) {
+        self.events.lock().unwrap().push((
+            subsystem.to_string(),
+            event.to_string(),
+            decision.to_string(),
+            fields,
+        ));
+    }
+}
+
+#[derive(Debug)]
+struct PkgOracle;
+
+impl OwnershipOracle for PkgOracle {
+    fn owner_of(&self, _path: &SafePath) -> Result<OwnershipInfo> {
+        Ok(OwnershipInfo {
+            uid: 0,
+            gid: 0,
+            pkg: "coreutils".to_string(),
+        })
+    }
+}
+
+fn run(mode: ApplyMode) -> (Vec<Value>, String) {
+    let td = tempfile::tempdir().unwrap();
+    let root = td.path();
+    let root_str = root.display().to_string();
+    let redaction = RedactionPolicy::new()
+        .rewrite_paths(&regex::escape(&root_str), "<root>")
+        .unwrap()
+        .hash("provenance.pkg")
+        .keep("before_hash")
+        .keep("after_hash")
+        .keep("hash_alg")
+        .with_salt("test-salt");
+
+    let facts = TestEmitter::default();
+    let mut policy = Policy::default();
+    policy.governance.allow_unlocked_commit = true;
+    policy.risks.source_trust = switchyard::policy::types::SourceTrustPolicy::AllowUntrusted;
+    let api = switchyard::Switchyard::builder(facts.clone(), JsonlSink, policy)
+        .with_ownership_oracle(Box::new(PkgOracle))
+        .with_redaction_policy(redaction)
+        .build();
+
+    std::fs::create_dir_all(root.join("bin")).unwrap();
+    std::fs::create_dir_all(root.join("usr/bin")).unwrap();
+    std::fs::write(root.join("bin/new"), b"new").unwrap();
+    std::fs::write(root.join("usr/bin/app"), b"old").unwrap();
+    let plan = api.plan(PlanInput {
+        link: vec![LinkRequest {
+            source: SafePath::from_rooted(root, &root.join("bin/new")).unwrap(),
+            target: SafePath::from_rooted(root, &root.join("usr/bin/app")).unwrap(),
+        }],
+        restore: vec![],
+    });
+    let report = api.apply(&plan, mode).unwrap();
+    assert!(report.errors.is_empty(), "{:?}", report.errors);
+
+    let events = facts.events.lock().unwrap();
+    (
+        events.iter().map(|(_, _, _, f)| f.clone()).collect(),
+        root_str,
+    )
+}
+
+#[test]
+fn policy_applies_in_dry_run_and_commit() {
+    for mode in [ApplyMode::DryRun, ApplyMode::Commit] {
+        let (facts, root) = run(mode);
+        for f in &facts {
+            assert!(
+                !f.to_string().contains(&root),
+                "{mode:?}: root must be rewritten: {f}"
+            );
+        }
+        let result = facts
+            .iter()
+            .find(|f| f["stage"] == "apply.result" && f.get("action_id").is_some())
+            .unwrap_or_else(|| panic!("{mode:?}: per-action apply.result"));
+        assert!(result["path"].as_str().unwrap().starts_with("<root>/"));
+        assert!(
+            result.get("before_hash").is_some() && result.get("hash_alg").is_some(),
+            "{mode:?}: hashes are kept: {result}"
+        );
+        let pkg = result["provenance"]["pkg"].as_str().unwrap();
+        assert_ne!(pkg, "coreutils");
+        assert_eq!(pkg.len(), 64);
+        assert_eq!(result["redacted"], true);
+        let rules: Vec<&str> = result["redaction"]["rules"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(Value::as_str)
+            .collect();
+        assert!(rules.contains(&"hash:provenance.pkg"), "{rules:?}");
+        assert!(rules.iter().any(|r| r.starts_with("rewrite:")), "{rules:?}");
+    }
+}
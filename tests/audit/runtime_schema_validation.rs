@@ -0,0 +1,79 @@
+//! Every fact of a plan/preflight/apply/rollback/prune run passes the runtime schema check.
+#![cfg(feature = "schema-validation")]
+
+use std::sync::{Arc, Mutex};
+
+use serde_json::Value;
+use switchyard::logging::{FactsEmitter, JsonlSink, SchemaValidatingEmitter, SchemaViolation};
+use switchyard::policy::Policy;
+use switchyard::types::plan::{LinkRequest, PlanInput};
+use switchyard::types::safepath::SafePath;
+use switchyard::types::ApplyMode;
+
+type Captured = Arc<Mutex<Vec<(String, String, String, Value)>>>;
+
+#[derive(Default, Clone, Debug)]
+struct TestEmitter {
+    events: Captured,
+}
+
+impl FactsEmitter for TestEmitter {
+    fn emit(&self, subsystem: &str, event: &str, decision: &str, fields: Value) {
+        self.events.lock().unwrap().push((
+            subsystem.to_string(),
+            event.to_string(),
+            decision.to_string(),
+            fields,
+        ));
+    }
+}
+
+#[derive(Debug)]
+struct FailingSmokeRunner;
+
+impl switchyard::adapters::SmokeTestRunner for FailingSmokeRunner {
+    fn run(
+        &self,
+        _plan: &switchyard::types::plan::Plan,
+    ) -> Result<(), switchyard::adapters::SmokeFailure> {
+        Err(switchyard::adapters::SmokeFailure::default())
+    }
+}
+
+#[test]
+fn emitted_facts_pass_runtime_validation() {
+    let inner = TestEmitter::default();
+    let facts =
+        SchemaValidatingEmitter::new(inner.clone()).with_on_violation(SchemaViolation::Panic);
+    let mut policy = Policy::default();
+    policy.governance.allow_unlocked_commit = true;
+    policy.governance.smoke = switchyard::policy::types::SmokePolicy::Require {
+        auto_rollback: true,
+    };
+    policy.risks.source_trust = switchyard::policy::types::SourceTrustPolicy::AllowUntrusted;
+    policy.audit.hash_chain = true;
+    let api = switchyard::Switchyard::new(facts.clone(), JsonlSink, policy)
+        .with_smoke_runner(Box::new(FailingSmokeRunner));
+
+    let td = tempfile::tempdir().unwrap();
+    let root = td.path();
+    std::fs::create_dir_all(root.join("bin")).unwrap();
+    std::fs::create_dir_all(root.join("usr/bin")).unwrap();
+    std::fs::write(root.join("bin/new"), b"new").unwrap();
+    std::fs::write(root.join("usr/bin/app"), b"old").unwrap();
+    let target = SafePath::from_rooted(root, &root.join("usr/bin/app")).unwrap();
+    let plan = api.plan(PlanInput {
+        link: vec![LinkRequest {
+            source: SafePath::from_rooted(root, &root.join("bin/new")).unwrap(),
+            target: target.clone(),
+        }],
+        restore: vec![],
+    });
+    let _ = api.preflight(&plan).unwrap();
+    let report = api.apply(&plan, ApplyMode::Commit).unwrap();
+    assert!(report.rolled_back);
+    let _ = api.prune_backups(&target).unwrap();
+
+    assert_eq!(facts.violations(), 0, "{:?}", facts.last_violation());
+    assert!(inner.events.lock().unwrap().len() > 5);
+}
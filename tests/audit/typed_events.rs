@@ -0,0 +1,183 @@
+//! Typed audit events: every fact a run emits parses into `AuditEvent` and serializes back to
+//! the same JSON, and `parse_jsonl` reads facts back from a log file.
+
+use std::collections::BTreeSet;
+use std::io::Write as _;
+use std::sync::{Arc, Mutex};
+
+use serde_json::{json, Value};
+use switchyard::api::errors::ErrorId;
+use switchyard::logging::event::{parse_jsonl, AuditEvent, Nullable};
+use switchyard::logging::{Decision, FactsEmitter, JsonlSink};
+use switchyard::policy::Policy;
+use switchyard::types::plan::{LinkRequest, PlanInput};
+use switchyard::types::safepath::SafePath;
+use switchyard::types::ApplyMode;
+
+type Captured = Arc<Mutex<Vec<(String, String, String, Value)>>>;
+
+#[derive(Default, Clone, Debug)]
+struct TestEmitter {
+    events: Captured,
+}
+
+impl FactsEmitter for TestEmitter {
+    fn emit(&self, subsystem: &str, event: &str, decision: &str, fields: Value) {
+        self.events.lock().unwrap().push((
+            subsystem.to_string(),
+            event.to_string(),
+            decision.to_string(),
+            fields,
+        ));
+    }
+}
+
+#[derive(Debug)]
+struct FailingSmokeRunner;
+
+impl switchyard::adapters::SmokeTestRunner for FailingSmokeRunner {
+    fn run(
+        &self,
+        _plan: &switchyard::types::plan::Plan,
+    ) -> Result<(), switchyard::adapters::SmokeFailure> {
+        Err(switchyard::adapters::SmokeFailure::default())
+    }
+}
+
+/// Plan, preflight, a commit whose smoke check fails (rollback) and a prune; returns every fact.
+fn full_run() -> Vec<Value> {
+    let facts = TestEmitter::default();
+    let mut policy = Policy::default();
+    policy.governance.allow_unlocked_commit = true;
+    policy.governance.smoke = switchyard::policy::types::SmokePolicy::Require {
+        auto_rollback: true,
+    };
+    policy.risks.source_trust = switchyard::policy::types::SourceTrustPolicy::AllowUntrusted;
+    let api = switchyard::Switchyard::new(facts.clone(), JsonlSink, policy)
+        .with_smoke_runner(Box::new(FailingSmokeRunner));
+
+    let td = tempfile::tempdir().unwrap();
+    let root = td.path();
+    std::fs::create_dir_all(root.join("bin")).unwrap();
+    std::fs::create_dir_all(root.join("usr/bin")).unwrap();
+    std::fs::write(root.join("bin/new"), b"new").unwrap();
+    std::fs::write(root.join("usr/bin/app"), b"old").unwrap();
+    let target = SafePath::from_rooted(root, &root.join("usr/bin/app")).unwrap();
+    let plan = api.plan(PlanInput {
+        link: vec![LinkRequest {
+            source: SafePath::from_rooted(root, &root.join("bin/new")).unwrap(),
+            target: target.clone(),
+        }],
+        restore: vec![],
+    });
+    let _ = api.preflight(&plan).unwrap();
+    let report = api.apply(&plan, ApplyMode::Commit).unwrap();
+    assert!(report.rolled_back, "smoke failure rolls back");
+    let _ = api.prune_backups(&target).unwrap();
+
+    let events = facts.events.lock().unwrap();
+    events.iter().map(|(_, _, _, f)| f.clone()).collect()
+}
+
+#[test]
+fn every_emitted_fact_round_trips_through_the_typed_model() {
+    let facts = full_run();
+    let schema: Value =
+        serde_json::from_str(include_str!("../../SPEC/audit_event.v2.schema.json")).unwrap();
+    let compiled = jsonschema::JSONSchema::compile(&schema).expect("valid schema");
+
+    let mut stages = BTreeSet::new();
+    for fact in &facts {
+        let ev = AuditEvent::from_value(fact.clone())
+            .unwrap_or_else(|e| panic!("fact must parse: {e}: {fact}"));
+        assert_eq!(ev.stage().as_str(), fact["stage"], "{fact}");
+        let back = ev.to_value().unwrap();
+        assert_eq!(
+            &back, fact,
+            "typed fact must serialize to the emitted shape"
+        );
+        assert!(
+            compiled.is_valid(&back),
+            "fact must match the v2 schema: {back}"
+        );
+        stages.insert(ev.stage().as_str());
+    }
+    for stage in [
+        "plan",
+        "preflight",
+        "preflight.summary",
+        "apply.attempt",
+        "apply.result",
+        "rollback",
+        "smoke.result",
+        "prune.result",
+    ] {
+        assert!(stages.contains(stage), "run must emit {stage}: {stages:?}");
+    }
+
+    let smoke = facts
+        .iter()
+        .find_map(|f| match AuditEvent::from_value(f.clone()).unwrap() {
+            AuditEvent::SmokeResult(s) => Some(s),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(smoke.envelope.decision, Decision::Failure);
+    assert_eq!(smoke.envelope.error_id, Nullable::Set(ErrorId::E_SMOKE));
+}
+
+#[test]
+fn parse_jsonl_reads_a_sink_log() {
+    let facts = full_run();
+    let td = tempfile::tempdir().unwrap();
+    let path = td.path().join("facts.jsonl");
+    let mut f = std::fs::File::create(&path).unwrap();
+    writeln!(f, r#"{{"event":"audit","message":"apply: start"}}"#).unwrap();
+    for v in &facts {
+        let mut line = v.clone();
+        line["subsystem"] = json!("switchyard");
+        writeln!(f, "{line}").unwrap();
+        writeln!(f).unwrap();
+    }
+    drop(f);
+
+    let reader = std::io::BufReader::new(std::fs::File::open(&path).unwrap());
+    let parsed: Vec<AuditEvent> = parse_jsonl(reader).map(Result::unwrap).collect();
+    assert_eq!(parsed.len(), facts.len());
+    let prune = parsed
+        .iter()
+        .find_map(|e| match e {
+            AuditEvent::PruneResult(p) => Some(p),
+            _ => None,
+        })
+        .unwrap();
+    assert!(prune.pruned_count.is_some() && prune.retained_count.is_some());
+    assert_eq!(prune.extra["subsystem"], "switchyard");
+}
+
+#[test]
+fn failed_prune_reports_error_detail() {
+    let facts = TestEmitter::default();
+    let api = switchyard::Switchyard::new(facts.clone(), JsonlSink, Policy::default());
+    let td = tempfile::tempdir().unwrap();
+    let root = td.path();
+    // The parent directory does not exist, so listing backups fails.
+    let target = SafePath::from_rooted(root, &root.join("missing/app")).unwrap();
+    assert!(api.prune_backups(&target).is_err());
+
+    let fact = facts
+        .events
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|(_, _, _, f)| f.clone())
+        .find(|f| f["stage"] == "prune.result")
+        .unwrap();
+    // `error` is the structured error object in the v2 envelope, not a message.
+    assert!(fact.get("error").is_none(), "{fact}");
+    let AuditEvent::PruneResult(prune) = AuditEvent::from_value(fact.clone()).unwrap() else {
+        panic!("not a prune.result: {fact}");
+    };
+    assert_eq!(prune.envelope.decision, Decision::Failure);
+    assert!(matches!(prune.envelope.error_detail, Nullable::Set(_)));
+}
@@ -224,7 +224,7 @@ impl World {
                             _plan: &switchyard::types::plan::Plan,
                         ) -> Result<(), switchyard::adapters::smoke::SmokeFailure>
                         {
-                            Err(switchyard::adapters::smoke::SmokeFailure)
+                            Err(switchyard::adapters::smoke::SmokeFailure::default())
                         }
                     }
                     builder = builder.with_smoke_runner(Box::new(Failing));
@@ -1,4 +1,5 @@
 // Module declarations for all test files in the fs directory
+mod preservation_tier_roundtrip;
 mod prune_age_limits;
 mod prune_backups;
 mod prune_invariants_extended;
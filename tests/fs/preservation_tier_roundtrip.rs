@@ -0,0 +1,166 @@
+use std::os::unix::fs::MetadataExt;
+use std::time::{Duration, UNIX_EPOCH};
+
+use serde_json::Value;
+use switchyard::logging::{FactsEmitter, JsonlSink};
+use switchyard::policy::config::PreservationTier;
+use switchyard::policy::Policy;
+use switchyard::types::plan::{LinkRequest, PlanInput, RestoreRequest};
+use switchyard::types::safepath::SafePath;
+use switchyard::types::ApplyMode;
+
+#[derive(Default, Clone, Debug)]
+struct TestEmitter;
+impl FactsEmitter for TestEmitter {
+    fn emit(&self, _subsystem: &str, _event: &str, _decision: &str, _fields: Value) {}
+}
+
+fn api_with_tier(tier: PreservationTier) -> switchyard::Switchyard<TestEmitter, JsonlSink> {
+    let mut policy = Policy::default();
+    policy.governance.allow_unlocked_commit = true;
+    policy.risks.source_trust = switchyard::policy::types::SourceTrustPolicy::AllowUntrusted;
+    policy.apply.capture_restore_snapshot = false;
+    policy.preservation_tier = tier;
+    switchyard::Switchyard::new(TestEmitter, JsonlSink, policy)
+}
+
+fn link_then_restore(
+    api: &switchyard::Switchyard<TestEmitter, JsonlSink>,
+    root: &std::path::Path,
+    prepare: impl FnOnce(&std::path::Path),
+) -> std::path::PathBuf {
+    let src = root.join("bin/new");
+    let tgt = root.join("usr/bin/app");
+    std::fs::create_dir_all(src.parent().unwrap()).unwrap();
+    std::fs::create_dir_all(tgt.parent().unwrap()).unwrap();
+    std::fs::write(&src, b"new").unwrap();
+    std::fs::write(&tgt, b"old").unwrap();
+    prepare(&tgt);
+
+    let sp_tgt = SafePath::from_rooted(root, &tgt).unwrap();
+    let plan = api.plan(PlanInput {
+        link: vec![LinkRequest {
+            source: SafePath::from_rooted(root, &src).unwrap(),
+            target: sp_tgt.clone(),
+        }],
+        restore: vec![],
+    });
+    let rep = api.apply(&plan, ApplyMode::Commit).unwrap();
+    assert!(rep.errors.is_empty(), "link errors: {:?}", rep.errors);
+
+    let plan = api.plan(PlanInput {
+        link: vec![],
+        restore: vec![RestoreRequest { target: sp_tgt }],
+    });
+    let rep = api.apply(&plan, ApplyMode::Commit).unwrap();
+    assert!(rep.errors.is_empty(), "restore errors: {:?}", rep.errors);
+    tgt
+}
+
+fn set_mtime(p: &std::path::Path) {
+    let old = UNIX_EPOCH + Duration::from_secs(1_000_000_000);
+    std::fs::File::options()
+        .write(true)
+        .open(p)
+        .unwrap()
+        .set_times(std::fs::FileTimes::new().set_modified(old))
+        .unwrap();
+}
+
+#[test]
+fn extended_tier_restores_timestamps() {
+    let td = tempfile::tempdir().unwrap();
+    let api = api_with_tier(PreservationTier::Extended);
+    let tgt = link_then_restore(&api, td.path(), set_mtime);
+    let md = std::fs::symlink_metadata(&tgt).unwrap();
+    assert!(md.file_type().is_file());
+    assert_eq!(md.mtime(), 1_000_000_000);
+}
+
+#[test]
+fn basic_tier_does_not_restore_timestamps() {
+    let td = tempfile::tempdir().unwrap();
+    let api = api_with_tier(PreservationTier::Basic);
+    let tgt = link_then_restore(&api, td.path(), set_mtime);
+    assert_ne!(std::fs::metadata(&tgt).unwrap().mtime(), 1_000_000_000);
+}
+
+#[test]
+fn full_tier_restores_xattrs() {
+    let td = tempfile::tempdir().unwrap();
+    let probe = td.path().join("probe");
+    std::fs::write(&probe, b"").unwrap();
+    if xattr::set(&probe, "user.switchyard", b"v").is_err() {
+        eprintln!("skipping: user xattrs unsupported on this filesystem");
+        return;
+    }
+    let api = api_with_tier(PreservationTier::Full);
+    let tgt = link_then_restore(&api, td.path(), |p| {
+        xattr::set(p, "user.switchyard", b"keep").unwrap();
+    });
+    assert_eq!(
+        xattr::get(&tgt, "user.switchyard").unwrap().as_deref(),
+        Some(&b"keep"[..])
+    );
+}
+
+#[test]
+fn preflight_reports_tier_and_effective_dimensions() {
+    let td = tempfile::tempdir().unwrap();
+    let root = td.path();
+    let src = root.join("bin/new");
+    let tgt = root.join("usr/bin/app");
+    std::fs::create_dir_all(src.parent().unwrap()).unwrap();
+    std::fs::create_dir_all(tgt.parent().unwrap()).unwrap();
+    std::fs::write(&src, b"new").unwrap();
+    std::fs::write(&tgt, b"old").unwrap();
+    let input = || PlanInput {
+        link: vec![LinkRequest {
+            source: SafePath::from_rooted(root, &src).unwrap(),
+            target: SafePath::from_rooted(root, &tgt).unwrap(),
+        }],
+        restore: vec![],
+    };
+
+    let basic = api_with_tier(PreservationTier::Basic);
+    let report = basic.preflight(&basic.plan(input())).unwrap();
+    let row = &report.rows[0];
+    assert_eq!(row["preservation_tier"], "basic");
+    assert_eq!(row["preservation_effective"]["mode"], true);
+    assert_eq!(row["preservation_effective"]["timestamps"], false);
+
+    let ext = api_with_tier(PreservationTier::Extended);
+    let report = ext.preflight(&ext.plan(input())).unwrap();
+    let row = &report.rows[0];
+    assert_eq!(row["preservation_tier"], "extended");
+    assert_eq!(row["preservation_effective"]["timestamps"], true);
+    assert_eq!(row["preservation_effective"]["xattrs"], false);
+}
+
+#[test]
+fn full_tier_restore_fails_when_metadata_cannot_be_reapplied() {
+    let td = tempfile::tempdir().unwrap();
+    let root = td.path();
+    let tgt = root.join("usr/bin/app");
+    std::fs::create_dir_all(tgt.parent().unwrap()).unwrap();
+    std::fs::write(&tgt, b"old").unwrap();
+    switchyard::fs::backup::create_snapshot_with_tier(&tgt, "t", PreservationTier::Full).unwrap();
+
+    // No kernel namespace accepts this name, so re-applying it always fails.
+    let sidecar = std::fs::read_dir(tgt.parent().unwrap())
+        .unwrap()
+        .map(|e| e.unwrap().path())
+        .find(|p| p.to_string_lossy().ends_with(".meta.json"))
+        .unwrap();
+    let mut sc: Value = serde_json::from_slice(&std::fs::read(&sidecar).unwrap()).unwrap();
+    sc["xattrs"] = serde_json::json!({ "bogus.ns": "eA==" });
+    std::fs::write(&sidecar, serde_json::to_vec(&sc).unwrap()).unwrap();
+
+    std::fs::remove_file(&tgt).unwrap();
+    std::os::unix::fs::symlink("elsewhere", &tgt).unwrap();
+    let sp = SafePath::from_rooted(root, &tgt).unwrap();
+    let err = switchyard::fs::restore::restore_file(&sp, false, false, "t").unwrap_err();
+    assert!(err.to_string().contains("xattr:bogus.ns"), "{err}");
+    // The bytes are back even though the restore is reported as failed.
+    assert_eq!(std::fs::read(&tgt).unwrap(), b"old");
+}
@@ -0,0 +1,104 @@
+//! Lock holder metadata: a timed-out apply reports who holds the lock and whether it is stale.
+
+use std::sync::{Arc, Mutex};
+
+use serde_json::Value;
+use switchyard::adapters::{FileLockManager, LockHolder, LockManager, LockMode};
+use switchyard::api::errors::ApiError;
+use switchyard::logging::{FactsEmitter, JsonlSink};
+use switchyard::policy::Policy;
+use switchyard::types::plan::{LinkRequest, PlanInput};
+use switchyard::types::safepath::SafePath;
+use switchyard::types::ApplyMode;
+
+type Captured = Arc<Mutex<Vec<(String, String, String, Value)>>>;
+
+#[derive(Default, Clone, Debug)]
+struct TestEmitter {
+    events: Captured,
+}
+
+impl FactsEmitter for TestEmitter {
+    fn emit(&self, subsystem: &str, event: &str, decision: &str, fields: Value) {
+        self.events.lock().unwrap().push((
+            subsystem.to_string(),
+            event.to_string(),
+            decision.to_string(),
+            fields,
+        ));
+    }
+}
+
+/// Run a contended apply and return the error message and the failed `apply.attempt` fact.
+fn contended_apply(root: &std::path::Path, lock_path: &std::path::Path) -> (String, Value) {
+    let facts = TestEmitter::default();
+    let mut policy = Policy::default();
+    policy.risks.source_trust = switchyard::policy::types::SourceTrustPolicy::AllowUntrusted;
+    let api = switchyard::Switchyard::new(facts.clone(), JsonlSink, policy)
+        .with_lock_manager(Box::new(FileLockManager::new(lock_path.to_path_buf())))
+        .with_lock_timeout_ms(100);
+    let src = root.join("bin/new");
+    let tgt = root.join("usr/bin/app");
+    std::fs::create_dir_all(src.parent().unwrap()).unwrap();
+    std::fs::create_dir_all(tgt.parent().unwrap()).unwrap();
+    std::fs::write(&src, b"n").unwrap();
+    std::fs::write(&tgt, b"o").unwrap();
+    let plan = api.plan(PlanInput {
+        link: vec![LinkRequest {
+            source: SafePath::from_rooted(root, &src).unwrap(),
+            target: SafePath::from_rooted(root, &tgt).unwrap(),
+        }],
+        restore: vec![],
+    });
+    let Err(ApiError::LockingTimeout(msg)) = api.apply(&plan, ApplyMode::Commit) else {
+        panic!("expected LockingTimeout");
+    };
+    let events = facts.events.lock().unwrap();
+    let attempt = events
+        .iter()
+        .find(|(_, ev, dec, _)| ev == "apply.attempt" && dec == "failure")
+        .map(|(_, _, _, f)| f.clone())
+        .expect("failed apply.attempt");
+    (msg, attempt)
+}
+
+#[test]
+fn live_holder_is_reported_in_error_and_fact() {
+    let td = tempfile::tempdir().unwrap();
+    let lock_path = td.path().join("switchyard.lock");
+    let holder = LockHolder::current(Some("other-run"), Some("other-plan"));
+    let _g = FileLockManager::new(lock_path.clone())
+        .acquire_process_lock_as(100, LockMode::Exclusive, &holder)
+        .unwrap();
+
+    let (msg, attempt) = contended_apply(td.path(), &lock_path);
+    assert!(
+        msg.contains("held by pid") && msg.contains("run_id other-run"),
+        "{msg}"
+    );
+    assert!(!msg.contains("stale"));
+    let h = &attempt["lock_holder"];
+    assert_eq!(h["pid"], std::process::id());
+    assert_eq!(h["run_id"], "other-run");
+    assert_eq!(h["plan_id"], "other-plan");
+    assert_eq!(h["stale"], false);
+    assert!(h["acquired_at"].as_str().is_some_and(|s| !s.is_empty()));
+}
+
+#[test]
+fn holder_whose_pid_is_gone_is_flagged_stale() {
+    let td = tempfile::tempdir().unwrap();
+    let lock_path = td.path().join("switchyard.lock");
+    let _g = FileLockManager::new(lock_path.clone())
+        .acquire_process_lock(100)
+        .unwrap();
+    // Metadata left behind by a holder that no longer exists (e.g. the lock fd was inherited).
+    let mut dead = LockHolder::current(Some("dead-run"), None);
+    dead.pid = u32::MAX - 1;
+    std::fs::write(&lock_path, dead.to_json().to_string()).unwrap();
+
+    let (msg, attempt) = contended_apply(td.path(), &lock_path);
+    assert!(msg.contains("stale"), "{msg}");
+    assert_eq!(attempt["lock_holder"]["stale"], true);
+    assert_eq!(attempt["lock_holder"]["run_id"], "dead-run");
+}
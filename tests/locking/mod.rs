@@ -1,8 +1,11 @@
 // Module declarations for all test files in the locking directory
 mod lock_attempts;
+mod lock_holder_diagnostics;
 mod lock_timeout_high_ok;
 mod lock_wait_fact;
 mod locking_required;
 mod locking_stage_parity;
 mod locking_timeout;
 mod optional_no_manager_warn;
+mod path_scoped_locks;
+mod shared_exclusive_locks;
@@ -0,0 +1,298 @@
+//! Per-path lock scopes: unrelated trees do not serialize; locks are taken in canonical order;
+//! scopes meet at the process lock and at shared parent keys.
+
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use serde_json::Value;
+use switchyard::adapters::{FileLockManager, LockHolder, LockManager, LockMode};
+use switchyard::logging::{FactsEmitter, JsonlSink};
+use switchyard::policy::types::{LockScope, SourceTrustPolicy};
+use switchyard::policy::Policy;
+use switchyard::types::plan::{LinkRequest, PlanInput};
+use switchyard::types::safepath::SafePath;
+use switchyard::types::{ApplyMode, Plan};
+
+type Captured = Arc<Mutex<Vec<(String, String, String, Value)>>>;
+
+#[derive(Default, Clone, Debug)]
+struct TestEmitter {
+    events: Captured,
+}
+
+impl FactsEmitter for TestEmitter {
+    fn emit(&self, subsystem: &str, event: &str, decision: &str, fields: Value) {
+        self.events.lock().unwrap().push((
+            subsystem.to_string(),
+            event.to_string(),
+            decision.to_string(),
+            fields,
+        ));
+    }
+}
+
+fn api(
+    facts: &TestEmitter,
+    lock_file: &Path,
+    scope: LockScope,
+) -> switchyard::Switchyard<TestEmitter, JsonlSink> {
+    let mut policy = Policy::default();
+    policy.risks.source_trust = SourceTrustPolicy::AllowUntrusted;
+    policy.governance.lock_scope = scope;
+    switchyard::Switchyard::new(facts.clone(), JsonlSink, policy)
+        .with_lock_manager(Box::new(FileLockManager::new(lock_file.to_path_buf())))
+        .with_lock_timeout_ms(200)
+}
+
+fn plan_for(
+    api: &switchyard::Switchyard<TestEmitter, JsonlSink>,
+    root: &Path,
+    targets: &[&str],
+) -> Plan {
+    let link = targets
+        .iter()
+        .map(|t| {
+            let src = root.join("src").join(t.replace('/', "_"));
+            let tgt = root.join(t);
+            std::fs::create_dir_all(src.parent().unwrap()).unwrap();
+            std::fs::create_dir_all(tgt.parent().unwrap()).unwrap();
+            std::fs::write(&src, b"new").unwrap();
+            std::fs::write(&tgt, b"old").unwrap();
+            LinkRequest {
+                source: SafePath::from_rooted(root, &src).unwrap(),
+                target: SafePath::from_rooted(root, &tgt).unwrap(),
+            }
+        })
+        .collect();
+    api.plan(PlanInput {
+        link,
+        restore: vec![],
+    })
+}
+
+fn attempt_facts(facts: &TestEmitter) -> Vec<Value> {
+    facts
+        .events
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|(_, ev, _, _)| ev == "apply.attempt")
+        .map(|(_, _, _, f)| f.clone())
+        .filter(|f| f.get("lock_scope").is_some())
+        .collect()
+}
+
+#[test]
+fn unrelated_tree_is_not_blocked_and_locks_are_sorted() {
+    let td = tempfile::tempdir().unwrap();
+    let root = td.path().canonicalize().unwrap();
+    let lock_file = root.join("switchyard.lock");
+    let facts = TestEmitter::default();
+    let api = api(&facts, &lock_file, LockScope::ParentDirs);
+    // Listed in reverse order on purpose.
+    let plan = plan_for(&api, &root, &["usr/bin/app", "opt/a/tool"]);
+
+    // Another path-scoped run owns /usr/sbin (unrelated) and the process lock shared.
+    let other = FileLockManager::new(lock_file.clone());
+    std::fs::create_dir_all(root.join("usr/sbin")).unwrap();
+    let _held = other
+        .acquire_path_lock(
+            &root.join("usr/sbin"),
+            100,
+            LockMode::Exclusive,
+            &LockHolder::current(None, None),
+        )
+        .unwrap();
+    let _global = other
+        .acquire_process_lock_as(100, LockMode::Shared, &LockHolder::current(None, None))
+        .unwrap();
+
+    let report = api.apply(&plan, ApplyMode::Commit).unwrap();
+    assert!(report.errors.is_empty(), "{:?}", report.errors);
+
+    let attempt = attempt_facts(&facts)
+        .pop()
+        .expect("apply.attempt with lock_scope");
+    assert_eq!(attempt["lock_scope"], "parent_dirs");
+    let paths: Vec<&str> = attempt["lock_paths"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|p| p["path"].as_str().unwrap())
+        .collect();
+    let want = [
+        "global".to_string(),
+        root.join("opt/a").display().to_string(),
+        root.join("usr/bin").display().to_string(),
+    ];
+    assert_eq!(paths, want);
+    assert!(attempt["lock_paths"][0]["wait_ms"].is_u64());
+    assert_eq!(attempt["lock_paths"][0]["mode"], "shared");
+    assert_eq!(attempt["lock_paths"][1]["mode"], "exclusive");
+}
+
+#[test]
+fn overlapping_tree_times_out_with_per_lock_facts() {
+    let td = tempfile::tempdir().unwrap();
+    let root = td.path().canonicalize().unwrap();
+    let lock_file = root.join("switchyard.lock");
+    let facts = TestEmitter::default();
+    let api = api(&facts, &lock_file, LockScope::Targets);
+    let plan = plan_for(&api, &root, &["opt/a/tool", "usr/bin/app"]);
+
+    let other = FileLockManager::new(lock_file.clone());
+    let _held = other
+        .acquire_path_lock(
+            &root.join("usr/bin/app"),
+            100,
+            LockMode::Exclusive,
+            &LockHolder::current(None, None),
+        )
+        .unwrap();
+
+    assert!(api.apply(&plan, ApplyMode::Commit).is_err());
+
+    let attempt = attempt_facts(&facts).pop().expect("failed apply.attempt");
+    assert_eq!(attempt["error_id"], "E_LOCKING");
+    let locks: Vec<(&str, &str, bool)> = attempt["lock_paths"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|l| {
+            (
+                l["path"].as_str().unwrap(),
+                l["mode"].as_str().unwrap(),
+                l["acquired"].as_bool().unwrap(),
+            )
+        })
+        .collect();
+    let (opt_a, opt_tool) = (root.join("opt/a"), root.join("opt/a/tool"));
+    let (usr_bin, usr_app) = (root.join("usr/bin"), root.join("usr/bin/app"));
+    assert_eq!(
+        locks,
+        [
+            ("global", "shared", true),
+            (opt_a.to_str().unwrap(), "shared", true),
+            (opt_tool.to_str().unwrap(), "exclusive", true),
+            (usr_bin.to_str().unwrap(), "shared", true),
+            (usr_app.to_str().unwrap(), "exclusive", false),
+        ]
+    );
+
+    // The first lock was released on failure.
+    assert!(other
+        .acquire_path_lock(
+            &root.join("opt/a/tool"),
+            100,
+            LockMode::Exclusive,
+            &LockHolder::current(None, None)
+        )
+        .is_ok());
+}
+
+/// Holds the locks a run with `scope` takes for `targets`, mirroring the apply orchestration.
+fn hold(
+    lock_file: &Path,
+    scope: LockScope,
+    targets: &[&Path],
+) -> Vec<Box<dyn switchyard::adapters::LockGuard>> {
+    let mgr = FileLockManager::new(lock_file.to_path_buf());
+    let me = LockHolder::current(Some("other-team"), None);
+    if scope == LockScope::Global {
+        return vec![mgr
+            .acquire_process_lock_as(100, LockMode::Exclusive, &me)
+            .unwrap()];
+    }
+    let mut guards = vec![mgr
+        .acquire_process_lock_as(100, LockMode::Shared, &me)
+        .unwrap()];
+    for t in targets {
+        let parent = t.parent().unwrap();
+        let key = if scope == LockScope::ParentDirs {
+            parent
+        } else {
+            guards.push(
+                mgr.acquire_path_lock(parent, 100, LockMode::Shared, &me)
+                    .unwrap(),
+            );
+            t
+        };
+        guards.push(
+            mgr.acquire_path_lock(key, 100, LockMode::Exclusive, &me)
+                .unwrap(),
+        );
+    }
+    guards
+}
+
+#[test]
+fn mixed_scopes_on_the_same_tree_exclude_each_other() {
+    let td = tempfile::tempdir().unwrap();
+    let root = td.path().canonicalize().unwrap();
+    let lock_file = root.join("switchyard.lock");
+    let app = root.join("usr/bin/app");
+    let other_app = root.join("usr/bin/other");
+
+    let usr_bin = root.join("usr/bin").display().to_string();
+    // (held by the other team, applied by us, first lock we cannot take, whether the other
+    // team holds that lock exclusively and so is recorded in the lock file)
+    let cases = [
+        (LockScope::Global, LockScope::ParentDirs, "global", true),
+        (LockScope::ParentDirs, LockScope::Global, "global", false),
+        (LockScope::Targets, LockScope::Global, "global", false),
+        (
+            LockScope::ParentDirs,
+            LockScope::Targets,
+            usr_bin.as_str(),
+            true,
+        ),
+        (
+            LockScope::Targets,
+            LockScope::ParentDirs,
+            usr_bin.as_str(),
+            false,
+        ),
+    ];
+    for (held_scope, our_scope, blocked_on, recorded) in cases {
+        let facts = TestEmitter::default();
+        let api = api(&facts, &lock_file, our_scope);
+        let plan = plan_for(&api, &root, &["usr/bin/app"]);
+        let held = hold(&lock_file, held_scope, &[&other_app]);
+
+        assert!(
+            api.apply(&plan, ApplyMode::Commit).is_err(),
+            "{held_scope:?} holder must block a {our_scope:?} apply"
+        );
+        let attempt = facts
+            .events
+            .lock()
+            .unwrap()
+            .iter()
+            .rfind(|(_, ev, _, f)| ev == "apply.attempt" && f["error_id"] == "E_LOCKING")
+            .map(|(_, _, _, f)| f.clone())
+            .expect("E_LOCKING apply.attempt");
+        let failed = attempt["lock_paths"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|l| l["acquired"] == false)
+            .unwrap();
+        assert_eq!(
+            failed["path"], blocked_on,
+            "{held_scope:?} vs {our_scope:?}"
+        );
+        if recorded {
+            assert_eq!(attempt["lock_holder"]["run_id"], "other-team");
+        }
+        drop(held);
+    }
+
+    // Different targets in one directory under `Targets` run side by side.
+    let facts = TestEmitter::default();
+    let api = api(&facts, &lock_file, LockScope::Targets);
+    let plan = plan_for(&api, &root, &["usr/bin/app"]);
+    let _held = hold(&lock_file, LockScope::Targets, &[&other_app]);
+    let report = api.apply(&plan, ApplyMode::Commit).unwrap();
+    assert!(report.errors.is_empty(), "{:?}", report.errors);
+    assert!(app.is_symlink());
+}
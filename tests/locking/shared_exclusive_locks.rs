@@ -0,0 +1,137 @@
+//! Shared vs exclusive locks: preflight optionally reads under a shared lock that excludes a
+//! concurrent apply, and facts record the lock mode.
+
+use std::sync::{Arc, Mutex};
+
+use serde_json::Value;
+use switchyard::adapters::{FileLockManager, LockHolder, LockManager, LockMode};
+use switchyard::logging::{FactsEmitter, JsonlSink};
+use switchyard::policy::Policy;
+use switchyard::types::plan::{LinkRequest, PlanInput};
+use switchyard::types::safepath::SafePath;
+use switchyard::types::{ApplyMode, Plan};
+
+type Captured = Arc<Mutex<Vec<(String, String, String, Value)>>>;
+
+#[derive(Default, Clone, Debug)]
+struct TestEmitter {
+    events: Captured,
+}
+
+impl FactsEmitter for TestEmitter {
+    fn emit(&self, subsystem: &str, event: &str, decision: &str, fields: Value) {
+        self.events.lock().unwrap().push((
+            subsystem.to_string(),
+            event.to_string(),
+            decision.to_string(),
+            fields,
+        ));
+    }
+}
+
+impl TestEmitter {
+    fn last(&self, stage: &str) -> Value {
+        self.events
+            .lock()
+            .unwrap()
+            .iter()
+            .rev()
+            .find(|(_, ev, _, _)| ev == stage)
+            .map(|(_, _, _, f)| f.clone())
+            .unwrap_or_else(|| panic!("no {stage} fact"))
+    }
+}
+
+fn setup(
+    root: &std::path::Path,
+    lock_path: &std::path::Path,
+) -> (
+    TestEmitter,
+    switchyard::Switchyard<TestEmitter, JsonlSink>,
+    Plan,
+) {
+    let facts = TestEmitter::default();
+    let mut policy = Policy::default();
+    policy.risks.source_trust = switchyard::policy::types::SourceTrustPolicy::AllowUntrusted;
+    policy.governance.preflight_shared_lock = true;
+    let api = switchyard::Switchyard::new(facts.clone(), JsonlSink, policy)
+        .with_lock_manager(Box::new(FileLockManager::new(lock_path.to_path_buf())))
+        .with_lock_timeout_ms(100);
+    let src = root.join("bin/new");
+    let tgt = root.join("usr/bin/app");
+    std::fs::create_dir_all(src.parent().unwrap()).unwrap();
+    std::fs::create_dir_all(tgt.parent().unwrap()).unwrap();
+    std::fs::write(&src, b"n").unwrap();
+    std::fs::write(&tgt, b"o").unwrap();
+    let plan = api.plan(PlanInput {
+        link: vec![LinkRequest {
+            source: SafePath::from_rooted(root, &src).unwrap(),
+            target: SafePath::from_rooted(root, &tgt).unwrap(),
+        }],
+        restore: vec![],
+    });
+    (facts, api, plan)
+}
+
+#[test]
+fn preflight_waits_out_exclusive_holder_and_reports_e_locking() {
+    let td = tempfile::tempdir().unwrap();
+    let lock_path = td.path().join("switchyard.lock");
+    let (facts, api, plan) = setup(td.path(), &lock_path);
+    let applying = LockHolder::current(Some("apply-run"), None);
+    let g = FileLockManager::new(lock_path.clone())
+        .acquire_process_lock_as(100, LockMode::Exclusive, &applying)
+        .unwrap();
+
+    let report = api.preflight(&plan).unwrap();
+    assert!(!report.ok);
+    assert!(report.rows.is_empty(), "nothing inspected without the lock");
+    assert!(report.stops[0].contains("E_LOCKING"), "{:?}", report.stops);
+    let summary = facts.last("preflight.summary");
+    assert_eq!(summary["error_id"], "E_LOCKING");
+    assert_eq!(summary["lock_mode"], "shared");
+    assert_eq!(summary["lock_holder"]["run_id"], "apply-run");
+
+    drop(g);
+    let report = api.preflight(&plan).unwrap();
+    assert!(report.ok, "{:?}", report.stops);
+    let summary = facts.last("preflight.summary");
+    assert_eq!(summary["lock_mode"], "shared");
+    assert_eq!(summary["lock_paths"][0]["acquired"], true);
+
+    let schema: Value =
+        serde_json::from_str(include_str!("../../SPEC/audit_event.v2.schema.json")).unwrap();
+    let compiled = jsonschema::JSONSchema::compile(&schema).expect("valid schema");
+    assert!(compiled.is_valid(&summary));
+}
+
+#[test]
+fn shared_holder_admits_preflight_but_blocks_apply() {
+    let td = tempfile::tempdir().unwrap();
+    let lock_path = td.path().join("switchyard.lock");
+    let (facts, api, plan) = setup(td.path(), &lock_path);
+    let reader = FileLockManager::new(lock_path.clone())
+        .acquire_process_lock_as(100, LockMode::Shared, &LockHolder::current(None, None))
+        .unwrap();
+
+    assert!(api.preflight(&plan).unwrap().ok);
+    let err = api.apply(&plan, ApplyMode::Commit).unwrap_err();
+    assert!(
+        format!("{err:?}").contains("exclusive process lock"),
+        "{err:?}"
+    );
+    assert_eq!(facts.last("apply.attempt")["lock_mode"], "exclusive");
+
+    drop(reader);
+    facts.events.lock().unwrap().clear();
+    let report = api.apply(&plan, ApplyMode::Commit).unwrap();
+    assert!(report.errors.is_empty(), "{:?}", report.errors);
+    // The first attempt fact is the lock summary; per-action attempts follow.
+    let events = facts.events.lock().unwrap();
+    let attempt = &events
+        .iter()
+        .find(|(_, ev, _, _)| ev == "apply.attempt")
+        .unwrap()
+        .3;
+    assert_eq!(attempt["lock_mode"], "exclusive");
+}
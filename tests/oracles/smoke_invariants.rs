@@ -32,7 +32,7 @@ impl switchyard::adapters::SmokeTestRunner for FailingSmokeRunner {
         &self,
         _plan: &switchyard::types::plan::Plan,
     ) -> Result<(), switchyard::adapters::SmokeFailure> {
-        Err(switchyard::adapters::SmokeFailure)
+        Err(switchyard::adapters::SmokeFailure::default())
     }
 }
 
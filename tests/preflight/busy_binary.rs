@@ -0,0 +1,126 @@
+use switchyard::policy::types::RiskLevel;
+use switchyard::policy::Policy;
+use switchyard::types::plan::{LinkRequest, PlanInput};
+use switchyard::types::safepath::SafePath;
+use switchyard::types::ApplyMode;
+use switchyard::Switchyard;
+
+struct Running(std::process::Child);
+impl Drop for Running {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+/// Copy `sleep` into place as the target and run it.
+fn spawn_target(tgt: &std::path::Path) -> Option<Running> {
+    let sleep = ["/bin/sleep", "/usr/bin/sleep"]
+        .into_iter()
+        .map(std::path::Path::new)
+        .find(|p| p.exists())?;
+    std::fs::copy(sleep, tgt).ok()?;
+    // Retry on ETXTBSY: a concurrently forked test may briefly hold our write fd.
+    for _ in 0..50 {
+        match std::process::Command::new(tgt).arg("30").spawn() {
+            Ok(child) => {
+                // Wait until exec has happened so /proc/<pid>/exe points at the target.
+                let exe = format!("/proc/{}/exe", child.id());
+                for _ in 0..100 {
+                    if std::fs::read_link(&exe).is_ok_and(|p| p == tgt) {
+                        break;
+                    }
+                    std::thread::sleep(std::time::Duration::from_millis(10));
+                }
+                return Some(Running(child));
+            }
+            Err(e) if e.raw_os_error() == Some(26) => {
+                std::thread::sleep(std::time::Duration::from_millis(20));
+            }
+            Err(_) => return None,
+        }
+    }
+    None
+}
+
+fn setup(root: &std::path::Path) -> (PlanInput, std::path::PathBuf) {
+    let src = root.join("bin/new");
+    let tgt = root.join("usr/bin/app");
+    std::fs::create_dir_all(src.parent().unwrap()).unwrap();
+    std::fs::create_dir_all(tgt.parent().unwrap()).unwrap();
+    std::fs::write(&src, b"new").unwrap();
+    let input = PlanInput {
+        link: vec![LinkRequest {
+            source: SafePath::from_rooted(root, &src).unwrap(),
+            target: SafePath::from_rooted(root, &tgt).unwrap(),
+        }],
+        restore: vec![],
+    };
+    (input, tgt)
+}
+
+fn api(
+    level: RiskLevel,
+) -> Switchyard<switchyard::logging::JsonlSink, switchyard::logging::JsonlSink> {
+    let mut policy = Policy::default();
+    policy.risks.source_trust = switchyard::policy::types::SourceTrustPolicy::AllowUntrusted;
+    policy.risks.busy_binaries = level;
+    policy.governance.allow_unlocked_commit = true;
+    Switchyard::new(
+        switchyard::logging::JsonlSink,
+        switchyard::logging::JsonlSink,
+        policy,
+    )
+}
+
+#[test]
+fn busy_target_is_reported_and_listed_after_apply() {
+    let td = tempfile::tempdir().unwrap();
+    let (input, tgt) = setup(td.path());
+    let Some(child) = spawn_target(&tgt) else {
+        eprintln!("skipping: cannot run a copy of sleep");
+        return;
+    };
+    let pid = child.0.id();
+
+    let api = api(RiskLevel::Warn);
+    let plan = api.plan(input);
+    let report = api.preflight(&plan).unwrap();
+    assert!(report.ok, "Warn must not stop: {:?}", report.stops);
+    assert!(
+        report.warnings.iter().any(|w| w.contains("busy binary")),
+        "warnings: {:?}",
+        report.warnings
+    );
+    let busy = report.rows[0]["busy"].as_array().unwrap();
+    assert!(busy
+        .iter()
+        .any(|b| b["pid"] == pid && b["via"] == "exe" && b["comm"] == "app"));
+
+    let rep = api.apply(&plan, ApplyMode::Commit).unwrap();
+    assert!(rep.errors.is_empty(), "{:?}", rep.errors);
+    assert!(
+        rep.stale_processes
+            .iter()
+            .any(|p| p.pid == pid && p.path.ends_with("usr/bin/app")),
+        "stale: {:?}",
+        rep.stale_processes
+    );
+}
+
+#[test]
+fn busy_target_stops_when_policy_says_stop() {
+    let td = tempfile::tempdir().unwrap();
+    let (input, tgt) = setup(td.path());
+    let Some(_child) = spawn_target(&tgt) else {
+        eprintln!("skipping: cannot run a copy of sleep");
+        return;
+    };
+    let api = api(RiskLevel::Stop);
+    let plan = api.plan(input);
+    let report = api.preflight(&plan).unwrap();
+    assert!(report.stops.iter().any(|s| s.contains("busy binary")));
+    let rep = api.apply(&plan, ApplyMode::Commit).unwrap();
+    assert!(rep.executed.is_empty());
+    assert!(rep.errors.iter().any(|e| e.contains("busy binary")));
+}
@@ -0,0 +1,177 @@
+use serde_json::Value;
+use switchyard::logging::{FactsEmitter, JsonlSink};
+use switchyard::policy::{CheckFinding, Policy, PolicyCheck};
+use switchyard::types::plan::{LinkRequest, PlanInput};
+use switchyard::types::safepath::SafePath;
+use switchyard::types::{Action, ApplyMode, Plan};
+
+type Captured = std::sync::Arc<std::sync::Mutex<Vec<(String, String, String, Value)>>>;
+
+#[derive(Default, Clone, Debug)]
+struct TestEmitter {
+    events: Captured,
+}
+impl FactsEmitter for TestEmitter {
+    fn emit(&self, subsystem: &str, event: &str, decision: &str, fields: Value) {
+        self.events.lock().unwrap().push((
+            subsystem.to_string(),
+            event.to_string(),
+            decision.to_string(),
+            fields,
+        ));
+    }
+}
+
+/// Site rule: never touch a binary named `critical`; warn on everything else.
+#[derive(Debug)]
+struct CriticalBins;
+
+impl PolicyCheck for CriticalBins {
+    fn name(&self) -> &str {
+        "critical-bins"
+    }
+    fn check_action(&self, _policy: &Policy, act: &Action) -> Vec<CheckFinding> {
+        let Action::EnsureSymlink { target, .. } = act else {
+            return Vec::new();
+        };
+        if target.rel().ends_with("critical") {
+            vec![CheckFinding::stop(
+                "critical_bin",
+                "target is a critical binary",
+            )]
+        } else {
+            vec![CheckFinding::warn(
+                "unsigned_source",
+                "source not from a signed package",
+            )]
+        }
+    }
+}
+
+/// Plan-level rule: cap the number of actions.
+#[derive(Debug)]
+struct MaxActions(usize);
+
+impl PolicyCheck for MaxActions {
+    fn name(&self) -> &str {
+        "max-actions"
+    }
+    fn check_plan(&self, _policy: &Policy, plan: &Plan) -> Vec<CheckFinding> {
+        if plan.actions.len() > self.0 {
+            vec![CheckFinding::stop(
+                "too_many_actions",
+                "plan exceeds action budget",
+            )]
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+fn layout(root: &std::path::Path, names: &[&str]) -> PlanInput {
+    std::fs::create_dir_all(root.join("bin")).unwrap();
+    std::fs::create_dir_all(root.join("usr/bin")).unwrap();
+    let mut link = Vec::new();
+    for n in names {
+        std::fs::write(root.join("bin").join(n), b"new").unwrap();
+        std::fs::write(root.join("usr/bin").join(n), b"old").unwrap();
+        link.push(LinkRequest {
+            source: SafePath::from_rooted(root, &root.join("bin").join(n)).unwrap(),
+            target: SafePath::from_rooted(root, &root.join("usr/bin").join(n)).unwrap(),
+        });
+    }
+    PlanInput {
+        link,
+        restore: vec![],
+    }
+}
+
+fn policy() -> Policy {
+    let mut policy = Policy::default();
+    policy.risks.source_trust = switchyard::policy::types::SourceTrustPolicy::AllowUntrusted;
+    policy.governance.allow_unlocked_commit = true;
+    policy
+}
+
+#[test]
+fn custom_action_check_findings_merge_into_rows_and_gate_apply() {
+    let facts = TestEmitter::default();
+    let api = switchyard::api::ApiBuilder::new(facts.clone(), JsonlSink, policy())
+        .with_policy_check(Box::new(CriticalBins))
+        .build();
+
+    let td = tempfile::tempdir().unwrap();
+    let plan = api.plan(layout(td.path(), &["critical", "ls"]));
+    let report = api.preflight(&plan).unwrap();
+
+    assert!(!report.ok);
+    assert!(
+        report
+            .stops
+            .iter()
+            .any(|s| s.contains("critical-bins") && s.contains("critical_bin")),
+        "stops: {:?}",
+        report.stops
+    );
+    assert!(
+        report
+            .warnings
+            .iter()
+            .any(|w| w.contains("unsigned_source")),
+        "warnings: {:?}",
+        report.warnings
+    );
+    let crit = report
+        .rows
+        .iter()
+        .find(|r| r["path"].as_str().unwrap().ends_with("critical"))
+        .unwrap();
+    assert_eq!(crit["policy_ok"], Value::from(false));
+    assert_eq!(crit["checks"][0]["code"], Value::from("critical_bin"));
+    assert_eq!(crit["checks"][0]["severity"], Value::from("stop"));
+    let ls = report
+        .rows
+        .iter()
+        .find(|r| r["path"].as_str().unwrap().ends_with("ls"))
+        .unwrap();
+    assert_eq!(ls["policy_ok"], Value::from(true));
+    assert_eq!(ls["checks"][0]["severity"], Value::from("warn"));
+
+    let rep = api.apply(&plan, ApplyMode::Commit).unwrap();
+    assert!(
+        rep.executed.is_empty(),
+        "apply must refuse when a custom check stops"
+    );
+    assert!(rep.errors.iter().any(|e| e.contains("critical_bin")));
+    let events = facts.events.lock().unwrap();
+    assert!(events.iter().any(|(_, _, _, f)| {
+        f["stage"] == "apply.result" && f["decision"] == "failure" && f["error_id"] == "E_POLICY"
+    }));
+    assert!(
+        std::fs::symlink_metadata(td.path().join("usr/bin/critical"))
+            .unwrap()
+            .file_type()
+            .is_file()
+    );
+}
+
+#[test]
+fn custom_plan_check_stops_are_global_and_reported_in_summary() {
+    let facts = TestEmitter::default();
+    let api = switchyard::Switchyard::new(facts.clone(), JsonlSink, policy())
+        .with_policy_check(Box::new(MaxActions(1)));
+
+    let td = tempfile::tempdir().unwrap();
+    let plan = api.plan(layout(td.path(), &["cp", "mv"]));
+    let report = api.preflight(&plan).unwrap();
+    assert!(report.stops.iter().any(|s| s.contains("too_many_actions")));
+
+    let events = facts.events.lock().unwrap();
+    let summary = events
+        .iter()
+        .map(|(_, _, _, f)| f)
+        .find(|f| f["stage"] == "preflight.summary")
+        .unwrap();
+    assert_eq!(summary["decision"], Value::from("failure"));
+    assert_eq!(summary["checks"][0]["check"], Value::from("max-actions"));
+}
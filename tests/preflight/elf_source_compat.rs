@@ -0,0 +1,136 @@
+use switchyard::policy::types::{ElfTarget, RiskLevel};
+use switchyard::policy::Policy;
+use switchyard::preflight::elf::parse_elf;
+use switchyard::types::plan::{LinkRequest, PlanInput};
+use switchyard::types::safepath::SafePath;
+use switchyard::Switchyard;
+
+/// A dynamically linked host binary to use as a source, if one is available.
+fn dynamic_host_binary() -> Option<std::path::PathBuf> {
+    ["/bin/sleep", "/usr/bin/sleep", "/bin/ls", "/usr/bin/ls"]
+        .into_iter()
+        .map(std::path::PathBuf::from)
+        .find(|p| {
+            parse_elf(p)
+                .ok()
+                .flatten()
+                .is_some_and(|i| i.interp.is_some() && !i.needed.is_empty())
+        })
+}
+
+fn setup(root: &std::path::Path, bin: &std::path::Path) -> PlanInput {
+    let src = root.join("opt/new/tool");
+    let tgt = root.join("usr/bin/tool");
+    std::fs::create_dir_all(src.parent().unwrap()).unwrap();
+    std::fs::create_dir_all(tgt.parent().unwrap()).unwrap();
+    std::fs::copy(bin, &src).unwrap();
+    std::fs::write(&tgt, b"old").unwrap();
+    PlanInput {
+        link: vec![LinkRequest {
+            source: SafePath::from_rooted(root, &src).unwrap(),
+            target: SafePath::from_rooted(root, &tgt).unwrap(),
+        }],
+        restore: vec![],
+    }
+}
+
+fn api(
+    policy_mut: impl FnOnce(&mut Policy),
+) -> Switchyard<switchyard::logging::JsonlSink, switchyard::logging::JsonlSink> {
+    let mut policy = Policy::default();
+    policy.risks.source_trust = switchyard::policy::types::SourceTrustPolicy::AllowUntrusted;
+    policy_mut(&mut policy);
+    Switchyard::new(
+        switchyard::logging::JsonlSink,
+        switchyard::logging::JsonlSink,
+        policy,
+    )
+}
+
+#[test]
+fn missing_interpreter_and_libraries_stop_when_policy_is_stop() {
+    let Some(bin) = dynamic_host_binary() else {
+        eprintln!("skipping: no dynamically linked host binary found");
+        return;
+    };
+    let td = tempfile::tempdir().unwrap();
+    let api = api(|p| p.risks.elf_compat = RiskLevel::Stop);
+    let report = api.preflight(&api.plan(setup(td.path(), &bin))).unwrap();
+    assert!(
+        report
+            .stops
+            .iter()
+            .any(|s| s.contains("elf incompatible") && s.contains("interpreter")),
+        "stops: {:?}",
+        report.stops
+    );
+    assert!(report.stops.iter().any(|s| s.contains("does not resolve")));
+}
+
+#[test]
+fn resolvable_layout_passes_and_warn_level_does_not_stop() {
+    let Some(bin) = dynamic_host_binary() else {
+        eprintln!("skipping: no dynamically linked host binary found");
+        return;
+    };
+    let info = parse_elf(&bin).unwrap().unwrap();
+    let td = tempfile::tempdir().unwrap();
+    let root = td.path();
+    let input = setup(root, &bin);
+
+    // Warn: findings become warnings, not stops.
+    let warn = api(|p| p.risks.elf_compat = RiskLevel::Warn);
+    let report = warn.preflight(&warn.plan(input.clone())).unwrap();
+    assert!(report.ok, "stops: {:?}", report.stops);
+    assert!(report.warnings.iter().any(|w| w.contains("interpreter")));
+
+    // Allow: findings are recorded on the row but are neither warnings nor stops.
+    let allow = api(|p| p.risks.elf_compat = RiskLevel::Allow);
+    let report = allow.preflight(&allow.plan(input.clone())).unwrap();
+    assert!(report.ok, "stops: {:?}", report.stops);
+    assert!(
+        !report
+            .warnings
+            .iter()
+            .any(|w| w.contains("elf incompatible")),
+        "{:?}",
+        report.warnings
+    );
+
+    // Provide interpreter and libraries under the root: no findings.
+    let interp = root.join(info.interp.as_deref().unwrap().trim_start_matches('/'));
+    std::fs::create_dir_all(interp.parent().unwrap()).unwrap();
+    std::fs::write(&interp, b"").unwrap();
+    std::fs::create_dir_all(root.join("usr/lib")).unwrap();
+    for lib in &info.needed {
+        std::fs::write(root.join("usr/lib").join(lib), b"").unwrap();
+    }
+    let strict = api(|p| p.risks.elf_compat = RiskLevel::Stop);
+    let report = strict.preflight(&strict.plan(input)).unwrap();
+    assert!(report.ok, "stops: {:?}", report.stops);
+}
+
+#[test]
+fn foreign_architecture_is_flagged() {
+    let Some(bin) = dynamic_host_binary() else {
+        eprintln!("skipping: no dynamically linked host binary found");
+        return;
+    };
+    let host = parse_elf(&bin).unwrap().unwrap();
+    let other = if host.machine == 183 {
+        "x86_64"
+    } else {
+        "aarch64"
+    };
+    let td = tempfile::tempdir().unwrap();
+    let api = api(|p| {
+        p.risks.elf_compat = RiskLevel::Stop;
+        p.risks.elf_target = ElfTarget::from_arch(other);
+    });
+    let report = api.preflight(&api.plan(setup(td.path(), &bin))).unwrap();
+    assert!(
+        report.stops.iter().any(|s| s.contains("expected machine")),
+        "stops: {:?}",
+        report.stops
+    );
+}
@@ -0,0 +1,115 @@
+use serde_json::Value;
+use switchyard::fs::fstype::{fs_type_at, fs_type_of, FsClass};
+use switchyard::logging::{FactsEmitter, JsonlSink};
+use switchyard::policy::types::{ExdevPolicy, FsClassAction};
+use switchyard::policy::Policy;
+use switchyard::types::plan::{LinkRequest, PlanInput};
+use switchyard::types::safepath::SafePath;
+use switchyard::types::ApplyMode;
+
+type Captured = std::sync::Arc<std::sync::Mutex<Vec<(String, String, String, Value)>>>;
+
+#[derive(Default, Clone, Debug)]
+struct TestEmitter {
+    events: Captured,
+}
+impl FactsEmitter for TestEmitter {
+    fn emit(&self, subsystem: &str, event: &str, decision: &str, fields: Value) {
+        self.events.lock().unwrap().push((
+            subsystem.to_string(),
+            event.to_string(),
+            decision.to_string(),
+            fields,
+        ));
+    }
+}
+
+fn setup(root: &std::path::Path) -> PlanInput {
+    let src = root.join("bin/new");
+    let tgt = root.join("usr/bin/app");
+    std::fs::create_dir_all(src.parent().unwrap()).unwrap();
+    std::fs::create_dir_all(tgt.parent().unwrap()).unwrap();
+    std::fs::write(&src, b"new").unwrap();
+    std::fs::write(&tgt, b"old").unwrap();
+    PlanInput {
+        link: vec![LinkRequest {
+            source: SafePath::from_rooted(root, &src).unwrap(),
+            target: SafePath::from_rooted(root, &tgt).unwrap(),
+        }],
+        restore: vec![],
+    }
+}
+
+fn policy(tmpfs: FsClassAction, exdev: ExdevPolicy) -> Policy {
+    let mut p = Policy::default();
+    p.risks.source_trust = switchyard::policy::types::SourceTrustPolicy::AllowUntrusted;
+    p.governance.allow_unlocked_commit = true;
+    p.risks.fs_classes.tmpfs = tmpfs;
+    p.apply.exdev = exdev;
+    p
+}
+
+#[test]
+fn fs_type_recorded_in_rows_and_apply_facts() {
+    let td = tempfile::tempdir().unwrap();
+    let tgt = td.path().join("usr/bin/app");
+    let input = setup(td.path());
+    let Some(expected) = fs_type_at(&tgt) else {
+        eprintln!("skipping: statfs unavailable");
+        return;
+    };
+    let facts = TestEmitter::default();
+    let mut p = policy(FsClassAction::Allow, ExdevPolicy::Fail);
+    p.risks.fs_classes.network = FsClassAction::Allow;
+    p.risks.fs_classes.fuse = FsClassAction::Allow;
+    p.risks.fs_classes.overlay = FsClassAction::Allow;
+    let api = switchyard::Switchyard::new(facts.clone(), JsonlSink, p);
+    let plan = api.plan(input);
+    let report = api.preflight(&plan).unwrap();
+    assert!(report.ok, "stops: {:?}", report.stops);
+    assert_eq!(report.rows[0]["fs_type"], expected.name);
+
+    let rep = api.apply(&plan, ApplyMode::Commit).unwrap();
+    assert!(rep.errors.is_empty(), "{:?}", rep.errors);
+    assert!(facts
+        .events
+        .lock()
+        .unwrap()
+        .iter()
+        .any(|(_, _, _, f)| f["stage"] == "apply.result"
+            && f["action_id"].is_string()
+            && f["fs_type"] == expected.name));
+}
+
+#[test]
+fn tmpfs_class_actions() {
+    let shm = std::path::Path::new("/dev/shm");
+    if fs_type_of(shm).map(|t| t.class) != Some(FsClass::Tmpfs) {
+        eprintln!("skipping: /dev/shm is not tmpfs");
+        return;
+    }
+    let Ok(td) = tempfile::tempdir_in(shm) else {
+        eprintln!("skipping: /dev/shm not writable");
+        return;
+    };
+    let run = |p: Policy| {
+        let api = switchyard::Switchyard::new(JsonlSink, JsonlSink, p);
+        api.preflight(&api.plan(setup(td.path()))).unwrap()
+    };
+
+    let r = run(policy(FsClassAction::Stop, ExdevPolicy::Fail));
+    assert!(r.stops.iter().any(|s| s.contains("filesystem tmpfs")));
+
+    let r = run(policy(FsClassAction::RequireDegraded, ExdevPolicy::Fail));
+    assert!(r.stops.iter().any(|s| s.contains("requires apply.exdev")));
+
+    let r = run(policy(
+        FsClassAction::RequireDegraded,
+        ExdevPolicy::DegradedFallback,
+    ));
+    assert!(r.ok, "stops: {:?}", r.stops);
+    assert!(r.warnings.iter().any(|w| w.contains("filesystem tmpfs")));
+
+    let r = run(policy(FsClassAction::Allow, ExdevPolicy::Fail));
+    assert!(!r.warnings.iter().any(|w| w.contains("filesystem tmpfs")));
+}
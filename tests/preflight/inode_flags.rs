@@ -0,0 +1,157 @@
+use rustix::fs::{ioctl_getflags, ioctl_setflags, IFlags};
+use switchyard::policy::Policy;
+use switchyard::preflight::{check_inode_flags, InodeFlagStopKind};
+use switchyard::types::plan::{LinkRequest, PlanInput};
+use switchyard::types::safepath::SafePath;
+use switchyard::Switchyard;
+
+fn setup() -> (tempfile::TempDir, PlanInput, std::path::PathBuf) {
+    let td = tempfile::tempdir().unwrap();
+    let root = td.path();
+    let src = root.join("bin/new");
+    let tgt = root.join("usr/bin/app");
+    std::fs::create_dir_all(src.parent().unwrap()).unwrap();
+    std::fs::create_dir_all(tgt.parent().unwrap()).unwrap();
+    std::fs::write(&src, b"new").unwrap();
+    std::fs::write(&tgt, b"old").unwrap();
+    let input = PlanInput {
+        link: vec![LinkRequest {
+            source: SafePath::from_rooted(root, &src).unwrap(),
+            target: SafePath::from_rooted(root, &tgt).unwrap(),
+        }],
+        restore: vec![],
+    };
+    (td, input, tgt)
+}
+
+fn add_flags(path: &std::path::Path, add: IFlags) -> bool {
+    let Ok(f) = std::fs::File::open(path) else {
+        return false;
+    };
+    let Ok(cur) = ioctl_getflags(&f) else {
+        return false;
+    };
+    ioctl_setflags(&f, cur | add).is_ok()
+}
+
+fn clear_flags(path: &std::path::Path, del: IFlags) {
+    if let Ok(f) = std::fs::File::open(path) {
+        if let Ok(cur) = ioctl_getflags(&f) {
+            let _ = ioctl_setflags(&f, cur - del);
+        }
+    }
+}
+
+fn api() -> Switchyard<switchyard::logging::JsonlSink, switchyard::logging::JsonlSink> {
+    let mut policy = Policy::default();
+    policy.risks.source_trust = switchyard::policy::types::SourceTrustPolicy::AllowUntrusted;
+    Switchyard::new(
+        switchyard::logging::JsonlSink,
+        switchyard::logging::JsonlSink,
+        policy,
+    )
+}
+
+#[test]
+fn append_only_target_stops_preflight() {
+    let (_td, input, tgt) = setup();
+    if !add_flags(&tgt, IFlags::APPEND) {
+        eprintln!("skipping: cannot set append-only flag (requires CAP_LINUX_IMMUTABLE)");
+        return;
+    }
+    let api = api();
+    let report = api.preflight(&api.plan(input)).unwrap();
+    clear_flags(&tgt, IFlags::APPEND);
+    assert!(
+        report
+            .stops
+            .iter()
+            .any(|s| s.starts_with("append-only target: ")),
+        "expected append-only STOP: {:?}",
+        report.stops
+    );
+    assert!(
+        !report.stops.iter().any(|s| s.contains("immutable")),
+        "append-only is not reported as immutable: {:?}",
+        report.stops
+    );
+}
+
+#[test]
+fn nodump_target_is_noted_but_not_stopped() {
+    let (_td, input, tgt) = setup();
+    if !add_flags(&tgt, IFlags::NODUMP) {
+        eprintln!("skipping: filesystem does not support inode flags");
+        return;
+    }
+    let api = api();
+    let report = api.preflight(&api.plan(input)).unwrap();
+    assert!(report.ok, "stops: {:?}", report.stops);
+    let notes = report.rows[0]["notes"].as_array().unwrap();
+    assert!(notes.iter().any(|n| n == "nodump flag set on target"));
+}
+
+#[test]
+fn unreliable_probe_honors_policy_knob() {
+    // procfs does not implement FS_IOC_GETFLAGS
+    let p = std::path::Path::new("/proc/self/status");
+    let strict = check_inode_flags(p, false);
+    assert!(
+        strict
+            .stops
+            .iter()
+            .any(|(_, s)| s.contains("immutable check unreliable")),
+        "{strict:?}"
+    );
+    let relaxed = check_inode_flags(p, true);
+    assert!(relaxed.stops.is_empty());
+    assert!(relaxed
+        .notes
+        .iter()
+        .any(|n| n.contains("allowed by policy")));
+}
+
+#[test]
+fn unsupported_filesystem_is_unreliable_and_policy_gated() {
+    // procfs does not implement FS_IOC_GETFLAGS (ENOTTY).
+    let path = std::path::Path::new("/proc/version");
+    if !path.exists() {
+        eprintln!("skipping: /proc not mounted");
+        return;
+    }
+    let strict = check_inode_flags(path, false);
+    assert!(
+        strict
+            .stops
+            .iter()
+            .all(|(kind, _)| *kind == InodeFlagStopKind::Unreliable),
+        "{:?}",
+        strict.stops
+    );
+    assert_eq!(strict.stops.len(), 1);
+    let allowed = check_inode_flags(path, true);
+    assert!(allowed.stops.is_empty(), "{:?}", allowed.stops);
+    assert!(allowed
+        .notes
+        .iter()
+        .any(|n| n.contains("unreliable (allowed by policy)")));
+}
+
+#[test]
+fn unreadable_flags_stop_regardless_of_policy() {
+    // open(2) on a unix socket fails with ENXIO: not an unsupported ioctl, so the knob does not
+    // apply.
+    let td = tempfile::tempdir().unwrap();
+    let path = td.path().join("sock");
+    let _listener = std::os::unix::net::UnixListener::bind(&path).unwrap();
+    for allow in [false, true] {
+        let res = check_inode_flags(&path, allow);
+        assert_eq!(res.stops.len(), 1, "{res:?}");
+        assert_eq!(res.stops[0].0, InodeFlagStopKind::Unreadable);
+        assert!(res.stops[0].1.starts_with("cannot read inode flags"));
+    }
+    assert_eq!(
+        InodeFlagStopKind::Unreadable.label(),
+        "inode flags unreadable"
+    );
+}
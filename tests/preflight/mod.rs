@@ -1,13 +1,21 @@
 // Module declarations for all test files in the preflight directory
 mod backup_tag_long;
 mod baseline_ok;
+mod busy_binary;
+mod custom_policy_check;
+mod elf_source_compat;
 mod extra_mount_check;
 mod extra_mount_checks_five;
 mod extra_mount_checks_many;
 mod fallback_toolset_on_path;
+mod fs_class_policy;
+mod inode_flags;
+mod mount_analysis;
 mod ownership_strict_without_oracle;
 mod preflight_preservation_required;
 mod preflight_suid_sgid;
 mod preflight_yaml;
 mod preflight_yaml_golden;
 mod rescue_exec_min_count;
+mod rescue_plan_aware;
+mod space_budget;
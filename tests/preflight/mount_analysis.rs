@@ -0,0 +1,91 @@
+use std::os::unix::fs::PermissionsExt;
+use switchyard::fs::mount::MountTable;
+use switchyard::policy::Policy;
+use switchyard::types::plan::{LinkRequest, PlanInput};
+use switchyard::types::safepath::SafePath;
+use switchyard::Switchyard;
+
+fn setup(root: &std::path::Path) -> PlanInput {
+    let src = root.join("bin/new");
+    let tgt = root.join("usr/bin/app");
+    std::fs::create_dir_all(src.parent().unwrap()).unwrap();
+    std::fs::create_dir_all(tgt.parent().unwrap()).unwrap();
+    std::fs::write(&src, b"new").unwrap();
+    std::fs::write(&tgt, b"old").unwrap();
+    PlanInput {
+        link: vec![LinkRequest {
+            source: SafePath::from_rooted(root, &src).unwrap(),
+            target: SafePath::from_rooted(root, &tgt).unwrap(),
+        }],
+        restore: vec![],
+    }
+}
+
+fn api() -> Switchyard<switchyard::logging::JsonlSink, switchyard::logging::JsonlSink> {
+    let mut policy = Policy::default();
+    policy.risks.source_trust = switchyard::policy::types::SourceTrustPolicy::AllowUntrusted;
+    Switchyard::new(
+        switchyard::logging::JsonlSink,
+        switchyard::logging::JsonlSink,
+        policy,
+    )
+}
+
+#[test]
+fn rows_carry_mount_of_target() {
+    let Ok(table) = MountTable::read() else {
+        eprintln!("skipping: /proc/self/mountinfo unavailable");
+        return;
+    };
+    let td = tempfile::tempdir().unwrap();
+    let api = api();
+    let report = api.preflight(&api.plan(setup(td.path()))).unwrap();
+    let expected = table.find(&td.path().join("usr/bin/app")).unwrap();
+
+    let m = &report.rows[0]["mount"];
+    assert_eq!(m["fs_type"], expected.fs_type.as_str());
+    assert_eq!(m["mount_id"], expected.mount_id);
+    assert_eq!(m["no_suid"], expected.flags.no_suid);
+    assert_eq!(m["backup_same_dev"], true);
+    assert!(!report.warnings.iter().any(|w| w.contains("separate mount")));
+}
+
+#[test]
+fn setuid_source_on_nosuid_mount_warns() {
+    let table = MountTable::read().unwrap_or_default();
+    let uid = rustix::process::getuid().as_raw();
+    let Some(dir) = [
+        "/dev/shm".to_string(),
+        format!("/run/user/{uid}"),
+        "/tmp".to_string(),
+    ]
+    .into_iter()
+    .map(std::path::PathBuf::from)
+    .find(|d| {
+        d.is_dir()
+            && table
+                .find(&d.join("x"))
+                .is_some_and(|m| m.flags.no_suid && !m.flags.read_only)
+    }) else {
+        eprintln!("skipping: no writable nosuid mount found");
+        return;
+    };
+    let Ok(td) = tempfile::tempdir_in(&dir) else {
+        eprintln!("skipping: {} not writable", dir.display());
+        return;
+    };
+    let input = setup(td.path());
+    std::fs::set_permissions(
+        td.path().join("bin/new"),
+        std::fs::Permissions::from_mode(0o4755),
+    )
+    .unwrap();
+    let api = api();
+    let report = api.preflight(&api.plan(input)).unwrap();
+    assert!(
+        report.warnings.iter().any(|w| w.contains("nosuid mount")),
+        "warnings: {:?}",
+        report.warnings
+    );
+    assert_eq!(report.rows[0]["mount"]["no_suid"], true);
+}
@@ -0,0 +1,133 @@
+use std::os::unix::fs::PermissionsExt;
+use switchyard::logging::JsonlSink;
+use switchyard::policy::rescue::verify_rescue_for_plan;
+use switchyard::policy::types::RescueProfile;
+use switchyard::policy::Policy;
+use switchyard::types::plan::{LinkRequest, PlanInput};
+use switchyard::types::safepath::SafePath;
+use switchyard::types::RescueStatus;
+
+const TOOLS: [&str; 6] = ["cp", "mv", "rm", "ln", "stat", "ls"];
+
+fn exe(path: &std::path::Path, body: &str) {
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, body).unwrap();
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755)).unwrap();
+}
+
+/// A plan that replaces `<root>/usr/bin/cp` with a symlink to `<root>/opt/uu/cp`.
+fn plan_input(root: &std::path::Path) -> PlanInput {
+    let src = root.join("opt/uu/cp");
+    let tgt = root.join("usr/bin/cp");
+    exe(&src, "new");
+    PlanInput {
+        link: vec![LinkRequest {
+            source: SafePath::from_rooted(root, &src).unwrap(),
+            target: SafePath::from_rooted(root, &tgt).unwrap(),
+        }],
+        restore: vec![],
+    }
+}
+
+fn policy(profiles: Vec<RescueProfile>) -> Policy {
+    let mut p = Policy::default();
+    p.risks.source_trust = switchyard::policy::types::SourceTrustPolicy::AllowUntrusted;
+    p.rescue.require = true;
+    p.rescue.exec_check = true;
+    p.rescue_profiles = profiles;
+    p
+}
+
+#[test]
+fn named_profile_excludes_tools_the_plan_replaces() {
+    let td = tempfile::tempdir().unwrap();
+    let root = td.path();
+    let paths: Vec<_> = TOOLS.iter().map(|t| root.join("usr/bin").join(t)).collect();
+    for p in &paths {
+        exe(p, "old");
+    }
+    let profile = RescueProfile::Paths {
+        name: "site".to_string(),
+        paths,
+    };
+
+    let api = switchyard::Switchyard::new(JsonlSink, JsonlSink, policy(vec![profile.clone()]));
+    let plan = api.plan(plan_input(root));
+    let report = api.preflight(&plan).unwrap();
+    assert!(report
+        .stops
+        .iter()
+        .any(|s| s == "rescue profile unavailable"));
+    assert!(
+        report
+            .warnings
+            .iter()
+            .any(|w| w.starts_with("rescue tool excluded: cp at ")),
+        "warnings: {:?}",
+        report.warnings
+    );
+
+    let p = policy(vec![profile]);
+    let check = verify_rescue_for_plan(&p.rescue, &p.rescue_profiles, &plan, None);
+    assert_eq!(check.excluded.len(), 1);
+    assert_eq!(check.excluded[0].tool, "cp");
+    assert_eq!(check.excluded[0].path, root.join("usr/bin/cp"));
+}
+
+#[test]
+fn static_busybox_profile_verifies_applets() {
+    let td = tempfile::tempdir().unwrap();
+    let root = td.path();
+    exe(&root.join("usr/bin/cp"), "old");
+    let bb = root.join("rescue/busybox");
+    exe(
+        &bb,
+        &format!("#!/bin/sh\nprintf '%s\\n' {}\n", TOOLS.join(" ")),
+    );
+    let thin = root.join("rescue/busybox-thin");
+    exe(&thin, "#!/bin/sh\necho sh\n");
+
+    let plan =
+        switchyard::Switchyard::new(JsonlSink, JsonlSink, Policy::default()).plan(plan_input(root));
+    let p = policy(vec![
+        RescueProfile::BusyBox(thin.clone()),
+        RescueProfile::BusyBox(bb.clone()),
+    ]);
+    let check = verify_rescue_for_plan(&p.rescue, &p.rescue_profiles, &plan, None);
+    assert_eq!(
+        check.status,
+        Ok(RescueStatus::BusyBox {
+            path: bb.display().to_string()
+        })
+    );
+    assert!(check.excluded.is_empty());
+
+    let only_thin = policy(vec![RescueProfile::BusyBox(thin)]);
+    assert!(
+        verify_rescue_for_plan(&only_thin.rescue, &only_thin.rescue_profiles, &plan, None)
+            .status
+            .is_err()
+    );
+}
+
+#[test]
+fn tool_excluded_by_several_profiles_is_reported_once() {
+    let td = tempfile::tempdir().unwrap();
+    let root = td.path();
+    exe(&root.join("usr/bin/cp"), "old");
+    let plan =
+        switchyard::Switchyard::new(JsonlSink, JsonlSink, Policy::default()).plan(plan_input(root));
+    let named = |name: &str, rel: &str| RescueProfile::Paths {
+        name: name.to_string(),
+        paths: vec![root.join(rel)],
+    };
+    // The target, then the source, then the target again: duplicates are not adjacent.
+    let p = policy(vec![
+        named("a", "usr/bin/cp"),
+        named("b", "opt/uu/cp"),
+        named("c", "usr/bin/cp"),
+    ]);
+    let check = verify_rescue_for_plan(&p.rescue, &p.rescue_profiles, &plan, None);
+    let paths: Vec<_> = check.excluded.iter().map(|e| e.path.clone()).collect();
+    assert_eq!(paths, vec![root.join("opt/uu/cp"), root.join("usr/bin/cp")]);
+}
@@ -0,0 +1,114 @@
+use serde_json::Value;
+use switchyard::logging::{FactsEmitter, JsonlSink};
+use switchyard::policy::Policy;
+use switchyard::types::plan::{LinkRequest, PlanInput};
+use switchyard::types::safepath::SafePath;
+use switchyard::types::ApplyMode;
+
+type Captured = std::sync::Arc<std::sync::Mutex<Vec<(String, String, String, Value)>>>;
+
+#[derive(Default, Clone, Debug)]
+struct TestEmitter {
+    events: Captured,
+}
+impl FactsEmitter for TestEmitter {
+    fn emit(&self, subsystem: &str, event: &str, decision: &str, fields: Value) {
+        self.events.lock().unwrap().push((
+            subsystem.to_string(),
+            event.to_string(),
+            decision.to_string(),
+            fields,
+        ));
+    }
+}
+
+fn setup(root: &std::path::Path) -> PlanInput {
+    let src = root.join("bin/new");
+    let tgt = root.join("usr/bin/app");
+    std::fs::create_dir_all(src.parent().unwrap()).unwrap();
+    std::fs::create_dir_all(tgt.parent().unwrap()).unwrap();
+    std::fs::write(&src, b"new").unwrap();
+    std::fs::write(&tgt, vec![0u8; 10_000]).unwrap();
+    PlanInput {
+        link: vec![LinkRequest {
+            source: SafePath::from_rooted(root, &src).unwrap(),
+            target: SafePath::from_rooted(root, &tgt).unwrap(),
+        }],
+        restore: vec![],
+    }
+}
+
+fn api(margin: u32) -> (switchyard::Switchyard<TestEmitter, JsonlSink>, Captured) {
+    let facts = TestEmitter::default();
+    let events = facts.events.clone();
+    let mut policy = Policy::default();
+    policy.risks.source_trust = switchyard::policy::types::SourceTrustPolicy::AllowUntrusted;
+    policy.governance.allow_unlocked_commit = true;
+    policy.durability.space_margin_percent = margin;
+    (
+        switchyard::Switchyard::new(facts, JsonlSink, policy),
+        events,
+    )
+}
+
+fn summary(events: &Captured) -> Value {
+    events
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|(_, _, _, f)| f["stage"] == "preflight.summary")
+        .map(|(_, _, _, f)| f.clone())
+        .unwrap()
+}
+
+#[test]
+fn summary_reports_backup_estimate() {
+    let td = tempfile::tempdir().unwrap();
+    let (api, events) = api(10);
+    let report = api.preflight(&api.plan(setup(td.path()))).unwrap();
+    assert!(report.ok, "stops: {:?}", report.stops);
+
+    let budget = &summary(&events)["space_budget"];
+    assert_eq!(budget["margin_percent"], 10);
+    let fs = &budget["filesystems"][0];
+    // Payload rounded up to whole fragments plus one fragment for the sidecar.
+    assert!(fs["need_bytes"].as_u64().unwrap() > 10_000);
+    assert_eq!(fs["need_inodes"], 2);
+    assert!(fs["required_bytes"].as_u64() >= fs["need_bytes"].as_u64());
+    assert_eq!(fs["ok"], true);
+}
+
+#[test]
+fn shortfall_stops_with_dedicated_error_id() {
+    let td = tempfile::tempdir().unwrap();
+    // A margin no filesystem can satisfy.
+    let (api, events) = api(u32::MAX);
+    let plan = api.plan(setup(td.path()));
+    let report = api.preflight(&plan).unwrap();
+    assert!(!report.ok);
+    assert!(
+        report
+            .stops
+            .iter()
+            .any(|s| s.starts_with("insufficient space for backups")),
+        "stops: {:?}",
+        report.stops
+    );
+    let s = summary(&events);
+    assert_eq!(s["error_id"], "E_NO_SPACE");
+    assert_eq!(s["exit_code"], 90);
+    assert_eq!(s["space_budget"]["filesystems"][0]["ok"], false);
+
+    // Apply enforces the same gate and leaves the target untouched.
+    let rep = api.apply(&plan, ApplyMode::Commit).unwrap();
+    assert!(rep.executed.is_empty());
+    assert!(rep
+        .errors
+        .iter()
+        .any(|e| e.starts_with("insufficient space for backups")));
+    assert!(events
+        .lock()
+        .unwrap()
+        .iter()
+        .any(|(_, _, _, f)| f["stage"] == "apply.result" && f["error_id"] == "E_NO_SPACE"));
+}
@@ -10,6 +10,7 @@ mod dry_run_facts_identical;
 mod idempotent_rollback;
 mod lockmanager_required_production;
 mod no_broken_path_visible;
+mod package_db_ownership;
 mod partial_restoration_facts;
 mod preflight_yaml_dry_run_parity;
 mod preservation_capability_gating;
@@ -0,0 +1,165 @@
+//! Package-manager ownership oracles populate `pkg` from on-disk databases under the root.
+
+use serde_json::Value;
+use switchyard::adapters::{
+    DpkgOwnershipOracle, OwnershipOracle, PacmanOwnershipOracle, RpmOwnershipOracle,
+};
+use switchyard::logging::{FactsEmitter, JsonlSink};
+use switchyard::policy::Policy;
+use switchyard::types::plan::{LinkRequest, PlanInput};
+use switchyard::types::safepath::SafePath;
+use switchyard::types::ApplyMode;
+
+type Captured = std::sync::Arc<std::sync::Mutex<Vec<(String, String, String, Value)>>>;
+
+#[derive(Default, Clone, Debug)]
+struct TestEmitter {
+    events: Captured,
+}
+
+impl FactsEmitter for TestEmitter {
+    fn emit(&self, subsystem: &str, event: &str, decision: &str, fields: Value) {
+        self.events.lock().unwrap().push((
+            subsystem.to_string(),
+            event.to_string(),
+            decision.to_string(),
+            fields,
+        ));
+    }
+}
+
+fn write(root: &std::path::Path, rel: &str, body: &str) {
+    let p = root.join(rel);
+    std::fs::create_dir_all(p.parent().unwrap()).unwrap();
+    std::fs::write(p, body).unwrap();
+}
+
+fn plan_input(root: &std::path::Path, target: &str) -> PlanInput {
+    let src = root.join("opt/new/tool");
+    let tgt = root.join(target);
+    write(root, "opt/new/tool", "new");
+    write(root, target, "old");
+    PlanInput {
+        link: vec![LinkRequest {
+            source: SafePath::from_rooted(root, &src).unwrap(),
+            target: SafePath::from_rooted(root, &tgt).unwrap(),
+        }],
+        restore: vec![],
+    }
+}
+
+#[test]
+fn dpkg_owner_flows_into_preflight_and_apply_provenance() {
+    let td = tempfile::tempdir().unwrap();
+    let root = td.path();
+    write(
+        root,
+        "var/lib/dpkg/info/coreutils.list",
+        "/.\n/usr\n/usr/bin\n/usr/bin/ls\n",
+    );
+    write(
+        root,
+        "var/lib/dpkg/info/libc6:amd64.list",
+        "/usr/lib/libc.so.6\n",
+    );
+
+    let facts = TestEmitter::default();
+    let mut policy = Policy::default();
+    policy.risks.source_trust = switchyard::policy::types::SourceTrustPolicy::AllowUntrusted;
+    policy.risks.ownership_strict = true;
+    policy.governance.allow_unlocked_commit = true;
+    let api = switchyard::Switchyard::new(facts.clone(), JsonlSink, policy)
+        .with_ownership_oracle(Box::new(DpkgOwnershipOracle::new()));
+
+    let plan = api.plan(plan_input(root, "usr/bin/ls"));
+    let report = api.preflight(&plan).unwrap();
+    assert!(report.ok, "stops: {:?}", report.stops);
+    assert_eq!(report.rows[0]["provenance"]["pkg"], "coreutils");
+
+    let rep = api.apply(&plan, ApplyMode::Commit).unwrap();
+    assert!(rep.errors.is_empty(), "{:?}", rep.errors);
+    let evs = facts.events.lock().unwrap();
+    assert!(evs.iter().any(|(_, _, _, f)| f["stage"] == "apply.result"
+        && f["path"]
+            .as_str()
+            .is_some_and(|p| p.ends_with("usr/bin/ls"))
+        && f["provenance"]["pkg"] == "coreutils"));
+}
+
+#[test]
+fn unowned_target_stops_under_strict_ownership() {
+    let td = tempfile::tempdir().unwrap();
+    let root = td.path();
+    write(root, "var/lib/dpkg/info/coreutils.list", "/usr/bin/ls\n");
+
+    let mut policy = Policy::default();
+    policy.risks.source_trust = switchyard::policy::types::SourceTrustPolicy::AllowUntrusted;
+    policy.risks.ownership_strict = true;
+    let api = switchyard::Switchyard::new(JsonlSink, JsonlSink, policy)
+        .with_ownership_oracle(Box::new(DpkgOwnershipOracle::new()));
+    let report = api
+        .preflight(&api.plan(plan_input(root, "usr/bin/stray")))
+        .unwrap();
+    assert!(
+        report
+            .stops
+            .iter()
+            .any(|s| s.contains("not owned by any dpkg package")),
+        "stops: {:?}",
+        report.stops
+    );
+}
+
+#[test]
+fn pacman_database_and_merged_usr_alias() {
+    let td = tempfile::tempdir().unwrap();
+    let root = td.path();
+    write(
+        root,
+        "var/lib/pacman/local/coreutils-9.4-3/desc",
+        "%NAME%\ncoreutils\n\n%VERSION%\n9.4-3\n",
+    );
+    write(
+        root,
+        "var/lib/pacman/local/coreutils-9.4-3/files",
+        "%FILES%\nbin/\nbin/ls\n\n%BACKUP%\n",
+    );
+    write(root, "usr/bin/ls", "old");
+    std::os::unix::fs::symlink("usr/bin", root.join("bin")).unwrap();
+
+    let oracle = PacmanOwnershipOracle::new();
+    let info = oracle
+        .owner_of(&SafePath::from_rooted(root, &root.join("usr/bin/ls")).unwrap())
+        .unwrap();
+    assert_eq!(info.pkg, "coreutils");
+}
+
+#[test]
+fn rpm_without_database_is_an_error() {
+    let td = tempfile::tempdir().unwrap();
+    write(td.path(), "usr/bin/ls", "old");
+    let oracle = RpmOwnershipOracle::new();
+    let sp = SafePath::from_rooted(td.path(), &td.path().join("usr/bin/ls")).unwrap();
+    assert!(oracle.owner_of(&sp).is_err());
+}
+
+#[test]
+fn long_lived_oracle_sees_database_changes() {
+    let td = tempfile::tempdir().unwrap();
+    let root = td.path();
+    write(root, "var/lib/dpkg/status", "Package: coreutils\n");
+    write(root, "var/lib/dpkg/info/coreutils.list", "/usr/bin/ls\n");
+    write(root, "usr/bin/tool", "new");
+    let sp = SafePath::from_rooted(root, &root.join("usr/bin/tool")).unwrap();
+
+    let oracle = DpkgOwnershipOracle::new();
+    assert!(oracle.owner_of(&sp).is_err());
+    // A package operation adds a file list and rewrites the status file.
+    write(root, "var/lib/dpkg/info/tool.list", "/usr/bin/tool\n");
+    write(
+        root,
+        "var/lib/dpkg/status",
+        "Package: coreutils\n\nPackage: tool\n",
+    );
+    assert_eq!(oracle.owner_of(&sp).unwrap().pkg, "tool");
+}
@@ -197,7 +197,7 @@ pub async fn when_apply_plan_commit(world: &mut World) {
                         &self,
                         _plan: &switchyard::types::plan::Plan,
                     ) -> Result<(), switchyard::adapters::smoke::SmokeFailure> {
-                        Err(switchyard::adapters::smoke::SmokeFailure)
+                        Err(switchyard::adapters::smoke::SmokeFailure::default())
                     }
                 }
                 builder = builder.with_smoke_runner(Box::new(Failing));
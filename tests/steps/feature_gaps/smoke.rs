@@ -17,7 +17,7 @@ pub async fn given_failing_smoke_runner(world: &mut World) {
             &self,
             _plan: &switchyard::types::plan::Plan,
         ) -> Result<(), switchyard::adapters::smoke::SmokeFailure> {
-            Err(switchyard::adapters::smoke::SmokeFailure)
+            Err(switchyard::adapters::smoke::SmokeFailure::default())
         }
     }
     let api = Switchyard::builder(
@@ -141,7 +141,7 @@ pub async fn given_auto_rollback_enabled(world: &mut World) {
                         &self,
                         _plan: &switchyard::types::plan::Plan,
                     ) -> Result<(), switchyard::adapters::smoke::SmokeFailure> {
-                        Err(switchyard::adapters::smoke::SmokeFailure)
+                        Err(switchyard::adapters::smoke::SmokeFailure::default())
                     }
                 }
                 builder = builder.with_smoke_runner(Box::new(Failing));
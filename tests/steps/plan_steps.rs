@@ -52,7 +52,7 @@ pub async fn given_exdev_env(world: &mut World) {
                         &self,
                         _plan: &switchyard::types::plan::Plan,
                     ) -> Result<(), switchyard::adapters::smoke::SmokeFailure> {
-                        Err(switchyard::adapters::smoke::SmokeFailure)
+                        Err(switchyard::adapters::smoke::SmokeFailure::default())
                     }
                 }
                 builder = builder.with_smoke_runner(Box::new(Failing));